@@ -1,3 +1,4 @@
+pub mod modules;
 pub mod parser;
 pub mod tokenizer;
 pub mod transpiler;
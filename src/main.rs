@@ -1,10 +1,8 @@
-use transpiler::{TypeError, transpiler::BeatriceTranspiler};
+use std::path::Path;
 
-mod parser;
-mod tokenizer;
-mod transpiler;
+use beatrice::modules::{ModuleError, ModuleGraph};
 
-fn transpile_file() -> Result<(), TypeError> {
+fn transpile_file() -> Result<(), ModuleError> {
     println!("What's the name of the file at 'templates' folder? ");
     let mut file_name = String::new();
 
@@ -13,12 +11,23 @@ fn transpile_file() -> Result<(), TypeError> {
 
     file_name.split_off(file_name.len() - 1).truncate(0);
 
-    let file_content = std::fs::read_to_string(format!("./templates/{file_name}",)).unwrap();
-
-    let mut parser = parser::Parser::from_content(&file_content);
-    let ast = parser.gen_ast();
-    let mut transpiler = BeatriceTranspiler::new(format!("./out/{file_name}.out.js"));
-    transpiler.start_transpilation(ast.unwrap().body())?;
+    let mut graph = ModuleGraph::load(format!("./templates/{file_name}"))?;
+    //with --bigint, ints are exact past 2^53
+    graph.set_bigint(std::env::args().any(|arg| arg == "--bigint"));
+    let root = Path::new("./templates").canonicalize().unwrap();
+    //every module is written at the same place relative to ./out as its source is to ./templates,
+    //so the paths of the imports stay the same
+    for (module, content) in graph.modules().iter().zip(graph.transpile()?) {
+        let relative = module.path.strip_prefix(&root).unwrap_or(&module.path);
+        let target = Path::new("./out").join(relative).with_extension("js");
+        println!("Writing into {target:?}:\n\n{content}");
+        let write = std::fs::create_dir_all(target.parent().unwrap())
+            .and_then(|_| std::fs::write(&target, content));
+        write.map_err(|error| ModuleError::Io {
+            path: target,
+            error,
+        })?;
+    }
     Ok(())
 }
 
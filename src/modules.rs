@@ -0,0 +1,152 @@
+//This file loads a program spread across many files. Every file is a module with its own global
+//scope, which only sees the names it imports from other modules.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    parser::{AST, AstError, Parser, Program},
+    transpiler::{Diagnostic, transpiler::BeatriceTranspiler},
+};
+
+#[derive(Debug)]
+pub enum ModuleError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        errors: Vec<AstError>,
+    },
+    Type {
+        path: PathBuf,
+        errors: Vec<Diagnostic>,
+    },
+    ///Modules importing each other. The first module of the cycle is repeated at its end
+    Cycle(Vec<PathBuf>),
+}
+
+///A parsed source file, with the files its imports resolved to
+pub struct Module {
+    pub path: PathBuf,
+    pub program: Program,
+    ///The file each import path written in the module points to
+    pub imports: HashMap<String, PathBuf>,
+}
+
+///Every module reachable from an entry one, ordered so each module comes after the ones it imports
+pub struct ModuleGraph {
+    modules: Vec<Module>,
+    ///Whether the modules are transpiled with int, i64 and u64 as js bigints
+    bigint: bool,
+}
+
+///Resolves an import path relative to the module importing it. Paths without an extension point to
+///a `.bt` file
+pub fn resolve_import(importer: &Path, path: &str) -> PathBuf {
+    let mut resolved = importer.parent().unwrap_or(Path::new("")).join(path);
+    if resolved.extension().is_none() {
+        resolved.set_extension("bt");
+    }
+    resolved
+}
+
+impl ModuleGraph {
+    ///Loads the module at entry and every module it imports, directly or not
+    pub fn load<T: AsRef<Path>>(entry: T) -> Result<Self, ModuleError> {
+        let mut graph = Self {
+            modules: Vec::new(),
+            bigint: false,
+        };
+        graph.visit(entry.as_ref().to_path_buf(), &mut Vec::new())?;
+        Ok(graph)
+    }
+
+    ///Loads the module at path after the ones it imports. stack holds the modules being loaded,
+    ///so finding one of them again means they import each other
+    fn visit(&mut self, path: PathBuf, stack: &mut Vec<PathBuf>) -> Result<(), ModuleError> {
+        let path = path.canonicalize().map_err(|error| ModuleError::Io {
+            path: path.clone(),
+            error,
+        })?;
+        if let Some(idx) = stack.iter().position(|p| *p == path) {
+            let mut cycle = stack.split_off(idx);
+            cycle.push(path);
+            return Err(ModuleError::Cycle(cycle));
+        }
+        if self.modules.iter().any(|m| m.path == path) {
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(&path).map_err(|error| ModuleError::Io {
+            path: path.clone(),
+            error,
+        })?;
+        let program =
+            Parser::from_content(&content)
+                .gen_ast()
+                .map_err(|errors| ModuleError::Parse {
+                    path: path.clone(),
+                    errors,
+                })?;
+
+        stack.push(path.clone());
+        let mut imports = HashMap::new();
+        for ast in program.body() {
+            if let AST::Import { path: import, .. } = ast {
+                let resolved = resolve_import(&path, import);
+                self.visit(resolved.clone(), stack)?;
+                let resolved = resolved.canonicalize().unwrap();
+                imports.insert(import.clone(), resolved);
+            }
+        }
+        stack.pop();
+        self.modules.push(Module {
+            path,
+            program,
+            imports,
+        });
+        Ok(())
+    }
+
+    ///Makes every module be transpiled with int, i64 and u64 as js bigints
+    pub fn set_bigint(&mut self, bigint: bool) {
+        self.bigint = bigint;
+    }
+
+    pub fn modules(&self) -> &[Module] {
+        &self.modules
+    }
+
+    ///Checks every module and generates its ES module, in the same order as the modules
+    pub fn transpile(&self) -> Result<Vec<String>, ModuleError> {
+        let mut exports = HashMap::with_capacity(self.modules.len());
+        let mut out = Vec::with_capacity(self.modules.len());
+        let mut next_struct_id = 0;
+        for module in &self.modules {
+            let mut transpiler = BeatriceTranspiler::new(module.path.with_extension("js"));
+            transpiler.set_bigint(self.bigint);
+            transpiler.set_next_struct_id(next_struct_id);
+            for (import, path) in &module.imports {
+                transpiler.define_module(import.clone(), Rc::clone(&exports[path]));
+            }
+            let content =
+                transpiler
+                    .generate(&module.program)
+                    .map_err(|errors| ModuleError::Type {
+                        path: module.path.clone(),
+                        errors,
+                    })?;
+            exports.insert(
+                module.path.clone(),
+                Rc::new(transpiler.exports(module.program.body())),
+            );
+            next_struct_id = transpiler.next_struct_id();
+            out.push(content);
+        }
+        Ok(out)
+    }
+}
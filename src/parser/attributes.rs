@@ -0,0 +1,147 @@
+use std::{collections::VecDeque, fmt::Display};
+
+use crate::expect;
+
+use super::{AstError, AstErrorKind, Attribute, Parser, ParsingCondition, Token, TokenKind};
+
+///The kind of declaration or statement an attribute is written on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeTarget {
+    Function,
+    Struct,
+    Enum,
+    Field,
+    Impl,
+    Statement,
+}
+
+///The attributes the compiler knows, with what each one can be written on
+pub const KNOWN_ATTRIBUTES: &[(&str, &[AttributeTarget])] = &[
+    ("Memorized", &[AttributeTarget::Struct]),
+    (
+        "Inline",
+        &[AttributeTarget::Function, AttributeTarget::Statement],
+    ),
+    (
+        "Deprecated",
+        &[
+            AttributeTarget::Function,
+            AttributeTarget::Struct,
+            AttributeTarget::Enum,
+            AttributeTarget::Field,
+            AttributeTarget::Impl,
+        ],
+    ),
+    ("Test", &[AttributeTarget::Function]),
+    ("Display", &[AttributeTarget::Struct]),
+];
+
+impl Display for AttributeTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Function => "function",
+            Self::Struct => "struct",
+            Self::Enum => "enum",
+            Self::Field => "field",
+            Self::Impl => "impl block",
+            Self::Statement => "statement",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl Attribute {
+    ///Whether the attribute is the one with the given name
+    pub fn is(&self, name: &str) -> bool {
+        self.name == name
+    }
+}
+
+impl Parser {
+    ///Parses an attribute such as @Inline or @Deprecated(2). The given token is its '@'
+    fn parse_attribute(&mut self, at: Token) -> Result<Attribute, AstError> {
+        let Token {
+            kind: TokenKind::Identifier(name),
+            ..
+        } = expect!(self, TokenKind::Identifier(_))?
+        else {
+            unreachable!();
+        };
+        let mut args = VecDeque::new();
+        if let Some(Token {
+            kind: TokenKind::OpenParen,
+            ..
+        }) = self.peek()
+        {
+            self.eat()?;
+            loop {
+                let tk = self.eat()?;
+                if let TokenKind::CloseParen = tk.kind {
+                    break;
+                }
+                args.push_back(self.parse_expr(tk, ParsingCondition::PrimitiveExpr)?);
+                if let TokenKind::CloseParen =
+                    expect!(self, TokenKind::Comma | TokenKind::CloseParen)?.kind
+                {
+                    break;
+                }
+            }
+        }
+        Ok(Attribute {
+            name,
+            args,
+            line: at.line,
+            column: at.column,
+        })
+    }
+    ///Eats the attributes written before the next declaration, if any
+    pub fn parse_optional_attributes(&mut self) -> Result<Vec<Attribute>, AstError> {
+        let mut attributes = Vec::new();
+        while let Some(Token {
+            kind: TokenKind::At,
+            ..
+        }) = self.peek()
+        {
+            let at = self.eat()?;
+            attributes.push(self.parse_attribute(at)?);
+        }
+        Ok(attributes)
+    }
+    ///Parses the attributes starting at the given '@' token, returning them along the token that
+    ///follows them
+    pub fn parse_leading_attributes(
+        &mut self,
+        at: Token,
+    ) -> Result<(Vec<Attribute>, Token), AstError> {
+        let mut attributes = vec![self.parse_attribute(at)?];
+        attributes.extend(self.parse_optional_attributes()?);
+        Ok((attributes, self.eat()?))
+    }
+    ///Checks that every attribute is known and can be written on target
+    pub fn check_attributes(
+        attributes: &[Attribute],
+        target: AttributeTarget,
+    ) -> Result<(), AstError> {
+        for attribute in attributes {
+            let Some((_, targets)) = KNOWN_ATTRIBUTES.iter().find(|(name, _)| attribute.is(name))
+            else {
+                return Err(AstError {
+                    line: attribute.line,
+                    column: attribute.column,
+                    kind: AstErrorKind::UnknownAttribute(attribute.name.clone()),
+                });
+            };
+            if !targets.contains(&target) {
+                return Err(AstError {
+                    line: attribute.line,
+                    column: attribute.column,
+                    kind: AstErrorKind::MisplacedAttribute {
+                        name: attribute.name.clone(),
+                        target,
+                    },
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -2,7 +2,10 @@
 
 use std::collections::VecDeque;
 
-use super::{AST, AstError, AstErrorKind, AstResult, Parser, ParsingCondition};
+use super::{
+    AST, AstError, AstErrorKind, AstResult, AttributeTarget, Parser, ParsingCondition, Program,
+    Span,
+};
 use crate::{
     expect,
     parser::Operator,
@@ -10,59 +13,102 @@ use crate::{
 };
 
 impl Parser {
-    pub fn parse_int(integer: String) -> AST {
-        let integer_bytes = integer.as_bytes();
-        if integer.len() > 2 && integer_bytes[0] == b'0' {
-            let n = i64::from_str_radix(
-                &integer,
-                match integer_bytes[1] {
-                    b'x' | b'X' => 16,
-                    b'B' | b'b' => 8,
-                    _ => 10,
-                },
-            )
-            .unwrap();
-            AST::Int(n)
-        } else {
-            AST::Int(integer.parse().unwrap())
+    ///Reads the value of an int literal, such as 42, 0x2A or 0b101010. None when it doesn't fit
+    ///in an u64, the widest integer type
+    pub fn parse_int(integer: &str) -> Option<u64> {
+        match integer.get(..2) {
+            Some("0x" | "0X") => u64::from_str_radix(&integer[2..], 16).ok(),
+            Some("0b") => u64::from_str_radix(&integer[2..], 2).ok(),
+            _ => integer.parse().ok(),
         }
     }
+    ///Parses an int literal token, failing when its value doesn't fit in an u64
+    pub(crate) fn parse_int_token(tk: Token) -> Result<u64, AstError> {
+        let TokenKind::Int(integer) = &tk.kind else {
+            panic!("This is a bug. Expected receiving an int token");
+        };
+        Self::parse_int(integer).ok_or_else(|| AstError {
+            line: tk.line,
+            column: tk.column,
+            kind: AstErrorKind::IntegerTooLarge(integer.clone()),
+        })
+    }
     pub fn parse_identifier(
         &mut self,
         identifier: String,
+        span: Span,
         condition: ParsingCondition,
     ) -> AstResult {
+        let type_args = match self.peek_type_args() {
+            Some(TokenKind::OpenBrace) if condition == ParsingCondition::NoStruct => Vec::new(),
+            Some(_) => {
+                self.eat()?;
+                self.parse_type_args()?
+            }
+            None => Vec::new(),
+        };
+        if identifier == "None" && type_args.is_empty() {
+            return Ok(AST::None);
+        }
+        if let ("true" | "false", true) = (identifier.as_str(), type_args.is_empty()) {
+            return Ok(AST::Bool(identifier == "true"));
+        }
         if let Some(Token {
+            kind: TokenKind::DoubleColon,
+            ..
+        }) = self.peek()
+        {
+            self.eat()?;
+            return self.parse_variant_expr(identifier, type_args, condition);
+        } else if let Some(Token {
             kind: TokenKind::OpenParen,
             ..
         }) = self.peek()
+            && matches!(identifier.as_str(), "Some" | "Ok" | "Err")
+            && type_args.is_empty()
         {
             self.eat()?;
-            return self.parse_function_call(identifier);
+            let tk = self.eat()?;
+            let value = Box::new(self.parse_expr(tk, ParsingCondition::None)?);
+            expect!(self, TokenKind::CloseParen)?;
+            return Ok(match identifier.as_str() {
+                "Some" => AST::Some(value),
+                "Ok" => AST::Ok(value),
+                _ => AST::Err(value),
+            });
+        } else if let Some(Token {
+            kind: TokenKind::OpenParen,
+            ..
+        }) = self.peek()
+        {
+            self.eat()?;
+            return self.parse_function_call(identifier, type_args, span);
         } else if let Some(Token {
             kind: TokenKind::OpenBrace,
             ..
         }) = self.peek()
+            && condition != ParsingCondition::NoStruct
         {
-            if condition != ParsingCondition::NoStruct {
-                self.eat()?;
-                return self.parse_struct_expr(identifier);
-            }
+            self.eat()?;
+            return self.parse_struct_expr(identifier, type_args);
         };
         Ok(AST::Identifier(identifier))
     }
     pub fn parse_primary(&mut self, tk: Token, condition: ParsingCondition) -> AstResult {
         match tk.kind {
-            TokenKind::Int(s) => Ok(Self::parse_int(s)),
+            TokenKind::Int(_) => Ok(AST::Int(Self::parse_int_token(tk)?)),
             TokenKind::Float(f) => Ok(AST::Float(f.parse().unwrap())),
 
-            TokenKind::Identifier(s) => self.parse_identifier(s, condition),
-            TokenKind::OpenParen => {
-                let next = self.eat()?;
-                let val = self.parse_expr(next, ParsingCondition::None)?;
-                expect!(self, TokenKind::CloseParen)?;
-                Ok(val)
-            }
+            TokenKind::Identifier(s) => self.parse_identifier(
+                s,
+                Span {
+                    line: tk.line,
+                    column: tk.column,
+                },
+                condition,
+            ),
+            TokenKind::OpenParen => self.parse_parenthesized(),
+            TokenKind::Str(s) => Self::parse_string(s, tk.line, tk.column),
             _ => Err(AstError {
                 line: tk.line,
                 column: tk.column,
@@ -70,18 +116,139 @@ impl Parser {
             }),
         }
     }
+    ///Parses what comes after a '(', either a tuple such as (a, b) and (a,) or a single value
+    ///such as (a + b)
+    fn parse_parenthesized(&mut self) -> AstResult {
+        let mut values = VecDeque::new();
+        loop {
+            let next = self.eat()?;
+            values.push_back(self.parse_expr(next, ParsingCondition::None)?);
+            if let TokenKind::CloseParen =
+                expect!(self, TokenKind::Comma | TokenKind::CloseParen)?.kind
+            {
+                return Ok(if values.len() == 1 {
+                    values.pop_front().unwrap()
+                } else {
+                    AST::Tuple(values)
+                });
+            }
+            if let Some(Token {
+                kind: TokenKind::CloseParen,
+                ..
+            }) = self.peek()
+            {
+                self.eat()?;
+                return Ok(AST::Tuple(values));
+            }
+        }
+    }
+    ///Parses the accesses to fields and tuple elements after a value, such as the .0.1 of pair.0.1
+    ///or the ?.x of point?.x, along the `?` of results
+    fn parse_accesses(&mut self, mut expr: AST) -> AstResult {
+        while let Some(Token {
+            kind: kind @ (TokenKind::Dot | TokenKind::QuestionDot | TokenKind::Question),
+            ..
+        }) = self.peek()
+        {
+            if *kind == TokenKind::Question {
+                self.eat()?;
+                expr = AST::Try(Box::new(expr));
+                continue;
+            }
+            let mut optional = *kind == TokenKind::QuestionDot;
+            self.eat()?;
+            let tk = self.eat()?;
+            //pair.0.1 tokenizes its indexes as the float 0.1
+            let indexes = match &tk.kind {
+                TokenKind::Identifier(field) => {
+                    expr = AST::FieldAccess {
+                        expr: Box::new(expr),
+                        field: field.clone(),
+                        optional,
+                    };
+                    continue;
+                }
+                TokenKind::Int(i) => vec![i.parse()],
+                TokenKind::Float(f) => f.split('.').map(|i| i.parse()).collect(),
+                _ => Vec::new(),
+            };
+            if indexes.is_empty() || indexes.iter().any(|i| i.is_err()) {
+                return Err(AstError {
+                    line: tk.line,
+                    column: tk.column,
+                    kind: AstErrorKind::UnexpectedToken(tk),
+                });
+            }
+            for index in indexes {
+                expr = AST::TupleIndex {
+                    expr: Box::new(expr),
+                    index: index.unwrap(),
+                    optional,
+                };
+                optional = false;
+            }
+        }
+        Ok(expr)
+    }
+
+    pub fn parse_postfix(&mut self, tk: Token, condition: ParsingCondition) -> AstResult {
+        let primary = self.parse_primary(tk, condition)?;
+        self.parse_accesses(primary)
+    }
+
+    ///Parses expr as type. It binds tighter than every binary operator, so n as float * 1.5 is
+    ///(n as float) * 1.5, and casts can be chained as in x as int as float. Options can't be cast
+    ///to, so the `?` after x as int is the one of results
+    pub fn parse_cast(&mut self, tk: Token, condition: ParsingCondition) -> AstResult {
+        let mut expr = self.parse_postfix(tk, condition)?;
+        while let Some(Token {
+            kind: TokenKind::Reserved(Reserved::As),
+            ..
+        }) = self.peek()
+        {
+            self.eat()?;
+            expr = AST::Cast {
+                expr: Box::new(expr),
+                kindof: self.get_non_optional_type()?,
+            };
+        }
+        Ok(expr)
+    }
+
+    ///Parses a ** b. It groups from the right, so 2 ** 3 ** 2 is 2 ** (3 ** 2)
+    pub fn parse_power(&mut self, tk: Token, condition: ParsingCondition) -> AstResult {
+        let left = self.parse_cast(tk, condition)?;
+        if let Some(Token {
+            kind: TokenKind::Operator(Operator::Pow(false)),
+            ..
+        }) = self.peek()
+        {
+            self.eat()?;
+            let curr = self.eat()?;
+            let right = self.parse_power(curr, condition)?;
+            return Ok(AST::BinExpr(
+                Box::new(left),
+                Box::new(right),
+                Operator::Pow(false),
+            ));
+        }
+        Ok(left)
+    }
 
     pub fn parse_multiplicative(&mut self, tk: Token, condition: ParsingCondition) -> AstResult {
-        let mut left = self.parse_primary(tk, condition)?;
+        let mut left = self.parse_power(tk, condition)?;
         while let Some(Token {
-            kind: TokenKind::Operator(op @ (Operator::Star(false) | Operator::Slash(false))),
+            kind:
+                TokenKind::Operator(
+                    op @ (Operator::Star(false) | Operator::Slash(false) | Operator::Mod(false)),
+                ),
             ..
         }) = self.peek()
         {
             let op = op.clone();
             self.eat()?;
             let curr = self.eat()?;
-            let right = self.parse_primary(curr, condition)?;
+            let right = self.parse_power(curr, condition)?;
             left = AST::BinExpr(Box::new(left), Box::new(right), op);
         }
         Ok(left)
@@ -101,18 +268,75 @@ impl Parser {
         }
         Ok(left)
     }
+    ///Returns the next token if it is a binary operator binding looser than the additive ones,
+    ///such as comparisons, bitwise and logical operators
+    fn peek_binary_operator(&self) -> Option<Operator> {
+        let Some(Token {
+            kind: TokenKind::Operator(op),
+            ..
+        }) = self.peek()
+        else {
+            return None;
+        };
+        match op {
+            Operator::BitLeft(false)
+            | Operator::BitRight(false)
+            | Operator::BitAnd(false)
+            | Operator::BitOr(false)
+            | Operator::Xor(false)
+            | Operator::Eq(true)
+            | Operator::Bang(true)
+            | Operator::Lt(_)
+            | Operator::Gt(_)
+            | Operator::And(false)
+            | Operator::Or(false)
+            | Operator::Coalesce => Some(op.clone()),
+            _ => None,
+        }
+    }
+    ///Keeps adding operators binding at most as loose as max_precedence to lhs, grouping the
+    ///right side of each one with every operator binding tighter than it
+    fn parse_binary_rhs(
+        &mut self,
+        mut lhs: AST,
+        condition: ParsingCondition,
+        max_precedence: u8,
+    ) -> AstResult {
+        while let Some(op) = self.peek_binary_operator()
+            && op.precedence() <= max_precedence
+        {
+            self.eat()?;
+            let curr = self.eat()?;
+            let mut rhs = self.parse_additive(curr, condition)?;
+            while let Some(next) = self.peek_binary_operator()
+                && next.precedence() < op.precedence()
+            {
+                rhs = self.parse_binary_rhs(rhs, condition, next.precedence())?;
+            }
+            lhs = AST::BinExpr(Box::new(lhs), Box::new(rhs), op);
+        }
+        Ok(lhs)
+    }
+    ///Parses a binary expression of any of the operators, from shifts to `||`
+    pub fn parse_binary(&mut self, tk: Token, condition: ParsingCondition) -> AstResult {
+        let lhs = self.parse_additive(tk, condition)?;
+        self.parse_binary_rhs(lhs, condition, Operator::Or(false).precedence())
+    }
     /// Parses the current expression. The given token is the current token
     pub fn parse_expr(&mut self, tk: Token, condition: ParsingCondition) -> AstResult {
         if let TokenKind::OpenBrace = tk.kind {
             self.parse_block_expr()
         } else if let TokenKind::Reserved(Reserved::If) = tk.kind {
             self.parse_if_expr()
+        } else if let TokenKind::Reserved(Reserved::Match) = tk.kind {
+            self.parse_match_expr()
         } else {
-            self.parse_additive(tk, condition)
+            self.parse_binary(tk, condition)
         }
     }
     pub fn parse_block_expr(&mut self) -> AstResult {
         let mut asts = VecDeque::new();
+        let mut spans = VecDeque::new();
         loop {
             if let Some(Token {
                 kind: TokenKind::CloseBrace,
@@ -123,6 +347,10 @@ impl Parser {
                 break;
             }
             let tk = self.eat()?;
+            spans.push_back(Span {
+                line: tk.line,
+                column: tk.column,
+            });
             asts.push_back(self.parse_statment(tk)?);
             if let Some(Token {
                 kind: TokenKind::CloseBrace,
@@ -135,67 +363,69 @@ impl Parser {
                 expect!(self, TokenKind::SemiColon)?;
             }
         }
-        Ok(AST::Block(asts))
+        Ok(AST::Block(Program { body: asts, spans }))
     }
     /**
      * Parses a basic statment.
      */
     pub fn parse_statment(&mut self, tk: Token) -> AstResult {
         let val = match tk.kind {
-            TokenKind::Reserved(Reserved::Let) => match self.eat()?.kind {
-                //let mut name = ...
-                TokenKind::Reserved(Reserved::Mut) => {
-                    let Token {
-                        kind: TokenKind::Identifier(varname),
-                        ..
-                    } = expect!(self, TokenKind::Identifier(_))?
-                    else {
-                        unreachable!();
-                    };
-                    expect!(self, TokenKind::Operator(Operator::Eq(false)))?; //eats the '='
-                    //operator
-                    let next = self.eat()?;
-                    Ok(AST::VarDecl {
-                        varname,
-                        mutable: true,
-                        body: Box::new(if let TokenKind::Reserved(Reserved::If) = next.kind {
-                            self.parse_if_assign_expr()?
-                        } else {
-                            self.parse_expr(next, ParsingCondition::PrimitiveExpr)?
-                        }),
-                    })
-                }
-                //let name = ...
-                TokenKind::Identifier(varname) => {
-                    expect!(self, TokenKind::Operator(Operator::Eq(false)))?; //eats the '=' operator
-                    let next = self.eat()?;
-
-                    Ok(AST::VarDecl {
-                        varname,
-                        mutable: false,
-                        body: Box::new(if let TokenKind::Reserved(Reserved::If) = next.kind {
-                            self.parse_if_assign_expr()?
-                        } else {
-                            self.parse_expr(next, ParsingCondition::PrimitiveExpr)?
-                        }),
-                    })
-                }
-                _ => Err(AstError {
-                    line: tk.line,
-                    column: tk.column,
-                    kind: AstErrorKind::UnexpectedToken(tk),
-                }),
-            },
-            TokenKind::OpenParen => {
+            //let pattern = ..., let mut pattern = ... or either of them with a type, as in let x: int = ...
+            TokenKind::Reserved(Reserved::Let) => {
+                let mutable = if let Some(Token {
+                    kind: TokenKind::Reserved(Reserved::Mut),
+                    ..
+                }) = self.peek()
+                {
+                    self.eat()?;
+                    true
+                } else {
+                    false
+                };
+                let pattern = self.parse_pattern()?;
+                let kindof = if let Some(Token {
+                    kind: TokenKind::Colon,
+                    ..
+                }) = self.peek()
+                {
+                    self.eat()?;
+                    Some(self.get_type()?)
+                } else {
+                    None
+                };
+                expect!(self, TokenKind::Operator(Operator::Eq(false)))?; //eats the '=' operator
                 let next = self.eat()?;
-                let val = self.parse_expr(next, ParsingCondition::PrimitiveExpr)?;
-                expect!(self, TokenKind::CloseParen)?;
-                Ok(val)
+                Ok(AST::VarDecl {
+                    pattern,
+                    mutable,
+                    kindof,
+                    body: Box::new(if let TokenKind::Reserved(Reserved::If) = next.kind {
+                        self.parse_if_assign_expr()?
+                    } else {
+                        self.parse_expr(next, ParsingCondition::PrimitiveExpr)?
+                    }),
+                })
+            }
+            TokenKind::OpenParen | TokenKind::Identifier(_) => {
+                self.parse_expr(tk, ParsingCondition::None)
+            }
+            TokenKind::Int(_) | TokenKind::Float(_) | TokenKind::Str(_) => {
+                self.parse_expr(tk, ParsingCondition::None)
             }
-            TokenKind::Identifier(_) => self.parse_expr(tk, ParsingCondition::None),
-            TokenKind::Int(_) | TokenKind::Float(_) => self.parse_expr(tk, ParsingCondition::None),
             TokenKind::Reserved(Reserved::If) => self.parse_if_expr(),
+            TokenKind::Reserved(Reserved::Match) => self.parse_match_expr(),
             TokenKind::Reserved(Reserved::Loop) => self.parse_loop_statment(),
+            TokenKind::Reserved(Reserved::Return | Reserved::Break | Reserved::Panic) => {
+                self.parse_jump(tk)
+            }
+            TokenKind::At => {
+                let (attributes, next) = self.parse_leading_attributes(tk)?;
+                Self::check_attributes(&attributes, AttributeTarget::Statement)?;
+                Ok(AST::Attributed {
+                    attributes,
+                    ast: Box::new(self.parse_statment(next)?),
+                })
+            }
             _ => Err(AstError {
                 line: tk.line,
                 column: tk.column,
@@ -206,9 +436,29 @@ impl Parser {
     }
 
     pub fn parse_global_scope(&mut self, token: Token) -> AstResult {
+        if let TokenKind::At = token.kind {
+            let (attributes, next) = self.parse_leading_attributes(token)?;
+            let mut ast = self.parse_global_scope(next)?;
+            let decl = match &mut ast {
+                AST::Export(decl) => &mut **decl,
+                decl => decl,
+            };
+            let (target, ast_attributes) = match decl {
+                AST::Function { attributes, .. } => (AttributeTarget::Function, attributes),
+                AST::Struct { attributes, .. } => (AttributeTarget::Struct, attributes),
+                AST::Enum { attributes, .. } => (AttributeTarget::Enum, attributes),
+                _ => unreachable!(),
+            };
+            Self::check_attributes(&attributes, target)?;
+            ast_attributes.splice(0..0, attributes);
+            return Ok(ast);
+        }
         match &token.kind {
             TokenKind::Reserved(Reserved::Struct) => self.parse_struct_decl(),
+            TokenKind::Reserved(Reserved::Enum) => self.parse_enum_decl(),
             TokenKind::Reserved(Reserved::Function) => self.parse_function(), //does not neet to give the token because the current is 'function' keyword
+            TokenKind::Reserved(Reserved::Import) => self.parse_import(),
+            TokenKind::Reserved(Reserved::Export) => self.parse_export(),
             _ => Err(AstError {
                 kind: AstErrorKind::InvalidScopeExpr(token.kind),
                 line: token.line,
@@ -0,0 +1,94 @@
+use crate::{
+    expect,
+    parser::{
+        AST, AstError, AstErrorKind, AstResult, MatchArm, Operator, Parser, ParsingCondition,
+        Token, TokenKind,
+    },
+    tokenizer::Reserved,
+};
+
+impl Parser {
+    ///Parses a match arm, such as Shape::Circle(r) if r > 0.0 -> r * r
+    fn parse_match_arm(&mut self) -> Result<MatchArm, AstError> {
+        let pattern = self.parse_pattern()?;
+        let guard = if let Some(Token {
+            kind: TokenKind::Reserved(Reserved::If),
+            ..
+        }) = self.peek()
+        {
+            self.eat()?;
+            let current = self.eat()?;
+            Some(self.parse_expr(current, ParsingCondition::NoStruct)?)
+        } else {
+            None
+        };
+        expect!(self, TokenKind::Operator(Operator::Arrow))?;
+        let current = self.eat()?;
+        let body = if let TokenKind::OpenBrace = current.kind {
+            self.parse_block_expr()?
+        } else if let TokenKind::Reserved(Reserved::Return | Reserved::Break | Reserved::Panic) =
+            current.kind
+        {
+            self.parse_jump(current)?
+        } else {
+            self.parse_expr(current, ParsingCondition::None)?
+        };
+        Ok(MatchArm {
+            pattern,
+            guard,
+            body,
+        })
+    }
+    ///Parses a match expression. The arms are separated by ',', which can be left out after an
+    ///arm whose body is a block. Such as
+    ///match shape {
+    ///  Shape::Circle(r) -> r * r,
+    ///  _ -> 0.0,
+    ///}
+    pub fn parse_match_expr(&mut self) -> AstResult {
+        let expr = {
+            let current = self.eat()?;
+            self.parse_expr(current, ParsingCondition::NoStruct)?
+        };
+        expect!(self, TokenKind::OpenBrace)?;
+        let mut arms = Vec::new();
+        loop {
+            if let Some(Token {
+                kind: TokenKind::CloseBrace,
+                ..
+            }) = self.peek()
+            {
+                self.eat()?;
+                break;
+            }
+            let arm = self.parse_match_arm()?;
+            let block_body = arm.body.is_blockexpr();
+            arms.push(arm);
+            match self.peek() {
+                Some(Token {
+                    kind: TokenKind::Comma,
+                    ..
+                }) => {
+                    self.eat()?;
+                }
+                Some(Token {
+                    kind: TokenKind::CloseBrace,
+                    ..
+                }) => {}
+                _ if block_body => {}
+                _ => {
+                    let tk = self.eat()?;
+                    return Err(AstError {
+                        line: tk.line,
+                        column: tk.column,
+                        kind: AstErrorKind::UnexpectedToken(tk),
+                    });
+                }
+            }
+        }
+        Ok(AST::Match {
+            expr: Box::new(expr),
+            arms,
+        })
+    }
+}
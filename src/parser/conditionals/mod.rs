@@ -1 +1,2 @@
 pub mod if_conditional;
+pub mod match_conditional;
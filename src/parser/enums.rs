@@ -0,0 +1,183 @@
+use std::collections::VecDeque;
+
+use crate::expect;
+
+use super::{
+    AST, AstError, AstErrorKind, AttributeTarget, KeyTypePair, Parser, ParsingCondition, Token,
+    TokenKind, TypeAst, VariantArgs, VariantDecl, VariantKind,
+};
+
+impl Parser {
+    ///Parses the fields of a struct-like variant, such as { w: float, h: float }. Fields can be
+    ///separated either by ',' or ';'. The '{' must already be eaten
+    fn parse_variant_fields_types(&mut self) -> Result<VecDeque<KeyTypePair>, AstError> {
+        let mut fields = VecDeque::new();
+        loop {
+            if let Some(Token {
+                kind: TokenKind::CloseBrace,
+                ..
+            }) = self.peek()
+            {
+                self.eat()?;
+                break;
+            }
+            let attributes = self.parse_optional_attributes()?;
+            Self::check_attributes(&attributes, AttributeTarget::Field)?;
+            let Token {
+                kind: TokenKind::Identifier(key),
+                ..
+            } = expect!(self, TokenKind::Identifier(_))?
+            else {
+                unreachable!();
+            };
+            expect!(self, TokenKind::Colon)?;
+            fields.push_back(KeyTypePair {
+                key,
+                kindof: self.get_type()?,
+                attributes,
+            });
+            if let TokenKind::CloseBrace = expect!(
+                self,
+                TokenKind::Comma | TokenKind::SemiColon | TokenKind::CloseBrace
+            )?
+            .kind
+            {
+                break;
+            }
+        }
+        Ok(fields)
+    }
+    ///Parses the types of a tuple variant, such as (float, int). The '(' must already be eaten
+    fn parse_variant_tuple_types(&mut self) -> Result<Vec<TypeAst>, AstError> {
+        let mut types = Vec::new();
+        loop {
+            if let Some(Token {
+                kind: TokenKind::CloseParen,
+                ..
+            }) = self.peek()
+            {
+                self.eat()?;
+                break;
+            }
+            types.push(self.get_type()?);
+            if let TokenKind::CloseParen =
+                expect!(self, TokenKind::Comma | TokenKind::CloseParen)?.kind
+            {
+                break;
+            }
+        }
+        Ok(types)
+    }
+    ///Parses an enum declaration, such as
+    ///enum Shape {
+    ///  Circle(float),
+    ///  Rect { w: float, h: float },
+    ///  Empty,
+    ///}
+    pub fn parse_enum_decl(&mut self) -> Result<AST, AstError> {
+        let Token {
+            kind: TokenKind::Identifier(name),
+            ..
+        } = expect!(self, TokenKind::Identifier(_))?
+        else {
+            unreachable!()
+        };
+        let mut generics = self.parse_optional_generic_params()?;
+        self.parse_where_clause(&mut generics)?;
+        expect!(self, TokenKind::OpenBrace)?;
+        let mut variants = Vec::new();
+        loop {
+            if let Some(Token {
+                kind: TokenKind::CloseBrace,
+                ..
+            }) = self.peek()
+            {
+                self.eat()?;
+                break;
+            }
+            let Token {
+                kind: TokenKind::Identifier(variant),
+                ..
+            } = expect!(self, TokenKind::Identifier(_))?
+            else {
+                unreachable!()
+            };
+            let kind = match self.peek() {
+                Some(Token {
+                    kind: TokenKind::OpenParen,
+                    ..
+                }) => {
+                    self.eat()?;
+                    VariantKind::Tuple(self.parse_variant_tuple_types()?)
+                }
+                Some(Token {
+                    kind: TokenKind::OpenBrace,
+                    ..
+                }) => {
+                    self.eat()?;
+                    VariantKind::Struct(self.parse_variant_fields_types()?)
+                }
+                _ => VariantKind::Unit,
+            };
+            variants.push(VariantDecl {
+                name: variant,
+                kind,
+            });
+            if let TokenKind::CloseBrace =
+                expect!(self, TokenKind::Comma | TokenKind::CloseBrace)?.kind
+            {
+                break;
+            }
+        }
+        Ok(AST::Enum {
+            name,
+            generics,
+            variants,
+            attributes: Vec::new(),
+        })
+    }
+    ///Parses the construction of a variant, such as Shape::Circle(1.5). The '::' must already be
+    ///eaten
+    pub fn parse_variant_expr(
+        &mut self,
+        name: String,
+        type_args: Vec<TypeAst>,
+        condition: ParsingCondition,
+    ) -> Result<AST, AstError> {
+        let Token {
+            kind: TokenKind::Identifier(variant),
+            ..
+        } = expect!(self, TokenKind::Identifier(_))?
+        else {
+            unreachable!()
+        };
+        let args = match self.peek() {
+            Some(Token {
+                kind: TokenKind::OpenParen,
+                ..
+            }) => {
+                self.eat()?;
+                VariantArgs::Tuple(self.parse_call_args(false)?.0)
+            }
+            Some(Token {
+                kind: TokenKind::OpenBrace,
+                ..
+            }) if condition != ParsingCondition::NoStruct => {
+                self.eat()?;
+                let AST::StructExpr { fields, .. } =
+                    self.parse_struct_expr(variant.clone(), vec![])?
+                else {
+                    unreachable!()
+                };
+                VariantArgs::Struct(fields)
+            }
+            _ => VariantArgs::Unit,
+        };
+        Ok(AST::VariantExpr {
+            name,
+            type_args,
+            variant,
+            args,
+        })
+    }
+}
@@ -1,8 +1,8 @@
 use std::collections::VecDeque;
 
 use super::{
-    AST, AstError, AstErrorKind, KeyTypePair, Operator, ParsingCondition, Program, Token,
-    TokenKind, TypeAst,
+    AST, AstError, AstErrorKind, FunctionParameter, KeyExprPair, Operator, ParsingCondition,
+    Program, Span, Token, TokenKind, TypeAst,
 };
 use crate::{expect, parser::Parser};
 
@@ -14,20 +14,30 @@ enum FunctionBodyType {
 
 impl Parser {
     ///Parses the current function parameter, eating only its necessary data to create a FunctionParameter.
-    fn parse_fparameter(&mut self) -> Result<KeyTypePair, AstError> {
-        let Token {
-            kind: TokenKind::Identifier(key),
-            ..
-        } = expect!(self, TokenKind::Identifier(_))?
-        else {
-            unreachable!();
-        };
+    ///A parameter can have a default value, as the b: int = 2 of function f(a: int, b: int = 2)
+    fn parse_fparameter(&mut self) -> Result<FunctionParameter, AstError> {
+        let pattern = self.parse_pattern()?;
         expect!(self, TokenKind::Colon)?;
         let kindof = self.get_type()?;
-        Ok(KeyTypePair { key, kindof })
+        let default = if let Some(Token {
+            kind: TokenKind::Operator(Operator::Eq(false)),
+            ..
+        }) = self.peek()
+        {
+            self.eat()?;
+            let next = self.eat()?;
+            Some(self.parse_expr(next, ParsingCondition::None)?)
+        } else {
+            None
+        };
+        Ok(FunctionParameter {
+            pattern,
+            kindof,
+            default,
+        })
     }
 
-    fn parse_params(&mut self) -> Result<VecDeque<KeyTypePair>, AstError> {
+    fn parse_params(&mut self) -> Result<VecDeque<FunctionParameter>, AstError> {
         let mut params = VecDeque::new();
         loop {
             if let Some(Token {
@@ -46,8 +56,9 @@ impl Parser {
         }
         Ok(params)
     }
-    pub fn parse_fbody(&mut self) -> Result<VecDeque<AST>, AstError> {
+    pub fn parse_fbody(&mut self) -> Result<Program, AstError> {
         let mut vec = VecDeque::new();
+        let mut spans = VecDeque::new();
         loop {
             let current = self.eat()?;
             if let Token {
@@ -57,19 +68,27 @@ impl Parser {
             {
                 break;
             }
+            spans.push_back(Span {
+                line: current.line,
+                column: current.column,
+            });
             let statment = self.parse_statment(current)?;
             if let Some(Token {
                 kind: TokenKind::CloseBrace,
                 ..
             }) = self.peek()
             {
-                vec.push_back(AST::Return(Box::new(statment)))
+                vec.push_back(if statment.gives_value() {
+                    AST::Return(Some(Box::new(statment)))
+                } else {
+                    statment
+                })
             } else {
                 vec.push_back(statment);
                 expect!(self, TokenKind::SemiColon)?;
             }
         }
-        Ok(vec)
+        Ok(Program { body: vec, spans })
     }
 
     pub fn parse_function(&mut self) -> Result<AST, AstError> {
@@ -80,87 +99,118 @@ impl Parser {
         else {
             unreachable!();
         };
+        let mut generics = self.parse_optional_generic_params()?;
         expect!(self, TokenKind::OpenParen)?;
         let params = self.parse_params()?;
-        let mut body_type = FunctionBodyType::Block;
-        let returntype = {
+        let returntype = if let Some(Token {
+            kind: TokenKind::Colon,
+            ..
+        }) = self.peek()
+        {
+            self.eat()?;
+            self.get_type()?
+        } else {
+            TypeAst::Primitive("void".to_string())
+        };
+        self.parse_where_clause(&mut generics)?;
+        let body_type = {
             let curr = self.eat()?;
-            if let Token {
-                kind: tk @ (TokenKind::Operator(Operator::Eq(false)) | TokenKind::OpenBrace),
-                ..
-            } = curr
-            {
-                match tk {
-                    TokenKind::OpenBrace => {}
-                    _ => body_type = FunctionBodyType::Expression,
-                };
-                TypeAst::Primitive("void".to_string())
-            } else if let Token {
-                kind: TokenKind::Colon,
-                ..
-            } = curr
-            {
-                let type_tokens = self.get_type()?;
-                let curr = self.eat()?;
-                match curr.kind {
-                    TokenKind::Operator(Operator::Eq(false)) => {
-                        body_type = FunctionBodyType::Expression
-                    }
-                    TokenKind::OpenBrace => {}
-                    _ => {
-                        return Err(AstError {
-                            line: curr.line,
-                            column: curr.column,
-                            kind: AstErrorKind::InvalidReturnType(curr.kind),
-                        });
-                    }
-                };
-                type_tokens
-            } else {
-                return Err(AstError {
-                    line: curr.line,
-                    column: curr.column,
-                    kind: AstErrorKind::InvalidReturnType(curr.kind),
-                });
+            match curr.kind {
+                TokenKind::Operator(Operator::Eq(false)) => FunctionBodyType::Expression,
+                TokenKind::OpenBrace => FunctionBodyType::Block,
+                _ => {
+                    return Err(AstError {
+                        line: curr.line,
+                        column: curr.column,
+                        kind: AstErrorKind::InvalidReturnType(curr.kind),
+                    });
+                }
             }
         };
         let body = if body_type == FunctionBodyType::Block {
-            Program {
-                body: self.parse_fbody()?,
-            }
+            self.parse_fbody()?
         } else {
             let current = self.eat()?;
+            let span = Span {
+                line: current.line,
+                column: current.column,
+            };
             let p = Program {
-                body: VecDeque::from(vec![AST::Return(Box::new(
+                body: VecDeque::from(vec![AST::Return(Some(Box::new(
                     self.parse_expr(current, ParsingCondition::None)?,
-                ))]),
+                )))]),
+                spans: VecDeque::from(vec![span]),
             };
             expect!(self, TokenKind::SemiColon)?;
             p
         };
         Ok(AST::Function {
             name,
+            generics,
             returntype,
             body,
             params,
+            attributes: Vec::new(),
         })
     }
-    pub fn parse_function_call(&mut self, fname: String) -> Result<AST, AstError> {
+    pub fn parse_function_call(
+        &mut self,
+        fname: String,
+        type_args: Vec<TypeAst>,
+        span: Span,
+    ) -> Result<AST, AstError> {
+        let (args, named) = self.parse_call_args(true)?;
+        Ok(AST::FunctionCall {
+            name: fname,
+            type_args,
+            args,
+            named,
+            span,
+        })
+    }
+    ///Parses the arguments of a call up to its ')', the '(' must already be eaten. The named ones,
+    ///as the b: 3 of f(1, b: 3), must come after the positional ones and are only parsed when
+    ///named is true
+    pub fn parse_call_args(
+        &mut self,
+        named: bool,
+    ) -> Result<(VecDeque<AST>, VecDeque<KeyExprPair>), AstError> {
+        let mut args = VecDeque::new();
+        let mut named_args = VecDeque::new();
         if let Some(Token {
             kind: TokenKind::CloseParen,
             ..
         }) = self.peek()
         {
             self.eat()?;
-            return Ok(AST::FunctionCall {
-                name: fname,
-                args: VecDeque::new(),
-            });
+            return Ok((args, named_args));
         }
-        let mut params = VecDeque::new();
         loop {
             let curr = self.eat()?;
-            params.push_back(self.parse_expr(curr, ParsingCondition::None)?);
+            match (&curr.kind, self.peek()) {
+                (
+                    TokenKind::Identifier(key),
+                    Some(Token {
+                        kind: TokenKind::Colon,
+                        ..
+                    }),
+                ) if named => {
+                    self.eat()?;
+                    let next = self.eat()?;
+                    named_args.push_back(KeyExprPair {
+                        key: key.clone(),
+                        value: self.parse_expr(next, ParsingCondition::None)?,
+                    });
+                }
+                _ if !named_args.is_empty() => {
+                    return Err(AstError {
+                        line: curr.line,
+                        column: curr.column,
+                        kind: AstErrorKind::PositionalAfterNamed,
+                    });
+                }
+                _ => args.push_back(self.parse_expr(curr, ParsingCondition::None)?),
+            }
             if let Some(Token {
                 kind: TokenKind::CloseParen,
                 ..
@@ -171,9 +221,6 @@ impl Parser {
             }
             expect!(self, TokenKind::Comma)?;
         }
-        Ok(AST::FunctionCall {
-            name: fname,
-            args: params,
-        })
+        Ok((args, named_args))
     }
 }
@@ -0,0 +1,41 @@
+use crate::{
+    expect,
+    parser::{AST, AstError, AstErrorKind, AstResult, Parser, ParsingCondition, Token, TokenKind},
+    tokenizer::Reserved,
+};
+
+impl Parser {
+    ///Parses the statements that leave the code they are in: return expr, return alone, break
+    ///and panic(message). tk is the keyword starting it
+    pub fn parse_jump(&mut self, tk: Token) -> AstResult {
+        match tk.kind {
+            TokenKind::Reserved(Reserved::Return) => {
+                //a return without a value ends the statement, the block or the match arm it's in
+                if let Some(Token {
+                    kind: TokenKind::SemiColon | TokenKind::CloseBrace | TokenKind::Comma,
+                    ..
+                }) = self.peek()
+                {
+                    return Ok(AST::Return(None));
+                }
+                let current = self.eat()?;
+                Ok(AST::Return(Some(Box::new(
+                    self.parse_expr(current, ParsingCondition::None)?,
+                ))))
+            }
+            TokenKind::Reserved(Reserved::Break) => Ok(AST::Break),
+            TokenKind::Reserved(Reserved::Panic) => {
+                expect!(self, TokenKind::OpenParen)?;
+                let current = self.eat()?;
+                let message = self.parse_expr(current, ParsingCondition::None)?;
+                expect!(self, TokenKind::CloseParen)?;
+                Ok(AST::Panic(Box::new(message)))
+            }
+            _ => Err(AstError {
+                line: tk.line,
+                column: tk.column,
+                kind: AstErrorKind::UnexpectedToken(tk),
+            }),
+        }
+    }
+}
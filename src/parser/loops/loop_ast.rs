@@ -1,4 +1,4 @@
-use crate::parser::{AST, AstResult, Parser, TokenKind};
+use crate::parser::{AST, AstResult, Parser};
 
 impl Parser {
     pub fn parse_loop_statment(&mut self) -> AstResult {
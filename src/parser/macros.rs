@@ -0,0 +1,849 @@
+//Declarative macros. Definitions are collected and removed from the token stream, then every
+//`name!(...)` invocation is replaced by the transcription of the first rule that matches it. All of
+//it happens before gen_ast builds the AST, so the parser only ever sees plain tokens.
+//
+//macro square {
+//    ($x:expr) -> ($x * $x);
+//}
+//macro sum {
+//    ($($x:expr),+) -> (0 $(+ $x)*);
+//}
+
+use std::{
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
+
+use super::{AstError, AstErrorKind, Operator, Parser, ParsingCondition, Token, TokenKind};
+use crate::{
+    expect,
+    tokenizer::{Expansion, Reserved},
+};
+
+///How deep macros may expand into other macros before giving up
+const RECURSION_LIMIT: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FragmentKind {
+    Expr,
+    Ident,
+    Type,
+    Block,
+    TokenTree,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RepeatOp {
+    ZeroOrMore,
+    OneOrMore,
+}
+
+#[derive(Debug)]
+enum Matcher {
+    Token(TokenKind),
+    Fragment {
+        name: String,
+        kind: FragmentKind,
+    },
+    Repeat {
+        inner: Vec<Matcher>,
+        separator: Option<TokenKind>,
+        op: RepeatOp,
+    },
+}
+
+#[derive(Debug)]
+enum Transcriber {
+    Token(Token),
+    Variable(Token, String),
+    Repeat {
+        inner: Vec<Transcriber>,
+        separator: Option<Token>,
+    },
+}
+
+#[derive(Debug)]
+struct Rule {
+    matcher: Vec<Matcher>,
+    transcriber: Vec<Transcriber>,
+    ///Rules written as `-> (...)` expand into a parenthesized expression, while `-> {...}` splices
+    ///the tokens as they are, so a macro can expand into statements
+    parenthesized: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Fragment {
+    Single {
+        kind: FragmentKind,
+        tokens: Vec<Token>,
+    },
+    Repeated(Vec<Fragment>),
+}
+
+type Bindings = HashMap<String, Fragment>;
+
+#[derive(Default)]
+struct MacroExpander {
+    macros: HashMap<String, Vec<Rule>>,
+    ///Counter used to give the bindings of each expansion a unique name
+    gensym: usize,
+}
+
+impl FragmentKind {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "expr" => Self::Expr,
+            "ident" => Self::Ident,
+            "type" => Self::Type,
+            "block" => Self::Block,
+            "tt" => Self::TokenTree,
+            _ => return None,
+        })
+    }
+}
+
+fn unexpected(tk: Token) -> AstError {
+    AstError {
+        line: tk.line,
+        column: tk.column,
+        kind: AstErrorKind::UnexpectedToken(tk),
+    }
+}
+
+fn next_token(tokens: &mut VecDeque<Token>) -> Result<Token, AstError> {
+    match tokens.pop_front() {
+        Some(Token {
+            kind: TokenKind::EOF,
+            line,
+            column,
+            ..
+        }) => Err(AstError {
+            line,
+            column,
+            kind: AstErrorKind::EatingEOF,
+        }),
+        Some(tk) => Ok(tk),
+        None => Err(AstError {
+            kind: AstErrorKind::EatingEOF,
+            line: 0,
+            column: 0,
+        }),
+    }
+}
+
+#[inline]
+fn is_open(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::OpenParen | TokenKind::OpenBrace | TokenKind::OpenBracket
+    )
+}
+
+#[inline]
+fn is_close(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::CloseParen | TokenKind::CloseBrace | TokenKind::CloseBracket
+    )
+}
+
+///Eats the tokens until the one closing the group whose opening token was already eaten. The
+///closing token is eaten but not returned
+fn take_group(tokens: &mut VecDeque<Token>) -> Result<Vec<Token>, AstError> {
+    let mut out = Vec::new();
+    let mut depth = 0usize;
+    loop {
+        let tk = next_token(tokens)?;
+        if is_open(&tk.kind) {
+            depth += 1;
+        } else if is_close(&tk.kind) {
+            if depth == 0 {
+                return Ok(out);
+            }
+            depth -= 1;
+        }
+        out.push(tk);
+    }
+}
+
+///Given that input[0] opens a group, returns the index of the token that closes it
+fn group_end(input: &[Token]) -> Option<usize> {
+    let mut depth = 0usize;
+    for (idx, tk) in input.iter().enumerate() {
+        if is_open(&tk.kind) {
+            depth += 1;
+        } else if is_close(&tk.kind) {
+            depth -= 1;
+            if depth == 0 {
+                return Some(idx);
+            }
+        }
+    }
+    None
+}
+
+fn repeat_op(kind: &TokenKind) -> Option<RepeatOp> {
+    match kind {
+        TokenKind::Operator(Operator::Star(false)) => Some(RepeatOp::ZeroOrMore),
+        TokenKind::Operator(Operator::Add(false)) => Some(RepeatOp::OneOrMore),
+        _ => None,
+    }
+}
+
+///Eats what comes after a `$(...)` group: an optional separator followed by `*` or `+`. A `*` or
+///`+` followed by another one is the separator, so `$($x)+*` repeats $x separated by `+`
+fn parse_repetition_suffix(
+    tokens: &mut VecDeque<Token>,
+) -> Result<(Option<Token>, RepeatOp), AstError> {
+    let tk = next_token(tokens)?;
+    if let Some(op) = repeat_op(&tk.kind)
+        && tokens
+            .front()
+            .is_none_or(|next| repeat_op(&next.kind).is_none())
+    {
+        return Ok((None, op));
+    }
+    let op_tk = next_token(tokens)?;
+    match repeat_op(&op_tk.kind) {
+        Some(op) => Ok((Some(tk), op)),
+        None => Err(unexpected(op_tk)),
+    }
+}
+
+fn parse_matcher(tokens: Vec<Token>) -> Result<Vec<Matcher>, AstError> {
+    let mut tokens = VecDeque::from(tokens);
+    let mut out = Vec::new();
+    while let Some(tk) = tokens.pop_front() {
+        if tk.kind != TokenKind::Dollar {
+            out.push(Matcher::Token(tk.kind));
+            continue;
+        }
+        let next = next_token(&mut tokens)?;
+        match next.kind {
+            TokenKind::Identifier(name) => {
+                let colon = next_token(&mut tokens)?;
+                if colon.kind != TokenKind::Colon {
+                    return Err(unexpected(colon));
+                }
+                let kind_tk = next_token(&mut tokens)?;
+                let kind_name = match &kind_tk.kind {
+                    TokenKind::Identifier(kind_name) => kind_name.as_str(),
+                    TokenKind::Reserved(Reserved::Type) => "type",
+                    _ => return Err(unexpected(kind_tk)),
+                };
+                let Some(kind) = FragmentKind::from_name(kind_name) else {
+                    return Err(AstError {
+                        line: kind_tk.line,
+                        column: kind_tk.column,
+                        kind: AstErrorKind::UnknownFragmentKind(kind_name.to_string()),
+                    });
+                };
+                out.push(Matcher::Fragment { name, kind });
+            }
+            TokenKind::OpenParen => {
+                let inner = parse_matcher(take_group(&mut tokens)?)?;
+                let (separator, op) = parse_repetition_suffix(&mut tokens)?;
+                out.push(Matcher::Repeat {
+                    inner,
+                    separator: separator.map(|s| s.kind),
+                    op,
+                });
+            }
+            _ => return Err(unexpected(next)),
+        }
+    }
+    Ok(out)
+}
+
+fn parse_transcriber(tokens: Vec<Token>) -> Result<Vec<Transcriber>, AstError> {
+    let mut tokens = VecDeque::from(tokens);
+    let mut out = Vec::new();
+    while let Some(tk) = tokens.pop_front() {
+        if tk.kind != TokenKind::Dollar {
+            out.push(Transcriber::Token(tk));
+            continue;
+        }
+        let next = next_token(&mut tokens)?;
+        match &next.kind {
+            TokenKind::Identifier(name) => {
+                let name = name.clone();
+                out.push(Transcriber::Variable(next, name));
+            }
+            TokenKind::OpenParen => {
+                let inner = parse_transcriber(take_group(&mut tokens)?)?;
+                let (separator, _) = parse_repetition_suffix(&mut tokens)?;
+                out.push(Transcriber::Repeat { inner, separator });
+            }
+            _ => return Err(unexpected(next)),
+        }
+    }
+    Ok(out)
+}
+
+fn matcher_variables(matchers: &[Matcher], out: &mut Vec<String>) {
+    for matcher in matchers {
+        match matcher {
+            Matcher::Token(_) => {}
+            Matcher::Fragment { name, .. } => out.push(name.clone()),
+            Matcher::Repeat { inner, .. } => matcher_variables(inner, out),
+        }
+    }
+}
+
+fn transcriber_variables(transcriber: &[Transcriber], out: &mut Vec<String>) {
+    for t in transcriber {
+        match t {
+            Transcriber::Token(_) => {}
+            Transcriber::Variable(_, name) => out.push(name.clone()),
+            Transcriber::Repeat { inner, .. } => transcriber_variables(inner, out),
+        }
+    }
+}
+
+///Names bound with `let` by the literal tokens of a transcriber. These are the ones renamed on
+///every expansion so they can't capture, or be captured by, the names at the invocation site.
+///Inside a destructuring pattern, struct and variant names and the fields being renamed are not
+///bindings
+fn introduced_bindings(transcriber: &[Transcriber], out: &mut Vec<String>) {
+    let kind_at = |idx: usize| match transcriber.get(idx) {
+        Some(Transcriber::Token(tk)) => Some(&tk.kind),
+        _ => None,
+    };
+    let mut in_pattern = false;
+    let mut depth = 0usize;
+    for (idx, t) in transcriber.iter().enumerate() {
+        let tk = match t {
+            Transcriber::Token(tk) => tk,
+            Transcriber::Repeat { inner, .. } => {
+                introduced_bindings(inner, out);
+                in_pattern = false;
+                continue;
+            }
+            Transcriber::Variable(..) => continue,
+        };
+        match &tk.kind {
+            TokenKind::Reserved(Reserved::Let) => {
+                in_pattern = true;
+                depth = 0;
+            }
+            _ if !in_pattern => {}
+            kind if is_open(kind) => depth += 1,
+            kind if is_close(kind) => depth = depth.saturating_sub(1),
+            //the pattern ends at the '=' or at the ':' of its type
+            TokenKind::Operator(Operator::Eq(false)) => in_pattern = false,
+            TokenKind::Colon if depth == 0 => in_pattern = false,
+            TokenKind::Identifier(name) if name != "_" && name != "None" => {
+                let field_or_path = matches!(
+                    kind_at(idx + 1),
+                    Some(TokenKind::DoubleColon | TokenKind::OpenBrace | TokenKind::OpenParen)
+                ) || (depth > 0
+                    && matches!(kind_at(idx + 1), Some(TokenKind::Colon)))
+                    || matches!(
+                        idx.checked_sub(1).and_then(kind_at),
+                        Some(TokenKind::DoubleColon)
+                    );
+                if !field_or_path {
+                    out.push(name.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+///Returns how many tokens at the start of input form a fragment of the given kind. Expressions,
+///types and blocks are measured by running the parser on a copy of the input
+fn fragment_len(kind: FragmentKind, input: &[Token]) -> Option<usize> {
+    let first = input.first()?;
+    match kind {
+        FragmentKind::Ident => matches!(first.kind, TokenKind::Identifier(_)).then_some(1),
+        FragmentKind::TokenTree if is_open(&first.kind) => group_end(input).map(|end| end + 1),
+        FragmentKind::TokenTree => (!is_close(&first.kind)).then_some(1),
+        FragmentKind::Expr | FragmentKind::Type | FragmentKind::Block => {
+            let mut parser = Parser {
+                tokens: input.iter().cloned().collect(),
+            };
+            parser.tokens.push_back(Token {
+                kind: TokenKind::EOF,
+                start: 0,
+                line: first.line,
+                column: first.column,
+                expansion: None,
+            });
+            let parsed = match kind {
+                FragmentKind::Expr => parser
+                    .eat()
+                    .and_then(|tk| parser.parse_expr(tk, ParsingCondition::None))
+                    .is_ok(),
+                FragmentKind::Type => parser.get_type().is_ok(),
+                _ => expect!(parser, TokenKind::OpenBrace)
+                    .and_then(|_| parser.parse_block_expr())
+                    .is_ok(),
+            };
+            let consumed = input.len() + 1 - parser.tokens.len();
+            (parsed && consumed <= input.len()).then_some(consumed)
+        }
+    }
+}
+
+///Matches the matchers against the input from pos, returning where the match ended
+fn match_sequence(
+    matchers: &[Matcher],
+    input: &[Token],
+    mut pos: usize,
+    bindings: &mut Bindings,
+) -> Option<usize> {
+    for matcher in matchers {
+        match matcher {
+            Matcher::Token(kind) => {
+                if input.get(pos)?.kind != *kind {
+                    return None;
+                }
+                pos += 1;
+            }
+            Matcher::Fragment { name, kind } => {
+                let len = fragment_len(*kind, &input[pos..])?;
+                bindings.insert(
+                    name.clone(),
+                    Fragment::Single {
+                        kind: *kind,
+                        tokens: input[pos..pos + len].to_vec(),
+                    },
+                );
+                pos += len;
+            }
+            Matcher::Repeat {
+                inner,
+                separator,
+                op,
+            } => {
+                let mut iterations = Vec::new();
+                loop {
+                    let mut start = pos;
+                    if let Some(separator) = separator
+                        && !iterations.is_empty()
+                    {
+                        if input.get(pos).map(|tk| &tk.kind) != Some(separator) {
+                            break;
+                        }
+                        start += 1;
+                    }
+                    let mut inner_bindings = Bindings::new();
+                    match match_sequence(inner, input, start, &mut inner_bindings) {
+                        Some(end) if end > pos => {
+                            iterations.push(inner_bindings);
+                            pos = end;
+                        }
+                        _ => break,
+                    }
+                }
+                if *op == RepeatOp::OneOrMore && iterations.is_empty() {
+                    return None;
+                }
+                let mut names = Vec::new();
+                matcher_variables(inner, &mut names);
+                for name in names {
+                    let fragments = iterations
+                        .iter_mut()
+                        .filter_map(|b| b.remove(&name))
+                        .collect();
+                    bindings.insert(name, Fragment::Repeated(fragments));
+                }
+            }
+        }
+    }
+    Some(pos)
+}
+
+fn transcribe(
+    transcriber: &[Transcriber],
+    bindings: &Bindings,
+    frame: &Rc<Expansion>,
+    hygiene: &HashMap<String, String>,
+    out: &mut VecDeque<Token>,
+    renamable: &mut Vec<usize>,
+) -> Result<(), AstError> {
+    for t in transcriber {
+        match t {
+            Transcriber::Token(tk) => {
+                let mut tk = tk.clone();
+                if let TokenKind::Identifier(name) = &tk.kind
+                    && hygiene.contains_key(name)
+                {
+                    renamable.push(out.len());
+                }
+                tk.expansion = Some(frame.clone());
+                out.push_back(tk);
+            }
+            Transcriber::Variable(tk, name) => match bindings.get(name) {
+                Some(Fragment::Single { kind, tokens }) => {
+                    //keeps the precedence of the expression it was given, so square!(a + 1) is
+                    //(a + 1) * (a + 1)
+                    let wrap = *kind == FragmentKind::Expr && tokens.len() > 1;
+                    let paren = |kind| Token {
+                        kind,
+                        expansion: Some(frame.clone()),
+                        ..tk.clone()
+                    };
+                    if wrap {
+                        out.push_back(paren(TokenKind::OpenParen));
+                    }
+                    out.extend(tokens.iter().cloned());
+                    if wrap {
+                        out.push_back(paren(TokenKind::CloseParen));
+                    }
+                }
+                Some(Fragment::Repeated(_)) => {
+                    return Err(AstError {
+                        line: tk.line,
+                        column: tk.column,
+                        kind: AstErrorKind::MacroRepetitionMismatch(name.clone()),
+                    });
+                }
+                None => {
+                    return Err(AstError {
+                        line: tk.line,
+                        column: tk.column,
+                        kind: AstErrorKind::UnboundMacroVariable(name.clone()),
+                    });
+                }
+            },
+            Transcriber::Repeat { inner, separator } => {
+                let mut names = Vec::new();
+                transcriber_variables(inner, &mut names);
+                let mut repeated = names.iter().filter_map(|name| match bindings.get(name) {
+                    Some(Fragment::Repeated(fragments)) => Some((name, fragments)),
+                    _ => None,
+                });
+                let Some((first_name, first)) = repeated.next() else {
+                    return Err(AstError {
+                        line: frame.line,
+                        column: frame.column,
+                        kind: AstErrorKind::MacroRepetitionMismatch(frame.name.clone()),
+                    });
+                };
+                let count = first.len();
+                let mut repeated = repeated.collect::<Vec<_>>();
+                if let Some((name, _)) = repeated.iter().find(|(_, f)| f.len() != count) {
+                    return Err(AstError {
+                        line: frame.line,
+                        column: frame.column,
+                        kind: AstErrorKind::MacroRepetitionMismatch((*name).clone()),
+                    });
+                }
+                repeated.push((first_name, first));
+                for idx in 0..count {
+                    if idx > 0
+                        && let Some(separator) = separator
+                    {
+                        let mut separator = separator.clone();
+                        separator.expansion = Some(frame.clone());
+                        out.push_back(separator);
+                    }
+                    let mut iteration = bindings.clone();
+                    for (name, fragments) in &repeated {
+                        iteration.insert((*name).clone(), fragments[idx].clone());
+                    }
+                    transcribe(inner, &iteration, frame, hygiene, out, renamable)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+///What the names inside a group of an expansion are, besides bindings and references
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Group {
+    ///The fields of a struct expression or pattern, as in Point { x: 1, y }
+    Fields,
+    ///The arguments of a call, which may be named, as in f(1, scale: 2)
+    Arguments,
+    Other,
+}
+
+///Renames the identifiers of the transcriber found at the given positions of an expansion. Only
+///the ones in binding or reference position are: field names, named arguments and the segments of
+///paths keep their names, and a renamed field shorthand is written out as `field: renamed`.
+///Braces after an identifier hold fields, unless they open the body of an if, while, for, match
+///or function, and parentheses after an identifier, a ')' or a '>' hold arguments, unless they
+///are the parameters of a function
+fn rename_introduced(
+    tokens: &VecDeque<Token>,
+    renamable: &[usize],
+    hygiene: &HashMap<String, String>,
+) -> VecDeque<Token> {
+    let kind_at = |idx: usize| tokens.get(idx).map(|tk| &tk.kind);
+    let mut out = VecDeque::with_capacity(tokens.len());
+    let mut groups = Vec::new();
+    //the depth of the keyword whose body opens with the next brace at that depth, and whether
+    //it's a function, whose parameters come first
+    let mut body_next: Option<(usize, bool)> = None;
+    for (idx, tk) in tokens.iter().enumerate() {
+        let previous = idx.checked_sub(1).and_then(kind_at);
+        let next = kind_at(idx + 1);
+        let depth = groups.len();
+        let at_keyword = body_next.is_some_and(|(keyword_depth, _)| keyword_depth == depth);
+        match &tk.kind {
+            TokenKind::Reserved(
+                keyword @ (Reserved::If
+                | Reserved::While
+                | Reserved::For
+                | Reserved::Match
+                | Reserved::Function),
+            ) => body_next = Some((depth, *keyword == Reserved::Function)),
+            TokenKind::OpenBrace => {
+                let group = if at_keyword {
+                    body_next = None;
+                    Group::Other
+                } else if matches!(previous, Some(TokenKind::Identifier(_))) {
+                    Group::Fields
+                } else {
+                    Group::Other
+                };
+                groups.push(group);
+            }
+            TokenKind::OpenParen => {
+                let parameters = body_next == Some((depth, true));
+                let called = matches!(
+                    previous,
+                    Some(
+                        TokenKind::Identifier(_)
+                            | TokenKind::CloseParen
+                            | TokenKind::Operator(Operator::Gt(false))
+                    )
+                );
+                groups.push(if called && !parameters {
+                    Group::Arguments
+                } else {
+                    Group::Other
+                });
+            }
+            TokenKind::OpenBracket => groups.push(Group::Other),
+            kind if is_close(kind) => {
+                groups.pop();
+            }
+            //a function whose body is an expression
+            TokenKind::Operator(Operator::Eq(false)) | TokenKind::SemiColon if at_keyword => {
+                body_next = None;
+            }
+            TokenKind::Identifier(name) if renamable.binary_search(&idx).is_ok() => {
+                let field = matches!(
+                    previous,
+                    Some(TokenKind::Dot | TokenKind::QuestionDot | TokenKind::DoubleColon)
+                ) || matches!(next, Some(TokenKind::DoubleColon))
+                    || match groups.last() {
+                        Some(Group::Fields) => matches!(next, Some(TokenKind::Colon)),
+                        Some(Group::Arguments) => {
+                            matches!(next, Some(TokenKind::Colon))
+                                && matches!(previous, Some(TokenKind::OpenParen | TokenKind::Comma))
+                        }
+                        _ => false,
+                    };
+                if !field {
+                    let shorthand = groups.last() == Some(&Group::Fields)
+                        && matches!(previous, Some(TokenKind::OpenBrace | TokenKind::Comma))
+                        && matches!(next, Some(TokenKind::Comma | TokenKind::CloseBrace));
+                    if shorthand {
+                        out.push_back(tk.clone());
+                        out.push_back(Token {
+                            kind: TokenKind::Colon,
+                            ..tk.clone()
+                        });
+                    }
+                    out.push_back(Token {
+                        kind: TokenKind::Identifier(hygiene[name].clone()),
+                        ..tk.clone()
+                    });
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        out.push_back(tk.clone());
+    }
+    out
+}
+
+impl MacroExpander {
+    ///Registers every macro definition and returns the tokens without them
+    fn collect_definitions(
+        &mut self,
+        mut tokens: VecDeque<Token>,
+    ) -> Result<VecDeque<Token>, AstError> {
+        let mut out = VecDeque::with_capacity(tokens.len());
+        while let Some(tk) = tokens.pop_front() {
+            if tk.kind != TokenKind::Reserved(Reserved::Macro) {
+                out.push_back(tk);
+                continue;
+            }
+            let name_tk = next_token(&mut tokens)?;
+            let TokenKind::Identifier(name) = name_tk.kind else {
+                return Err(unexpected(name_tk));
+            };
+            let open = next_token(&mut tokens)?;
+            if open.kind != TokenKind::OpenBrace {
+                return Err(unexpected(open));
+            }
+            let mut body = VecDeque::from(take_group(&mut tokens)?);
+            let mut rules = Vec::new();
+            while let Some(open) = body.pop_front() {
+                if open.kind != TokenKind::OpenParen {
+                    return Err(unexpected(open));
+                }
+                let matcher = parse_matcher(take_group(&mut body)?)?;
+                let arrow = next_token(&mut body)?;
+                if arrow.kind != TokenKind::Operator(Operator::Arrow) {
+                    return Err(unexpected(arrow));
+                }
+                let open = next_token(&mut body)?;
+                let parenthesized = match open.kind {
+                    TokenKind::OpenParen => true,
+                    TokenKind::OpenBrace => false,
+                    _ => return Err(unexpected(open)),
+                };
+                let transcriber = parse_transcriber(take_group(&mut body)?)?;
+                rules.push(Rule {
+                    matcher,
+                    transcriber,
+                    parenthesized,
+                });
+                if let Some(Token {
+                    kind: TokenKind::SemiColon,
+                    ..
+                }) = body.front()
+                {
+                    body.pop_front();
+                }
+            }
+            self.macros.insert(name, rules);
+        }
+        Ok(out)
+    }
+
+    ///Replaces every invocation in tokens by its expansion. Arguments are expanded before being
+    ///matched, and the result of an expansion is expanded again until no invocation is left
+    fn expand(
+        &mut self,
+        mut tokens: VecDeque<Token>,
+        parent: Option<&Rc<Expansion>>,
+        depth: usize,
+    ) -> Result<VecDeque<Token>, AstError> {
+        let mut out = VecDeque::with_capacity(tokens.len());
+        while let Some(tk) = tokens.pop_front() {
+            let is_invocation = matches!(tk.kind, TokenKind::Identifier(_))
+                && matches!(
+                    tokens.front(),
+                    Some(Token {
+                        kind: TokenKind::Operator(Operator::Bang(false)),
+                        ..
+                    })
+                )
+                && tokens.get(1).is_some_and(|tk| is_open(&tk.kind));
+            if !is_invocation {
+                out.push_back(tk);
+                continue;
+            }
+            let TokenKind::Identifier(name) = tk.kind else {
+                unreachable!();
+            };
+            if !self.macros.contains_key(&name) {
+                return Err(AstError {
+                    line: tk.line,
+                    column: tk.column,
+                    kind: AstErrorKind::UnknownMacro(name),
+                });
+            }
+            tokens.pop_front(); //eats '!'
+            tokens.pop_front(); //eats the opening delimiter
+            let args = take_group(&mut tokens)?;
+            let mut args = self.expand(VecDeque::from(args), parent, depth)?;
+            let frame = Rc::new(Expansion {
+                name,
+                line: tk.line,
+                column: tk.column,
+                parent: parent.cloned(),
+            });
+            if depth >= RECURSION_LIMIT {
+                return Err(AstError {
+                    line: tk.line,
+                    column: tk.column,
+                    kind: AstErrorKind::MacroRecursionLimit(frame),
+                });
+            }
+            let expanded = self.expand_invocation(&frame, args.make_contiguous())?;
+            out.extend(self.expand(expanded, Some(&frame), depth + 1)?);
+        }
+        Ok(out)
+    }
+
+    fn expand_invocation(
+        &mut self,
+        frame: &Rc<Expansion>,
+        args: &[Token],
+    ) -> Result<VecDeque<Token>, AstError> {
+        let id = self.gensym;
+        self.gensym += 1;
+        let rules = &self.macros[&frame.name];
+        let Some((rule, bindings)) = rules
+            .iter()
+            .find_map(|rule| Some((rule, match_rule(&rule.matcher, args)?)))
+        else {
+            return Err(AstError {
+                line: frame.line,
+                column: frame.column,
+                kind: AstErrorKind::NoMatchingMacroRule(frame.clone()),
+            });
+        };
+        let mut introduced = Vec::new();
+        introduced_bindings(&rule.transcriber, &mut introduced);
+        let hygiene = introduced
+            .into_iter()
+            .map(|name| {
+                //no variable of the program is emitted ending in $g and a number
+                let renamed = format!("{name}$g{id}");
+                (name, renamed)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut out = VecDeque::new();
+        let mut renamable = Vec::new();
+        transcribe(
+            &rule.transcriber,
+            &bindings,
+            frame,
+            &hygiene,
+            &mut out,
+            &mut renamable,
+        )?;
+        let mut out = rename_introduced(&out, &renamable, &hygiene);
+        if rule.parenthesized {
+            let paren = |kind| Token {
+                kind,
+                start: 0,
+                line: frame.line,
+                column: frame.column,
+                expansion: Some(frame.clone()),
+            };
+            out.push_front(paren(TokenKind::OpenParen));
+            out.push_back(paren(TokenKind::CloseParen));
+        }
+        Ok(out)
+    }
+}
+
+fn match_rule(matchers: &[Matcher], input: &[Token]) -> Option<Bindings> {
+    let mut bindings = Bindings::new();
+    let end = match_sequence(matchers, input, 0, &mut bindings)?;
+    (end == input.len()).then_some(bindings)
+}
+
+impl Parser {
+    ///Collects the macro definitions and replaces every invocation by its expansion
+    pub(crate) fn expand_macros(&mut self) -> Result<(), AstError> {
+        let mut expander = MacroExpander::default();
+        let tokens = expander.collect_definitions(std::mem::take(&mut self.tokens))?;
+        self.tokens = expander.expand(tokens, None, 0)?;
+        Ok(())
+    }
+}
@@ -1,12 +1,20 @@
+mod attributes;
 mod basics;
 mod conditionals;
+mod enums;
 mod functions;
+mod jumps;
 mod loops;
+mod macros;
+mod modules;
+mod patterns;
+mod strings;
 mod structs;
 mod types;
 
-pub use crate::tokenizer::{Operator, Token, TokenKind, tokenize};
-use std::collections::VecDeque;
+pub use crate::tokenizer::{Expansion, Operator, Token, TokenKind, tokenize};
+pub use attributes::{AttributeTarget, KNOWN_ATTRIBUTES};
+use std::{collections::VecDeque, rc::Rc};
 
 type AstResult = Result<AST, AstError>;
 
@@ -24,84 +32,328 @@ pub enum AstErrorKind {
     UnexpectedToken(Token),
     ExpectedElseBranch,
     EatingEOF,
+    ///An int literal past the largest u64
+    IntegerTooLarge(String),
+    ///A where clause bounding a name that isn't one of the generics of the declaration
+    UnknownGenericParam(String),
+
+    UnknownMacro(String),
+    UnknownFragmentKind(String),
+    UnboundMacroVariable(String),
+    ///A `$(...)` repetition used variables that matched a different amount of times
+    MacroRepetitionMismatch(String),
+    NoMatchingMacroRule(Rc<Expansion>),
+    MacroRecursionLimit(Rc<Expansion>),
+
+    UnknownAttribute(String),
+    ///A known attribute written on a declaration it doesn't apply to
+    MisplacedAttribute {
+        name: String,
+        target: AttributeTarget,
+    },
+    ///A string whose `{` and `}` don't surround an expression, such as "{1 +}" or "a } b"
+    InvalidInterpolation(String),
+    ///A call giving a positional argument after a named one, as in f(a: 1, 2)
+    PositionalAfterNamed,
+}
+
+///Where something starts in the source code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
 }
 
 #[derive(Debug)]
 pub struct AstError {
-    line: usize,
-    column: usize,
-    kind: AstErrorKind,
+    pub line: usize,
+    pub column: usize,
+    pub kind: AstErrorKind,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum TypeAst {
     Primitive(String),
     Function {
         params: Vec<TypeAst>,
         return_type: Box<TypeAst>,
     },
+    ///A generic type applied to its arguments, such as Pair<int>
+    Generic {
+        name: String,
+        args: Vec<TypeAst>,
+    },
+    ///(int, float)
+    Tuple(Vec<TypeAst>),
+    ///int?, either an int or None
+    Option(Box<TypeAst>),
+}
+///A generic parameter of a struct or function, with the bounds given to it by the `where` clause,
+///such as T in `function f<T>(a: T): T where T extends Numeric`
+#[derive(Debug, Clone)]
+pub struct GenericParam {
+    pub name: String,
+    pub bounds: Vec<String>,
+}
+///A piece of an interpolated string
+#[derive(Debug)]
+pub enum StringPart {
+    ///Text, with its escapes as written
+    Literal(String),
+    ///{expr}, or {expr:?} when `debug`
+    Expr { expr: Box<AST>, debug: bool },
 }
-///A pair of key and type. The key is the name and kindof the type of it. Used for function
-///parameters and struct fields
+///An attribute written before a declaration or statement, such as @Memorized or
+///@Deprecated(2). Its arguments are expressions
+#[derive(Debug)]
+pub struct Attribute {
+    pub name: String,
+    pub args: VecDeque<AST>,
+    pub line: usize,
+    pub column: usize,
+}
+///A pair of key and type. The key is the name and kindof the type of it. Used for struct fields
 #[derive(Debug)]
 pub struct KeyTypePair {
     pub key: String,
     pub kindof: TypeAst,
+    pub attributes: Vec<Attribute>,
+}
+///A function parameter. Its pattern can destructure the argument, as in
+///function norm(Point { x, y }: Point): float
+#[derive(Debug)]
+pub struct FunctionParameter {
+    pub pattern: Pattern,
+    pub kindof: TypeAst,
+    ///The value given to the parameter when a call leaves it out
+    pub default: Option<AST>,
 }
-///Same as KeyTypePair but instead, is used only by struct expressions to define the values of the
-///key
+///Same as KeyTypePair but instead, is used by struct expressions to define the values of the key
+///and by calls to give arguments by name
 #[derive(Debug)]
 pub struct KeyExprPair {
     pub key: String,
     pub value: AST,
 }
 
+///The fields of an enum variant declaration, such as the (float) of Circle(float)
+#[derive(Debug)]
+pub enum VariantKind {
+    Unit,
+    Tuple(Vec<TypeAst>),
+    Struct(VecDeque<KeyTypePair>),
+}
+#[derive(Debug)]
+pub struct VariantDecl {
+    pub name: String,
+    pub kind: VariantKind,
+}
+///The values given to an enum variant when building it, such as Shape::Circle(1.5)
+#[derive(Debug)]
+pub enum VariantArgs {
+    Unit,
+    Tuple(VecDeque<AST>),
+    Struct(VecDeque<KeyExprPair>),
+}
+///A pattern a value is tested against by a match arm, or destructured with by a `let` or a
+///parameter
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    ///`_`, matches anything without binding it
+    Wildcard,
+    ///Matches anything, binding it to the name
+    Binding(String),
+    Bool(bool),
+    ///An int literal, which may be negated
+    Int(i128),
+    Float(f64),
+    ///A range of literals, such as 1..5 or 0.0..=1.0
+    Range {
+        start: Box<Pattern>,
+        end: Box<Pattern>,
+        inclusive: bool,
+    },
+    ///A variant of an enum, with patterns for its fields, such as Shape::Circle(r)
+    Variant {
+        name: String,
+        variant: String,
+        fields: VariantPattern,
+    },
+    ///A struct with patterns for its fields, such as Point { x, y: py }
+    Struct {
+        name: String,
+        fields: Vec<KeyPatternPair>,
+        rest: bool,
+    },
+    ///(a, b)
+    Tuple(Vec<Pattern>),
+    ///[a, b]
+    Array(Vec<Pattern>),
+    ///Some(x), an option holding a value
+    Some(Box<Pattern>),
+    ///None, an empty option
+    None,
+    ///Ok(x), a result holding a value
+    Ok(Box<Pattern>),
+    ///Err(e), a result holding an error
+    Err(Box<Pattern>),
+}
+///The patterns of the fields of a variant pattern
+#[derive(Debug, Clone)]
+pub enum VariantPattern {
+    Unit,
+    Tuple(Vec<Pattern>),
+    ///`rest` tells if the pattern ends with `..`, so the fields not listed are ignored
+    Struct {
+        fields: Vec<KeyPatternPair>,
+        rest: bool,
+    },
+}
+///Same as KeyExprPair but for the fields of struct-like patterns
+#[derive(Debug, Clone)]
+pub struct KeyPatternPair {
+    pub key: String,
+    pub pattern: Pattern,
+}
+#[derive(Debug)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<AST>,
+    pub body: AST,
+}
+
 #[derive(Debug)]
 pub enum AST {
     Identifier(String),
-    Int(i64),
+    ///An int literal. It has no sign, as negative numbers are subtractions from 0
+    Int(u64),
     Float(f64),
+    Bool(bool),
     BinExpr(Box<AST>, Box<AST>, Operator),
     VarDecl {
-        varname: String,
+        pattern: Pattern,
         mutable: bool,
+        ///The type written after the pattern, as in let x: float = 1. None when it's inferred
+        kindof: Option<TypeAst>,
         body: Box<AST>,
     },
     Function {
         name: String,
-        params: VecDeque<KeyTypePair>,
+        generics: Vec<GenericParam>,
+        params: VecDeque<FunctionParameter>,
         returntype: TypeAst,
         body: Program,
+        attributes: Vec<Attribute>,
     },
     FunctionCall {
         name: String,
+        ///Explicit generic arguments, as in id<int>(5). Empty when they are inferred
+        type_args: Vec<TypeAst>,
         args: VecDeque<AST>,
+        ///The arguments given by name, as the b: 3 of f(1, b: 3)
+        named: VecDeque<KeyExprPair>,
+        ///Where the name of the called function is
+        span: Span,
     },
-    Return(Box<AST>),
+    ///return expr, or a return without a value. The last statement of a function body is its
+    ///return too, when it gives a value and isn't followed by ';'
+    Return(Option<Box<AST>>),
+    ///Leaves the innermost loop
+    Break,
+    ///panic(message), which stops the program with the message
+    Panic(Box<AST>),
     Struct {
         name: String,
+        generics: Vec<GenericParam>,
         fields: VecDeque<KeyTypePair>,
+        attributes: Vec<Attribute>,
     },
     StructExpr {
         name: String,
+        type_args: Vec<TypeAst>,
         fields: VecDeque<KeyExprPair>,
     },
+    Enum {
+        name: String,
+        generics: Vec<GenericParam>,
+        variants: Vec<VariantDecl>,
+        attributes: Vec<Attribute>,
+    },
+    ///The construction of an enum variant, such as Shape::Circle(1.5) or Shape::Empty
+    VariantExpr {
+        name: String,
+        type_args: Vec<TypeAst>,
+        variant: String,
+        args: VariantArgs,
+    },
+    Match {
+        expr: Box<AST>,
+        arms: Vec<MatchArm>,
+    },
+    ///(a, b), or (a,) for a single value
+    Tuple(VecDeque<AST>),
+    ///The access to an element of a tuple, such as pair.0, or pair?.0 when `optional`
+    TupleIndex {
+        expr: Box<AST>,
+        index: usize,
+        optional: bool,
+    },
+    ///The access to a field of a struct, such as point.x, or point?.x when `optional`
+    FieldAccess {
+        expr: Box<AST>,
+        field: String,
+        optional: bool,
+    },
+    ///"text", with its escapes as written
+    Str(String),
+    ///"x = {x}", a string with the values of expressions in it
+    Interpolated(Vec<StringPart>),
+    ///Some(expr), an option holding the value of expr
+    Some(Box<AST>),
+    ///None, an option without a value
+    None,
+    ///Ok(expr), a result holding the value of expr
+    Ok(Box<AST>),
+    ///Err(expr), a result holding the value of expr as its error
+    Err(Box<AST>),
+    ///expr?, the value held by the result expr. Returns its error from the enclosing function
+    ///when there's one
+    Try(Box<AST>),
+    ///expr as float, the value of expr converted to another numeric type
+    Cast {
+        expr: Box<AST>,
+        kindof: TypeAst,
+    },
     If {
         expr: Box<AST>,
         block: Box<AST>,
         elseblock: Option<Box<AST>>,
     },
-    Block(VecDeque<AST>),
+    Block(Program),
     Loop(Box<AST>),
+    ///import { names } from "path";
+    Import {
+        names: Vec<String>,
+        path: String,
+    },
+    ///A declaration visible to the modules importing this one
+    Export(Box<AST>),
+    ///A statement with the attributes written before it. Declarations hold their own attributes
+    Attributed {
+        attributes: Vec<Attribute>,
+        ast: Box<AST>,
+    },
 }
 #[derive(Debug, Default)]
 pub struct Program {
     body: VecDeque<AST>,
+    ///Where each statement of the body starts
+    spans: VecDeque<Span>,
 }
 pub struct Parser {
     tokens: VecDeque<Token>,
 }
-#[macro_export()]
+#[macro_export]
 macro_rules! expect {
     ($parser:expr, $pattern:pat) => {{
         let tk = $parser.tokens.pop_front();
@@ -143,7 +395,9 @@ impl Parser {
     }
 
     pub fn gen_ast(&mut self) -> Result<Program, Vec<AstError>> {
+        self.expand_macros().map_err(|e| vec![e])?;
         let mut body = VecDeque::with_capacity(self.tokens.len() >> 1); //At least half of the len to not reallocate a lot
+        let mut spans = VecDeque::with_capacity(body.capacity());
         let mut errs = Vec::new();
         loop {
             if matches!(
@@ -156,13 +410,20 @@ impl Parser {
                 break;
             }
             let tk = self.eat().unwrap();
+            let span = Span {
+                line: tk.line,
+                column: tk.column,
+            };
             match self.start_gen(tk) {
                 Err(e) => errs.push(e),
-                Ok(ast) => body.push_back(ast),
+                Ok(ast) => {
+                    body.push_back(ast);
+                    spans.push_back(span);
+                }
             }
         }
         if errs.is_empty() {
-            Ok(Program { body })
+            Ok(Program { body, spans })
         } else {
             Err(errs)
         }
@@ -174,6 +435,19 @@ impl Parser {
     }
 }
 
+impl AstError {
+    ///The chain of macro invocations that led to this error, innermost first. Empty when the error
+    ///did not come from expanded code
+    pub fn expansion_trace(&self) -> Vec<&Expansion> {
+        let expansion = match &self.kind {
+            AstErrorKind::NoMatchingMacroRule(e) | AstErrorKind::MacroRecursionLimit(e) => Some(e),
+            AstErrorKind::UnexpectedToken(tk) => tk.expansion.as_ref(),
+            _ => None,
+        };
+        expansion.map(|e| e.trace()).unwrap_or_default()
+    }
+}
+
 impl AST {
     #[inline]
     pub fn is_binexpr(&self) -> bool {
@@ -183,10 +457,35 @@ impl AST {
     pub fn is_blockexpr(&self) -> bool {
         matches!(self, Self::Block(_))
     }
+    ///Whether the statement gives a value, to be returned when it ends a function body. Variable
+    ///declarations, ifs without an else and the jumps out of the body don't
+    pub fn gives_value(&self) -> bool {
+        !matches!(
+            self,
+            Self::VarDecl { .. }
+                | Self::If {
+                    elseblock: None,
+                    ..
+                }
+                | Self::Return(_)
+                | Self::Break
+                | Self::Panic(_)
+        )
+    }
 }
 
 impl Program {
     pub fn body(&self) -> &VecDeque<AST> {
         &self.body
     }
+
+    ///Where each statement of the body starts, in the same order
+    pub fn spans(&self) -> &VecDeque<Span> {
+        &self.spans
+    }
+
+    ///The statements of the body along where each one starts
+    pub fn statements(&self) -> impl DoubleEndedIterator<Item = (&AST, Span)> {
+        self.body.iter().zip(self.spans.iter().copied())
+    }
 }
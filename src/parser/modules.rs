@@ -0,0 +1,64 @@
+use crate::{expect, tokenizer::Reserved};
+
+use super::{AST, AstError, AstErrorKind, AstResult, Parser, Token, TokenKind};
+
+impl Parser {
+    ///Parses an import, such as import { area, Rect } from "./geometry";. The 'import' keyword
+    ///must already be eaten
+    pub fn parse_import(&mut self) -> AstResult {
+        expect!(self, TokenKind::OpenBrace)?;
+        let mut names = Vec::new();
+        loop {
+            let tk = self.eat()?;
+            match tk.kind {
+                TokenKind::CloseBrace => break,
+                TokenKind::Identifier(name) => {
+                    names.push(name);
+                    if let TokenKind::CloseBrace =
+                        expect!(self, TokenKind::Comma | TokenKind::CloseBrace)?.kind
+                    {
+                        break;
+                    }
+                }
+                _ => {
+                    return Err(AstError {
+                        line: tk.line,
+                        column: tk.column,
+                        kind: AstErrorKind::UnexpectedToken(tk),
+                    });
+                }
+            }
+        }
+        //`from` is only a keyword here, so it can still name variables
+        let tk = self.eat()?;
+        if !matches!(&tk.kind, TokenKind::Identifier(s) if s == "from") {
+            return Err(AstError {
+                line: tk.line,
+                column: tk.column,
+                kind: AstErrorKind::UnexpectedToken(tk),
+            });
+        }
+        let Token {
+            kind: TokenKind::Str(path),
+            ..
+        } = expect!(self, TokenKind::Str(_))?
+        else {
+            unreachable!();
+        };
+        expect!(self, TokenKind::SemiColon)?;
+        Ok(AST::Import { names, path })
+    }
+    ///Parses the declaration after an 'export' keyword, which must already be eaten
+    pub fn parse_export(&mut self) -> AstResult {
+        let tk = self.eat()?;
+        match tk.kind {
+            TokenKind::Reserved(Reserved::Function | Reserved::Struct | Reserved::Enum)
+            | TokenKind::At => Ok(AST::Export(Box::new(self.parse_global_scope(tk)?))),
+            _ => Err(AstError {
+                line: tk.line,
+                column: tk.column,
+                kind: AstErrorKind::UnexpectedToken(tk),
+            }),
+        }
+    }
+}
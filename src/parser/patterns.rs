@@ -0,0 +1,364 @@
+use std::fmt::Display;
+
+use crate::expect;
+
+use super::{
+    AstError, AstErrorKind, KeyPatternPair, Operator, Parser, Pattern, Token, TokenKind,
+    VariantPattern,
+};
+
+impl Parser {
+    ///Parses a literal pattern, such as 5, -5 or 2.5. The given token is its first one
+    fn parse_literal_pattern(&mut self, tk: Token) -> Result<Pattern, AstError> {
+        let (negative, tk) = if let TokenKind::Operator(Operator::Sub(false)) = tk.kind {
+            (true, self.eat()?)
+        } else {
+            (false, tk)
+        };
+        match tk.kind {
+            TokenKind::Int(_) => {
+                let i = i128::from(Self::parse_int_token(tk)?);
+                Ok(Pattern::Int(if negative { -i } else { i }))
+            }
+            TokenKind::Float(f) => {
+                let f: f64 = f.parse().unwrap();
+                Ok(Pattern::Float(if negative { -f } else { f }))
+            }
+            _ => Err(AstError {
+                line: tk.line,
+                column: tk.column,
+                kind: AstErrorKind::UnexpectedToken(tk),
+            }),
+        }
+    }
+    ///Parses the fields of a struct-like variant pattern, such as { w, h: 0.0, .. }. The '{' must
+    ///already be eaten
+    fn parse_struct_pattern_fields(&mut self) -> Result<VariantPattern, AstError> {
+        let mut fields = Vec::new();
+        let mut rest = false;
+        loop {
+            let tk = self.eat()?;
+            match tk.kind {
+                TokenKind::CloseBrace => break,
+                TokenKind::DotDot => {
+                    rest = true;
+                    expect!(self, TokenKind::CloseBrace)?;
+                    break;
+                }
+                //true and false are values, so a field can't be bound to them by shorthand
+                TokenKind::Identifier(ref key) if matches!(key.as_str(), "true" | "false") => {
+                    if !matches!(
+                        self.peek(),
+                        Some(Token {
+                            kind: TokenKind::Colon,
+                            ..
+                        })
+                    ) {
+                        return Err(AstError {
+                            line: tk.line,
+                            column: tk.column,
+                            kind: AstErrorKind::UnexpectedToken(tk),
+                        });
+                    }
+                    let TokenKind::Identifier(key) = tk.kind else {
+                        unreachable!()
+                    };
+                    self.eat()?;
+                    let pattern = self.parse_pattern()?;
+                    fields.push(KeyPatternPair { key, pattern });
+                    if let TokenKind::CloseBrace =
+                        expect!(self, TokenKind::Comma | TokenKind::CloseBrace)?.kind
+                    {
+                        break;
+                    }
+                }
+                TokenKind::Identifier(key) => {
+                    let pattern = if let Some(Token {
+                        kind: TokenKind::Colon,
+                        ..
+                    }) = self.peek()
+                    {
+                        self.eat()?;
+                        self.parse_pattern()?
+                    } else {
+                        Pattern::Binding(key.clone())
+                    };
+                    fields.push(KeyPatternPair { key, pattern });
+                    if let TokenKind::CloseBrace =
+                        expect!(self, TokenKind::Comma | TokenKind::CloseBrace)?.kind
+                    {
+                        break;
+                    }
+                }
+                _ => {
+                    return Err(AstError {
+                        line: tk.line,
+                        column: tk.column,
+                        kind: AstErrorKind::UnexpectedToken(tk),
+                    });
+                }
+            }
+        }
+        Ok(VariantPattern::Struct { fields, rest })
+    }
+    ///Parses a list of patterns separated by ',' until the closing token, such as the (r, _) of a
+    ///tuple variant. The opening token must already be eaten. Also tells if the list had a
+    ///trailing ',', which makes (a,) a tuple instead of a parenthesized pattern
+    fn parse_pattern_list(&mut self, close: TokenKind) -> Result<(Vec<Pattern>, bool), AstError> {
+        let mut patterns = Vec::new();
+        let mut trailing = false;
+        loop {
+            if self.peek().is_some_and(|tk| tk.kind == close) {
+                self.eat()?;
+                break;
+            }
+            patterns.push(self.parse_pattern()?);
+            let tk = self.eat()?;
+            if tk.kind == close {
+                trailing = false;
+                break;
+            } else if tk.kind != TokenKind::Comma {
+                return Err(AstError {
+                    line: tk.line,
+                    column: tk.column,
+                    kind: AstErrorKind::UnexpectedToken(tk),
+                });
+            }
+            trailing = true;
+        }
+        Ok((patterns, trailing))
+    }
+    ///Eats a pattern, such as _, x, true, 5, 1..=9, (a, b), [a, b], Some(x), None, Ok(x), Err(e),
+    ///Point { x, y: py }, Shape::Circle(r) or Shape::Rect { w, .. }
+    pub fn parse_pattern(&mut self) -> Result<Pattern, AstError> {
+        let tk = self.eat()?;
+        match tk.kind {
+            TokenKind::Identifier(name) if name == "_" => Ok(Pattern::Wildcard),
+            TokenKind::Identifier(name) if name == "None" => Ok(Pattern::None),
+            TokenKind::Identifier(name) if matches!(name.as_str(), "true" | "false") => {
+                Ok(Pattern::Bool(name == "true"))
+            }
+            TokenKind::Identifier(name)
+                if matches!(name.as_str(), "Some" | "Ok" | "Err")
+                    && matches!(
+                        self.peek(),
+                        Some(Token {
+                            kind: TokenKind::OpenParen,
+                            ..
+                        })
+                    ) =>
+            {
+                self.eat()?;
+                let inner = Box::new(self.parse_pattern()?);
+                expect!(self, TokenKind::CloseParen)?;
+                Ok(match name.as_str() {
+                    "Some" => Pattern::Some(inner),
+                    "Ok" => Pattern::Ok(inner),
+                    _ => Pattern::Err(inner),
+                })
+            }
+            TokenKind::OpenParen => {
+                let (mut patterns, trailing) = self.parse_pattern_list(TokenKind::CloseParen)?;
+                if patterns.len() == 1 && !trailing {
+                    Ok(patterns.remove(0))
+                } else {
+                    Ok(Pattern::Tuple(patterns))
+                }
+            }
+            TokenKind::OpenBracket => Ok(Pattern::Array(
+                self.parse_pattern_list(TokenKind::CloseBracket)?.0,
+            )),
+            TokenKind::Identifier(name)
+                if matches!(
+                    self.peek(),
+                    Some(Token {
+                        kind: TokenKind::OpenBrace,
+                        ..
+                    })
+                ) =>
+            {
+                self.eat()?;
+                let VariantPattern::Struct { fields, rest } = self.parse_struct_pattern_fields()?
+                else {
+                    unreachable!()
+                };
+                Ok(Pattern::Struct { name, fields, rest })
+            }
+            TokenKind::Identifier(name) => {
+                if !matches!(
+                    self.peek(),
+                    Some(Token {
+                        kind: TokenKind::DoubleColon,
+                        ..
+                    })
+                ) {
+                    return Ok(Pattern::Binding(name));
+                }
+                self.eat()?;
+                let Token {
+                    kind: TokenKind::Identifier(variant),
+                    ..
+                } = expect!(self, TokenKind::Identifier(_))?
+                else {
+                    unreachable!()
+                };
+                let fields = match self.peek() {
+                    Some(Token {
+                        kind: TokenKind::OpenParen,
+                        ..
+                    }) => {
+                        self.eat()?;
+                        VariantPattern::Tuple(self.parse_pattern_list(TokenKind::CloseParen)?.0)
+                    }
+                    Some(Token {
+                        kind: TokenKind::OpenBrace,
+                        ..
+                    }) => {
+                        self.eat()?;
+                        self.parse_struct_pattern_fields()?
+                    }
+                    _ => VariantPattern::Unit,
+                };
+                Ok(Pattern::Variant {
+                    name,
+                    variant,
+                    fields,
+                })
+            }
+            _ => {
+                let start = self.parse_literal_pattern(tk)?;
+                let inclusive = match self.peek() {
+                    Some(Token {
+                        kind: TokenKind::DotDot,
+                        ..
+                    }) => false,
+                    Some(Token {
+                        kind: TokenKind::DotDotEq,
+                        ..
+                    }) => true,
+                    _ => return Ok(start),
+                };
+                self.eat()?;
+                let tk = self.eat()?;
+                let end = self.parse_literal_pattern(tk)?;
+                Ok(Pattern::Range {
+                    start: Box::new(start),
+                    end: Box::new(end),
+                    inclusive,
+                })
+            }
+        }
+    }
+}
+
+impl Pattern {
+    ///The names of the variables bound by the pattern
+    pub fn bindings(&self) -> Vec<&String> {
+        self.binding_patterns()
+            .into_iter()
+            .map(|pattern| match pattern {
+                Self::Binding(name) => name,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    ///The Binding patterns inside the pattern, in the order they were written
+    pub fn binding_patterns(&self) -> Vec<&Pattern> {
+        let mut out = Vec::new();
+        self.collect_bindings(&mut out);
+        out
+    }
+
+    fn collect_bindings<'a>(&'a self, out: &mut Vec<&'a Pattern>) {
+        match self {
+            Self::Binding(_) => out.push(self),
+            Self::Variant {
+                fields: VariantPattern::Tuple(patterns),
+                ..
+            }
+            | Self::Tuple(patterns)
+            | Self::Array(patterns) => {
+                for pattern in patterns {
+                    pattern.collect_bindings(out);
+                }
+            }
+            Self::Variant {
+                fields: VariantPattern::Struct { fields, .. },
+                ..
+            }
+            | Self::Struct { fields, .. } => {
+                for field in fields {
+                    field.pattern.collect_bindings(out);
+                }
+            }
+            Self::Some(pattern) | Self::Ok(pattern) | Self::Err(pattern) => {
+                pattern.collect_bindings(out)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn fmt_list(patterns: &[Pattern]) -> String {
+    patterns
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn fmt_fields(fields: &[KeyPatternPair], rest: bool) -> String {
+    let mut fields = fields
+        .iter()
+        .map(|field| match &field.pattern {
+            Pattern::Binding(name) if *name == field.key => name.clone(),
+            pattern => format!("{}: {pattern}", field.key),
+        })
+        .collect::<Vec<_>>();
+    if rest {
+        fields.push("..".to_string());
+    }
+    format!("{{ {} }}", fields.join(", "))
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Wildcard => write!(f, "_"),
+            Self::Binding(name) => write!(f, "{name}"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Int(i) => write!(f, "{i}"),
+            Self::Float(fl) => write!(f, "{fl:?}"),
+            Self::Range {
+                start,
+                end,
+                inclusive,
+            } => write!(f, "{start}..{}{end}", if *inclusive { "=" } else { "" }),
+            Self::Variant {
+                name,
+                variant,
+                fields,
+            } => {
+                write!(f, "{name}::{variant}")?;
+                match fields {
+                    VariantPattern::Unit => Ok(()),
+                    VariantPattern::Tuple(patterns) => write!(f, "({})", fmt_list(patterns)),
+                    VariantPattern::Struct { fields, rest } => {
+                        write!(f, " {}", fmt_fields(fields, *rest))
+                    }
+                }
+            }
+            Self::Struct { name, fields, rest } => {
+                write!(f, "{name} {}", fmt_fields(fields, *rest))
+            }
+            Self::Tuple(patterns) if patterns.len() == 1 => write!(f, "({},)", patterns[0]),
+            Self::Tuple(patterns) => write!(f, "({})", fmt_list(patterns)),
+            Self::Array(patterns) => write!(f, "[{}]", fmt_list(patterns)),
+            Self::Some(pattern) => write!(f, "Some({pattern})"),
+            Self::None => write!(f, "None"),
+            Self::Ok(pattern) => write!(f, "Ok({pattern})"),
+            Self::Err(pattern) => write!(f, "Err({pattern})"),
+        }
+    }
+}
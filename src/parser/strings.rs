@@ -0,0 +1,80 @@
+use crate::expect;
+
+use super::{
+    AST, AstError, AstErrorKind, AstResult, Parser, ParsingCondition, StringPart, Token, TokenKind,
+};
+
+impl Parser {
+    ///Parses the content of a string literal. Strings with `{expr}` or `{expr:?}` in them are
+    ///interpolated, and `{{` and `}}` are a brace. line and column are the ones of the literal,
+    ///where errors inside of its expressions are reported
+    pub fn parse_string(content: String, line: usize, column: usize) -> AstResult {
+        let error = || AstError {
+            line,
+            column,
+            kind: AstErrorKind::InvalidInterpolation(content.clone()),
+        };
+        let chars = content.chars().collect::<Vec<_>>();
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut idx = 0;
+        while idx < chars.len() {
+            match (chars[idx], chars.get(idx + 1)) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    literal.push(chars[idx]);
+                    idx += 2;
+                }
+                ('}', _) => return Err(error()),
+                ('{', _) => {
+                    //the expression may have braces of its own, as in {Point { x: 1 }:?}
+                    let mut depth = 0;
+                    let end = (idx + 1..chars.len())
+                        .find(|&end| {
+                            match chars[end] {
+                                '{' => depth += 1,
+                                '}' if depth == 0 => return true,
+                                '}' => depth -= 1,
+                                _ => {}
+                            }
+                            false
+                        })
+                        .ok_or_else(error)?;
+                    let mut source = chars[idx + 1..end].iter().collect::<String>();
+                    let debug = source.ends_with(":?");
+                    if debug {
+                        source.truncate(source.len() - 2);
+                    }
+                    if !literal.is_empty() {
+                        parts.push(StringPart::Literal(std::mem::take(&mut literal)));
+                    }
+                    let expr = Self::parse_interpolated_expr(&source).map_err(|_| error())?;
+                    parts.push(StringPart::Expr {
+                        expr: Box::new(expr),
+                        debug,
+                    });
+                    idx = end + 1;
+                }
+                (c, _) => {
+                    literal.push(c);
+                    idx += 1;
+                }
+            }
+        }
+        if parts.is_empty() {
+            return Ok(AST::Str(content));
+        }
+        if !literal.is_empty() {
+            parts.push(StringPart::Literal(literal));
+        }
+        Ok(AST::Interpolated(parts))
+    }
+
+    ///Parses the expression between the braces of an interpolation, which must be all there is
+    fn parse_interpolated_expr(source: &str) -> AstResult {
+        let mut parser = Self::from_content(source);
+        let tk = parser.eat()?;
+        let expr = parser.parse_expr(tk, ParsingCondition::None)?;
+        expect!(parser, TokenKind::EOF)?;
+        Ok(expr)
+    }
+}
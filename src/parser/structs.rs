@@ -3,8 +3,8 @@ use std::collections::VecDeque;
 use crate::expect;
 
 use super::{
-    AST, AstError, AstErrorKind, KeyExprPair, KeyTypePair, Parser, ParsingCondition, Token,
-    TokenKind,
+    AST, AstError, AstErrorKind, AttributeTarget, KeyExprPair, KeyTypePair, Parser,
+    ParsingCondition, Token, TokenKind, TypeAst,
 };
 
 impl Parser {
@@ -24,6 +24,8 @@ impl Parser {
         }
         let mut out = VecDeque::new();
         loop {
+            let attributes = self.parse_optional_attributes()?;
+            Self::check_attributes(&attributes, AttributeTarget::Field)?;
             let Token {
                 kind: TokenKind::Identifier(field_name),
                 ..
@@ -36,6 +38,7 @@ impl Parser {
             out.push_back(KeyTypePair {
                 key: field_name,
                 kindof: field_type,
+                attributes,
             });
             expect!(self, TokenKind::SemiColon)?;
             if let Some(Token {
@@ -62,11 +65,15 @@ impl Parser {
         else {
             unreachable!()
         };
+        let mut generics = self.parse_optional_generic_params()?;
+        self.parse_where_clause(&mut generics)?;
         expect!(self, TokenKind::OpenBrace)?;
         let params = self.parse_struct_fields_types()?;
         Ok(AST::Struct {
             name: structname,
+            generics,
             fields: params,
+            attributes: Vec::new(),
         })
     }
     ///Parses an struct expression fields.
@@ -121,7 +128,11 @@ impl Parser {
         Ok(fields)
     }
     ///Parses an struct expression itself
-    pub fn parse_struct_expr(&mut self, structname: String) -> Result<AST, AstError> {
+    pub fn parse_struct_expr(
+        &mut self,
+        structname: String,
+        type_args: Vec<TypeAst>,
+    ) -> Result<AST, AstError> {
         if let Some(Token {
             kind: TokenKind::CloseBrace,
             ..
@@ -130,12 +141,14 @@ impl Parser {
             self.eat()?;
             return Ok(AST::StructExpr {
                 name: structname,
+                type_args,
                 fields: VecDeque::new(),
             });
         };
         let fields = self.parse_struct_field_values()?;
         Ok(AST::StructExpr {
             name: structname,
+            type_args,
             fields,
         })
     }
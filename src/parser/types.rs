@@ -1,42 +1,93 @@
 use crate::expect;
 
-use super::{AstError, AstErrorKind, Parser, Token, TokenKind, TypeAst};
+use super::{AstError, AstErrorKind, GenericParam, Operator, Parser, Token, TokenKind, TypeAst};
 
 impl Parser {
+    ///Parses the types after a '(', which are the parameters of a function type when a ':'
+    ///follows the ')', such as (int, float): int. Otherwise they are a tuple type, such as
+    ///(int, float) or (int,), or a single parenthesized type as (int)
     pub fn get_f_type(&mut self) -> Result<TypeAst, AstError> {
         let mut vec = Vec::new();
+        let mut trailing = false;
         loop {
-            let current = self.eat()?;
-            if current.kind == TokenKind::CloseParen {
+            if let Some(Token {
+                kind: TokenKind::CloseParen,
+                ..
+            }) = self.peek()
+            {
+                self.eat()?;
+                break;
+            }
+            vec.push(self.get_type()?);
+            trailing = false;
+            if let TokenKind::CloseParen =
+                expect!(self, TokenKind::Comma | TokenKind::CloseParen)?.kind
+            {
                 break;
-            } else {
-                match current.kind {
-                    TokenKind::OpenParen => vec.push(self.get_f_type()?),
-                    TokenKind::Identifier(c) => vec.push(TypeAst::Primitive(c)),
-                    TokenKind::Comma => {}
-                    _ => {
-                        return Err(AstError {
-                            line: current.line,
-                            column: current.column,
-                            kind: AstErrorKind::UnexpectedToken(current),
-                        });
-                    }
-                }
             }
+            trailing = true;
+        }
+        //there is no empty tuple, so () is always the start of a function type
+        if vec.is_empty()
+            || matches!(
+                self.peek(),
+                Some(Token {
+                    kind: TokenKind::Colon,
+                    ..
+                })
+            )
+        {
+            expect!(self, TokenKind::Colon)?;
+            return Ok(TypeAst::Function {
+                params: vec,
+                return_type: Box::new(self.get_type()?),
+            });
+        }
+        if vec.len() == 1 && !trailing {
+            Ok(vec.remove(0))
+        } else {
+            Ok(TypeAst::Tuple(vec))
         }
-        expect!(self, TokenKind::Colon)?;
-        Ok(TypeAst::Function {
-            params: vec,
-            return_type: Box::new(self.get_type()?),
-        })
     }
     ///Eats the current token and return it's abstract type. Its supposed to be used only with type
     ///Tokens
     pub fn get_type(&mut self) -> Result<TypeAst, AstError> {
+        let mut t = self.get_non_optional_type()?;
+        //int?? tokenizes its end as the '??' operator
+        while let Some(Token {
+            kind: kind @ (TokenKind::Question | TokenKind::Operator(Operator::Coalesce)),
+            ..
+        }) = self.peek()
+        {
+            let depth = if *kind == TokenKind::Question { 1 } else { 2 };
+            self.eat()?;
+            for _ in 0..depth {
+                t = TypeAst::Option(Box::new(t));
+            }
+        }
+        Ok(t)
+    }
+
+    ///Parses a type without the '?' that may follow it
+    pub(crate) fn get_non_optional_type(&mut self) -> Result<TypeAst, AstError> {
         let current = self.eat()?;
         match current.kind {
             TokenKind::OpenParen => self.get_f_type(),
-            TokenKind::Identifier(t) => Ok(TypeAst::Primitive(t)),
+            TokenKind::Identifier(t) => {
+                if let Some(Token {
+                    kind: TokenKind::Operator(Operator::Lt(false)),
+                    ..
+                }) = self.peek()
+                {
+                    self.eat()?;
+                    Ok(TypeAst::Generic {
+                        name: t,
+                        args: self.parse_type_args()?,
+                    })
+                } else {
+                    Ok(TypeAst::Primitive(t))
+                }
+            }
             _ => Err(AstError {
                 line: current.line,
                 column: current.column,
@@ -44,4 +95,204 @@ impl Parser {
             }),
         }
     }
+
+    ///Eats the '>' closing a list of generics. As Pair<Pair<int>> tokenizes its end as '>>', the
+    ///token is split and only its first half is eaten
+    fn eat_closing_angle(&mut self) -> Result<(), AstError> {
+        let front = self.tokens.front_mut();
+        match front {
+            Some(Token {
+                kind: TokenKind::Operator(Operator::Gt(false)),
+                ..
+            }) => {
+                self.eat()?;
+            }
+            Some(Token {
+                kind: kind @ TokenKind::Operator(Operator::BitRight(false)),
+                ..
+            }) => *kind = TokenKind::Operator(Operator::Gt(false)),
+            Some(Token {
+                kind: kind @ TokenKind::Operator(Operator::Gt(true)),
+                ..
+            }) => *kind = TokenKind::Operator(Operator::Eq(false)),
+            _ => {
+                expect!(self, TokenKind::Operator(Operator::Gt(false)))?;
+            }
+        }
+        Ok(())
+    }
+
+    ///Parses the types given to a generic, such as <int, float>. The '<' must already be eaten
+    pub fn parse_type_args(&mut self) -> Result<Vec<TypeAst>, AstError> {
+        let mut args = vec![self.get_type()?];
+        while let Some(Token {
+            kind: TokenKind::Comma,
+            ..
+        }) = self.peek()
+        {
+            self.eat()?;
+            args.push(self.get_type()?);
+        }
+        self.eat_closing_angle()?;
+        Ok(args)
+    }
+
+    ///Checks without eating anything if the tokens after an identifier are the generic arguments
+    ///of a call, struct or variant expression, like the <int> in id<int>(5), and not a comparison.
+    ///Returns the kind of the token after the closing '>' if so
+    pub(crate) fn peek_type_args(&self) -> Option<&TokenKind> {
+        if !matches!(
+            self.peek(),
+            Some(Token {
+                kind: TokenKind::Operator(Operator::Lt(false)),
+                ..
+            })
+        ) {
+            return None;
+        }
+        let mut depth = 0isize;
+        for (idx, tk) in self.tokens.iter().enumerate() {
+            match &tk.kind {
+                TokenKind::Operator(Operator::Lt(false)) => depth += 1,
+                TokenKind::Operator(Operator::Gt(false)) => depth -= 1,
+                TokenKind::Operator(Operator::BitRight(false)) => depth -= 2,
+                TokenKind::Identifier(_)
+                | TokenKind::Comma
+                | TokenKind::Colon
+                | TokenKind::OpenParen
+                | TokenKind::CloseParen => {}
+                _ => return None,
+            }
+            if depth == 0 {
+                return self.tokens.get(idx + 1).map(|tk| &tk.kind).filter(|kind| {
+                    matches!(
+                        kind,
+                        TokenKind::OpenParen | TokenKind::OpenBrace | TokenKind::DoubleColon
+                    )
+                });
+            } else if depth < 0 {
+                return None;
+            }
+        }
+        None
+    }
+
+    ///Parses the generic parameters of a declaration, such as <T, U>. The '<' must already be
+    ///eaten
+    pub fn parse_generic_params(&mut self) -> Result<Vec<GenericParam>, AstError> {
+        let mut generics = Vec::new();
+        loop {
+            let Token {
+                kind: TokenKind::Identifier(name),
+                ..
+            } = expect!(self, TokenKind::Identifier(_))?
+            else {
+                unreachable!();
+            };
+            generics.push(GenericParam {
+                name,
+                bounds: Vec::new(),
+            });
+            if let Some(Token {
+                kind: TokenKind::Comma,
+                ..
+            }) = self.peek()
+            {
+                self.eat()?;
+            } else {
+                break;
+            }
+        }
+        self.eat_closing_angle()?;
+        Ok(generics)
+    }
+
+    ///Parses generic parameters if the next token is '<'
+    pub(crate) fn parse_optional_generic_params(&mut self) -> Result<Vec<GenericParam>, AstError> {
+        if let Some(Token {
+            kind: TokenKind::Operator(Operator::Lt(false)),
+            ..
+        }) = self.peek()
+        {
+            self.eat()?;
+            self.parse_generic_params()
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    ///Parses a where clause if the next token is `where`, adding its bounds to the generics they
+    ///refer to. Such as
+    ///where T extends Numeric + Integer, U extends Numeric
+    pub fn parse_where_clause(&mut self, generics: &mut [GenericParam]) -> Result<(), AstError> {
+        if !matches!(self.peek(), Some(Token { kind: TokenKind::Identifier(w), .. }) if w == "where")
+        {
+            return Ok(());
+        }
+        self.eat()?;
+        loop {
+            let Token {
+                kind: TokenKind::Identifier(param),
+                line,
+                column,
+                ..
+            } = expect!(self, TokenKind::Identifier(_))?
+            else {
+                unreachable!();
+            };
+            let extends = expect!(self, TokenKind::Identifier(_))?;
+            if !matches!(&extends.kind, TokenKind::Identifier(e) if e == "extends") {
+                return Err(AstError {
+                    line: extends.line,
+                    column: extends.column,
+                    kind: AstErrorKind::UnexpectedToken(extends),
+                });
+            }
+            let mut bounds = Vec::new();
+            loop {
+                let Token {
+                    kind: TokenKind::Identifier(bound),
+                    ..
+                } = expect!(self, TokenKind::Identifier(_))?
+                else {
+                    unreachable!();
+                };
+                bounds.push(bound);
+                if let Some(Token {
+                    kind: TokenKind::Operator(Operator::Add(false)),
+                    ..
+                }) = self.peek()
+                {
+                    self.eat()?;
+                } else {
+                    break;
+                }
+            }
+            let Some(generic) = generics.iter_mut().find(|g| g.name == param) else {
+                return Err(AstError {
+                    line,
+                    column,
+                    kind: AstErrorKind::UnknownGenericParam(param),
+                });
+            };
+            generic.bounds.extend(bounds);
+            if let Some(Token {
+                kind: TokenKind::Comma | TokenKind::SemiColon,
+                ..
+            }) = self.peek()
+            {
+                self.eat()?;
+            }
+            if !matches!(
+                self.peek(),
+                Some(Token {
+                    kind: TokenKind::Identifier(_),
+                    ..
+                })
+            ) {
+                break;
+            }
+        }
+        Ok(())
+    }
 }
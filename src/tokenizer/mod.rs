@@ -1,6 +1,6 @@
 mod operator;
 pub use operator::Operator;
-use std::collections::VecDeque;
+use std::{collections::VecDeque, rc::Rc};
 macro_rules! token {
     ($arg:expr, $actual:expr, $line:expr, $column:expr) => {{
         Token {
@@ -8,6 +8,7 @@ macro_rules! token {
             start: $actual,
             line: $line,
             column: $column,
+            expansion: None,
         }
     }};
 }
@@ -26,6 +27,14 @@ pub enum Reserved {
     Loop,
     While,
     For,
+    Enum,
+    Match,
+    Import,
+    Export,
+    As,
+    Return,
+    Break,
+    Panic,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -34,31 +43,73 @@ pub enum TokenKind {
     CloseParen,
     OpenBrace,
     CloseBrace,
+    OpenBracket,
+    CloseBracket,
     Colon,
     SemiColon,
     Comma,
+    Dot,
+    ///`..`, the exclusive range of patterns
+    DotDot,
+    ///`..=`, the inclusive range of patterns
+    DotDotEq,
+    ///`::`, separates an enum from its variant
+    DoubleColon,
 
     Operator(Operator),
     Reserved(Reserved),
     Identifier(String),
     Int(String),
     Float(String),
+    ///A string literal, without its quotes. Escapes are kept as written
+    Str(String),
 
     FnArrow,
+    Dollar,
+    ///`@`, starts an attribute
+    At,
+    ///`?`, as in the option type int?
+    Question,
+    ///`?.`, accesses a field or element of an option
+    QuestionDot,
 
     EOF,
 }
+///A macro invocation that produced a token. Nested invocations are chained through `parent`, so
+///walking it gives the whole expansion trace, innermost first
+#[derive(Debug)]
+pub struct Expansion {
+    pub name: String,
+    pub line: usize,
+    pub column: usize,
+    pub parent: Option<Rc<Expansion>>,
+}
 #[derive(Debug, Clone)]
 pub struct Token {
     pub kind: TokenKind,
     pub start: usize,
     pub line: usize,
     pub column: usize,
+    ///The macro expansion this token was produced by, if any
+    pub expansion: Option<Rc<Expansion>>,
+}
+
+impl Expansion {
+    ///Returns every invocation from this one up to the outermost
+    pub fn trace(&self) -> Vec<&Expansion> {
+        let mut out = vec![self];
+        let mut current = self;
+        while let Some(parent) = &current.parent {
+            out.push(parent);
+            current = parent;
+        }
+        out
+    }
 }
 fn is_operator(c: &char) -> bool {
     matches!(
         c,
-        '+' | '-' | '*' | '/' | '%' | '&' | '|' | '>' | '<' | '!' | '='
+        '+' | '-' | '*' | '/' | '%' | '&' | '|' | '^' | '>' | '<' | '!' | '='
     )
 }
 
@@ -132,7 +183,7 @@ fn check_operator(c: &char, chars: &[char], idx: usize) -> Option<(Operator, usi
                         Operator::Or(false)
                     }
                 }
-                _ => Operator::Or(flag),
+                _ => Operator::BitOr(flag),
             },
             '>' => match next {
                 '>' => {
@@ -175,7 +226,7 @@ fn check_operator(c: &char, chars: &[char], idx: usize) -> Option<(Operator, usi
             '=' => Operator::Eq(false),
             '!' => Operator::Bang(false),
             '&' => Operator::BitAnd(false),
-            '|' => Operator::Or(false),
+            '|' => Operator::BitOr(false),
             '>' => Operator::Gt(false),
             '<' => Operator::Lt(false),
             '^' => Operator::Xor(false),
@@ -188,10 +239,10 @@ fn check_operator(c: &char, chars: &[char], idx: usize) -> Option<(Operator, usi
 
 //Checks if the current char is a symbol initializer, if so, gets all symbol chars and return the corresponding token and amount of chars walked by
 fn check_symbol(c: &char, chars: &[char], mut idx: usize) -> Option<(TokenKind, usize)> {
-    if c.is_alphabetic() {
+    if c.is_alphabetic() || matches!(c, '_') {
         let mut buffer = String::new();
         while let Some(c) = chars.get(idx) {
-            if c.is_alphanumeric() {
+            if c.is_alphanumeric() || matches!(c, '_') {
                 buffer.push(*c);
                 idx += 1;
             } else {
@@ -213,6 +264,14 @@ fn check_symbol(c: &char, chars: &[char], mut idx: usize) -> Option<(TokenKind,
                 "loop" => TokenKind::Reserved(Reserved::Loop),
                 "while" => TokenKind::Reserved(Reserved::While),
                 "for" => TokenKind::Reserved(Reserved::For),
+                "enum" => TokenKind::Reserved(Reserved::Enum),
+                "match" => TokenKind::Reserved(Reserved::Match),
+                "import" => TokenKind::Reserved(Reserved::Import),
+                "export" => TokenKind::Reserved(Reserved::Export),
+                "as" => TokenKind::Reserved(Reserved::As),
+                "return" => TokenKind::Reserved(Reserved::Return),
+                "break" => TokenKind::Reserved(Reserved::Break),
+                "panic" => TokenKind::Reserved(Reserved::Panic),
                 _ => TokenKind::Identifier(buffer),
             },
             len,
@@ -222,6 +281,27 @@ fn check_symbol(c: &char, chars: &[char], mut idx: usize) -> Option<(TokenKind,
     }
 }
 
+///Checks if the current char opens a string literal, if so, gets everything until its closing
+///quote. An unclosed string takes the rest of the content. returns the corresponding token and the
+///amount of chars walked by
+fn check_string(c: &char, chars: &[char], idx: usize) -> Option<(TokenKind, usize)> {
+    if *c != '"' {
+        return None;
+    }
+    let mut buffer = String::new();
+    let mut escaped = false;
+    let mut end = idx + 1;
+    while let Some(c) = chars.get(end) {
+        end += 1;
+        if *c == '"' && !escaped {
+            break;
+        }
+        escaped = *c == '\\' && !escaped;
+        buffer.push(*c);
+    }
+    Some((TokenKind::Str(buffer), end - idx))
+}
+
 ///Checks if the current char is numeric, if so, gets all the numeric ones until none is found anymore. returns the corresponding token and the amount of chars walked by
 fn check_numeric(c: &char, chars: &[char], idx: usize) -> Option<(TokenKind, usize)> {
     if matches!(c, '0') {
@@ -252,18 +332,19 @@ fn check_numeric(c: &char, chars: &[char], idx: usize) -> Option<(TokenKind, usi
                     }
                     return Some((TokenKind::Int(buffer), i));
                 }
-                _ => panic!("Invalid Number initializing with 0"), //must implement octa digits 'o' => {}
+                _ => {} //must implement octa digits 'o' => {}
             }
         } else {
             return Some((TokenKind::Int(String::from("0")), 1));
         }
     }
-    if c.is_ascii_digit() || matches!(c, '.') {
+    if c.is_ascii_digit() {
         let mut i = 0;
         let mut dot = false;
         let mut buffer = String::new();
         while let Some(c) = chars.get(idx + i) {
-            if matches!(c, '.') {
+            //a dot not followed by a digit is not part of the number, as in the range 1..5
+            if matches!(c, '.') && chars.get(idx + i + 1).is_some_and(|c| c.is_ascii_digit()) {
                 if dot {
                     panic!("While tokenizing found a number with double dots");
                 }
@@ -323,15 +404,51 @@ pub fn tokenize(content: &str) -> VecDeque<Token> {
                 column += n - 1;
                 i += n - 1;
                 num
+            } else if let Some((string, n)) = check_string(c, &chars, i) {
+                column += n - 1;
+                i += n - 1;
+                string
             } else {
                 match c {
                     '(' => TokenKind::OpenParen,
                     ')' => TokenKind::CloseParen,
                     '{' => TokenKind::OpenBrace,
                     '}' => TokenKind::CloseBrace,
+                    '[' => TokenKind::OpenBracket,
+                    ']' => TokenKind::CloseBracket,
+                    ':' if matches!(chars.get(i + 1), Some(':')) => {
+                        i += 1;
+                        column += 1;
+                        TokenKind::DoubleColon
+                    }
                     ':' => TokenKind::Colon,
+                    '.' if matches!(chars.get(i + 1), Some('.')) => {
+                        let inclusive = matches!(chars.get(i + 2), Some('='));
+                        let n = if inclusive { 2 } else { 1 };
+                        i += n;
+                        column += n;
+                        if inclusive {
+                            TokenKind::DotDotEq
+                        } else {
+                            TokenKind::DotDot
+                        }
+                    }
+                    '.' => TokenKind::Dot,
                     ';' => TokenKind::SemiColon,
                     ',' => TokenKind::Comma,
+                    '$' => TokenKind::Dollar,
+                    '@' => TokenKind::At,
+                    '?' if matches!(chars.get(i + 1), Some('?')) => {
+                        i += 1;
+                        column += 1;
+                        TokenKind::Operator(Operator::Coalesce)
+                    }
+                    '?' if matches!(chars.get(i + 1), Some('.')) => {
+                        i += 1;
+                        column += 1;
+                        TokenKind::QuestionDot
+                    }
+                    '?' => TokenKind::Question,
                     _ => {
                         if let Some((op, n)) = check_operator(c, &chars, i) {
                             i += n - 1;
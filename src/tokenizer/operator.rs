@@ -26,6 +26,8 @@ pub enum Operator {
     BitRight(bool),
 
     Arrow,
+    ///`??`, the value on its left unless it is None
+    Coalesce,
 
     Increment,
     Decrement,
@@ -43,7 +45,7 @@ impl Operator {
             Operator::Xor(false) => 7,
             Operator::Eq(true) | Operator::Bang(true) | Operator::Lt(_) | Operator::Gt(_) => 8,
             Operator::And(false) => 9,
-            Operator::Or(false) => 10,
+            Operator::Or(false) | Operator::Coalesce => 10,
             Operator::Arrow => 11,
             Operator::Add(true)
             | Operator::Sub(true)
@@ -63,7 +65,7 @@ impl Operator {
     }
     pub fn has_eq(&self) -> bool {
         match self {
-            Self::Arrow | Self::Increment | Self::Decrement => false,
+            Self::Arrow | Self::Coalesce | Self::Increment | Self::Decrement => false,
             Self::Add(f)
             | Self::Sub(f)
             | Self::Star(f)
@@ -107,6 +109,7 @@ impl Display for Operator {
                 Self::BitLeft(_) => format!("<<{flag}"),
                 Self::BitRight(_) => format!(">>{flag}"),
                 Self::Arrow => "->".to_string(),
+                Self::Coalesce => "??".to_string(),
                 Self::Increment => "++".to_string(),
                 Self::Decrement => "--".to_string(),
             }
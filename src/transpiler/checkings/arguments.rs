@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+
+use crate::{
+    parser::{AST, FunctionParameter, KeyExprPair},
+    transpiler::{TypeError, checkings::generics::TypeParam, transpiler::BeatriceTranspiler},
+};
+
+///What a call needs to know about the declared function it calls, besides its type
+#[derive(Debug, Clone, Default)]
+pub struct Callee {
+    pub generics: Vec<TypeParam>,
+    pub parameters: Option<Vec<ParamSpec>>,
+}
+
+///What a call needs to know about a parameter to give it its argument
+#[derive(Debug, Clone)]
+pub struct ParamSpec {
+    ///The pattern of the parameter. Only a single name can be given by a named argument
+    pub name: String,
+    pub has_default: bool,
+}
+
+impl From<&FunctionParameter> for ParamSpec {
+    fn from(param: &FunctionParameter) -> Self {
+        Self {
+            name: param.pattern.to_string(),
+            has_default: param.default.is_some(),
+        }
+    }
+}
+
+///Where a call takes the argument of a parameter from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgSource {
+    ///The argument written at that position
+    Positional(usize),
+    ///The named argument written at that position among the named ones
+    Named(usize),
+    ///The argument was left out, so the parameter takes its default value
+    Default,
+}
+
+impl ArgSource {
+    ///The expression written for this argument, if it wasn't left out
+    pub fn arg<'a>(
+        self,
+        args: &'a VecDeque<AST>,
+        named: &'a VecDeque<KeyExprPair>,
+    ) -> Option<&'a AST> {
+        match self {
+            Self::Positional(idx) => Some(&args[idx]),
+            Self::Named(idx) => Some(&named[idx].value),
+            Self::Default => None,
+        }
+    }
+}
+
+impl BeatriceTranspiler {
+    ///The parameters of the declared function with the given name
+    fn parameters_of(&self, name: &str) -> Option<Vec<ParamSpec>> {
+        self.scopes()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.parameters_of(name))
+            .cloned()
+    }
+
+    ///Records the generics and parameters of every global function by its symbol, once the names
+    ///of the program are resolved. Calls find them through the symbol they call, so a variable
+    ///hiding a function never takes its generics or parameters
+    pub(crate) fn define_callees(&mut self) {
+        let callees = self
+            .resolution()
+            .functions()
+            .map(|(id, symbol)| {
+                let callee = Callee {
+                    generics: self.generics_of(&symbol.name),
+                    parameters: self.parameters_of(&symbol.name),
+                };
+                (id, callee)
+            })
+            .collect();
+        self.set_callees(callees);
+    }
+
+    ///Matches the arguments of a call with the amount parameters of the function, giving where
+    ///the value of each parameter comes from. Positional arguments come first, then the named
+    ///ones fill the parameters with their name and the remaining ones take their default value.
+    ///A call without named arguments that gives too many or too few is an arity mismatch
+    pub(crate) fn resolve_call_args(
+        &mut self,
+        call: &AST,
+        amount: usize,
+    ) -> Result<Vec<ArgSource>, TypeError> {
+        let AST::FunctionCall {
+            name,
+            args,
+            named,
+            span,
+            ..
+        } = call
+        else {
+            panic!("This is a bug. Expected to receive a function call");
+        };
+        let specs = self
+            .callee(call)
+            .and_then(|callee| callee.parameters.clone());
+        let specs = specs.unwrap_or_else(|| {
+            //functions held by variables only have types, so they are only called by position
+            (0..amount)
+                .map(|idx| ParamSpec {
+                    name: format!("_${idx}"),
+                    has_default: false,
+                })
+                .collect()
+        });
+        //the amount of arguments to give by position to reach every parameter without a default
+        let needed = specs
+            .iter()
+            .rposition(|spec| !spec.has_default)
+            .map_or(0, |last| last + 1);
+        if args.len() > specs.len() || (named.is_empty() && args.len() < needed) {
+            return Err(TypeError::ArityMismatch {
+                expected: if args.len() > specs.len() {
+                    specs.len()
+                } else {
+                    needed
+                },
+                received: args.len(),
+                span: *span,
+            });
+        }
+        let mut sources = (0..specs.len())
+            .map(|idx| (idx < args.len()).then_some(ArgSource::Positional(idx)))
+            .collect::<Vec<_>>();
+        for (idx, arg) in named.iter().enumerate() {
+            let Some(param) = specs.iter().position(|spec| spec.name == arg.key) else {
+                return Err(TypeError::UnknownArgument {
+                    function: name.clone(),
+                    argument: arg.key.clone(),
+                });
+            };
+            if sources[param].is_some() {
+                return Err(TypeError::DuplicateArgument {
+                    function: name.clone(),
+                    argument: arg.key.clone(),
+                });
+            }
+            sources[param] = Some(ArgSource::Named(idx));
+        }
+        let mut resolved = Vec::with_capacity(sources.len());
+        for (source, spec) in sources.into_iter().zip(&specs) {
+            match source {
+                Some(source) => resolved.push(source),
+                None if spec.has_default => resolved.push(ArgSource::Default),
+                None => {
+                    return Err(TypeError::MissingArgument {
+                        function: name.clone(),
+                        param: spec.name.clone(),
+                    });
+                }
+            }
+        }
+        self.define_call_args(call, resolved.clone());
+        Ok(resolved)
+    }
+}
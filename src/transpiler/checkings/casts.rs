@@ -0,0 +1,88 @@
+//The conversions written with `as`. They go between numeric types only:
+//- to a float, the value is kept as it is, and rounded to single precision for an f32
+//- from a float to an integer, the fractional part is dropped, so 2.7 as int is 2 and -2.7 is -2
+//- to a sized integer, the value wraps around its range as in two's complement, so 300 as u8 is
+//  44 and -1 as u32 is 4294967295. A float is truncated first, and NaN becomes 0
+//- to int, an integer is kept as it is, as it's too wide to wrap. A float wraps around 64 bits as
+//  it does for an u64, so 2^63 as int is -2^63, and NaN and the infinities become 0
+//- to u64, a value wraps around 64 bits as it does for the sized integers. Values past 2^53 are
+//  rounded to the nearest js number
+//In bigint mode, int and u64 wrap around 64 bits as the sized integers do, so a float is truncated
+//first, and NaN and the infinities become 0
+
+use crate::{
+    parser::AST,
+    transpiler::{BeatriceType, Bound, TypeError, transpiler::BeatriceTranspiler},
+};
+
+impl BeatriceTranspiler {
+    ///The numeric type the js number converted from a value of type from to one of type to must
+    ///be adapted to, None when it already is a value of to
+    fn cast_adaptation(&self, from: &BeatriceType, to: &BeatriceType) -> Option<BeatriceType> {
+        let from_integer = self.satisfies(from, Bound::Integer);
+        match to {
+            _ if from == to => None,
+            BeatriceType::Float => None,
+            BeatriceType::TypeVar(_) if self.satisfies(to, Bound::Floating) => None,
+            //a generic integer is emitted as an int, nothing tells which integer it is
+            BeatriceType::TypeVar(_) => (!from_integer).then_some(BeatriceType::Int),
+            BeatriceType::Int => (!from_integer).then(|| to.clone()),
+            //a negative integer given to an u64 wraps
+            BeatriceType::U64 => {
+                let unsigned = from.integer_range().is_some_and(|(min, _)| min >= 0);
+                (!unsigned).then(|| to.clone())
+            }
+            //every integer up to 2^24 is a single precision float
+            BeatriceType::F32 => {
+                let exact = from
+                    .integer_range()
+                    .is_some_and(|(min, max)| min >= -(1 << 24) && max <= 1 << 24);
+                (!exact).then_some(BeatriceType::F32)
+            }
+            _ => {
+                let (min, max) = to.integer_range()?;
+                let fits = from
+                    .integer_range()
+                    .is_some_and(|(fmin, fmax)| fmin >= min && fmax <= max);
+                (!fits).then(|| to.clone())
+            }
+        }
+    }
+
+    ///Generates the type of a cast, which is the type it converts to
+    pub(crate) fn ast_typeof_cast(&mut self, expr: &AST) -> Result<BeatriceType, TypeError> {
+        let AST::Cast {
+            expr: value,
+            kindof,
+        } = expr
+        else {
+            panic!("This is a bug. Expected receiving a Cast");
+        };
+        let from = self.ast_typeof_expression(value)?;
+        let to = self.t_abstract_from_primitive(kindof)?;
+        if from.is_error() {
+            return Ok(to);
+        }
+        //a generic only bound to be Numeric could be either, so nothing tells how to convert to it
+        if !self.satisfies(&from, Bound::Numeric)
+            || !(self.satisfies(&to, Bound::Integer) || self.satisfies(&to, Bound::Floating))
+        {
+            return Err(TypeError::InvalidCast { from, to });
+        }
+        //a literal that fits is already a value of to, as the 2 of 2 as u8
+        if self.literal_fits(value, &to) {
+            return Ok(to);
+        }
+        if self.is_bigint(&from) != self.is_bigint(&to) {
+            self.define_bigint_cast(expr, from, to.clone());
+        } else if self.is_bigint(&from) {
+            //between int and u64, which have the same bits
+            if from != to {
+                self.define_numeric(expr, to.clone());
+            }
+        } else if let Some(numeric) = self.cast_adaptation(&from, &to) {
+            self.define_numeric(expr, numeric);
+        }
+        Ok(to)
+    }
+}
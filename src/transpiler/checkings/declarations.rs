@@ -0,0 +1,157 @@
+//Every global declaration of a program is collected before any body is checked, so functions and
+//types can be used above the line declaring them, as js allows after hoisting. Imports come first,
+//then the types, which only need each other, and then the signatures of the functions. A
+//declaration that fails is still declared, with the Error type for the parts that failed, so its
+//uses don't report it again. A function named as an earlier function or import isn't declared, so
+//the first declaration of a name is the one its uses see.
+
+use std::collections::HashSet;
+
+use crate::{
+    parser::{AST, Program, Span},
+    transpiler::{BeatriceType, transpiler::BeatriceTranspiler},
+};
+
+use super::{arguments::ParamSpec, enums::EnumDef, structs::StructDef};
+
+impl BeatriceTranspiler {
+    ///Declares the imports, types and function signatures of a program, leaving the bodies of its
+    ///functions to be checked. The names of an import that fails are declared as functions of the
+    ///Error type. Returns the positions of the statements declaring a function with a name that was
+    ///already taken, which are left undeclared
+    pub(crate) fn declare_globals(&mut self, program: &Program) -> HashSet<usize> {
+        for (ast, span) in program.statements() {
+            if let AST::Import { names, path } = ast
+                && self
+                    .recovering(Some(span), |t| t.import_names(path, names))
+                    .is_none()
+            {
+                for name in names {
+                    if !self.current_scope().has_function(name) {
+                        self.current_scope_mut()
+                            .define_function(name.clone(), BeatriceType::Error);
+                    }
+                }
+            }
+        }
+        self.declare_types(program.statements());
+        let mut duplicates = HashSet::new();
+        for (idx, (ast, span)) in program.statements().enumerate() {
+            let ast = Self::declaration(ast);
+            if let AST::Function { name, .. } = ast {
+                if self.current_scope().has_function(name) {
+                    duplicates.insert(idx);
+                    continue;
+                }
+                let previous = self.set_span(span);
+                self.declare_function(ast);
+                self.set_span(previous);
+            }
+        }
+        duplicates
+    }
+
+    ///The declaration of a global, which may be exported
+    fn declaration(ast: &AST) -> &AST {
+        match ast {
+            AST::Export(decl) => decl,
+            ast => ast,
+        }
+    }
+
+    ///Declares the signature of a function, so it can be called before its body is checked. A
+    ///function whose generics can't be resolved is declared with the Error type
+    pub(crate) fn declare_function(&mut self, f: &AST) {
+        let AST::Function {
+            name,
+            params,
+            generics,
+            ..
+        } = f
+        else {
+            panic!("This is a bug. Expected to receive a function");
+        };
+        let Some(type_params) = self.recovering(None, |_| Self::t_abstract_generics(generics))
+        else {
+            self.current_scope_mut()
+                .define_function(name.clone(), BeatriceType::Error);
+            return;
+        };
+        self.enter_generic_scope(&type_params);
+        let ftype = self.ast_typeof_function(f);
+        self.exit_scope();
+        self.current_scope_mut()
+            .define_function(name.clone(), ftype);
+        if !type_params.is_empty() {
+            self.current_scope_mut()
+                .define_generics(name.clone(), type_params);
+        }
+        self.current_scope_mut()
+            .define_parameters(name.clone(), params.iter().map(ParamSpec::from).collect());
+    }
+
+    ///Declares the given structs and enums together, so each of them can refer to any other,
+    ///whatever order they were declared in. Other declarations are skipped. Each one comes with
+    ///where it starts, which its errors are reported at
+    pub(crate) fn declare_types<'a>(&mut self, asts: impl IntoIterator<Item = (&'a AST, Span)>) {
+        let types = asts
+            .into_iter()
+            .map(|(ast, span)| (Self::declaration(ast), span))
+            .filter(|(ast, _)| matches!(ast, AST::Struct { .. } | AST::Enum { .. }))
+            .collect::<Vec<_>>();
+        //every type is defined empty first, so the fields of the others can use it
+        let mut ids = Vec::with_capacity(types.len());
+        for (ast, span) in &types {
+            let (AST::Struct { name, generics, .. } | AST::Enum { name, generics, .. }) = ast
+            else {
+                unreachable!();
+            };
+            let type_params = self
+                .recovering(Some(*span), |_| Self::t_abstract_generics(generics))
+                .unwrap_or_default();
+            if !type_params.is_empty() {
+                self.current_scope_mut()
+                    .define_generics(name.clone(), type_params);
+            }
+            if let AST::Struct { .. } = ast {
+                let def = StructDef::new(self.new_struct_id(), name.clone());
+                ids.push(def.id);
+                self.current_scope_mut().define_struct(name.clone(), def);
+            } else {
+                self.current_scope_mut().define_enum(
+                    name.clone(),
+                    EnumDef {
+                        name: name.clone(),
+                        variants: Vec::new(),
+                    },
+                );
+            }
+        }
+        let mut ids = ids.into_iter();
+        let mut structs = Vec::new();
+        for (ast, span) in types {
+            let (AST::Struct { name, .. } | AST::Enum { name, .. }) = ast else {
+                unreachable!();
+            };
+            let previous = self.set_span(span);
+            self.enter_generic_scope(&self.generics_of(name));
+            if let AST::Struct { .. } = ast {
+                let def = self.ast_typeof_struct(ast, ids.next().unwrap());
+                self.exit_scope();
+                self.current_scope_mut()
+                    .define_struct(name.clone(), def.clone());
+                structs.push((def, span));
+            } else {
+                let def = self.ast_typeof_enum(ast);
+                self.exit_scope();
+                self.current_scope_mut().define_enum(name.clone(), def);
+            }
+            self.set_span(previous);
+        }
+        //a cycle is only known once every struct in it has its fields
+        let mut cycles = Vec::new();
+        for (def, span) in &structs {
+            self.recovering(Some(*span), |t| t.check_finite_struct(def, &mut cycles));
+        }
+    }
+}
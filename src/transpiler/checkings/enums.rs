@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use crate::{
+    parser::{AST, VariantArgs, VariantKind},
+    transpiler::{BeatriceType, TypeError, transpiler::BeatriceTranspiler},
+};
+
+use super::generics::Substitution;
+
+///The fields of a variant after their types were resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariantShape {
+    Unit,
+    Tuple(Vec<BeatriceType>),
+    ///The fields in the order they were declared
+    Struct(Vec<(String, BeatriceType)>),
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantDef {
+    pub name: String,
+    pub shape: VariantShape,
+}
+///An enum declaration after its variants were resolved. The position of a variant is the tag it
+///is emitted with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumDef {
+    pub name: String,
+    pub variants: Vec<VariantDef>,
+}
+
+impl VariantShape {
+    ///The types of the fields, in the order they were declared
+    pub fn field_types(&self) -> Vec<BeatriceType> {
+        match self {
+            Self::Unit => Vec::new(),
+            Self::Tuple(types) => types.clone(),
+            Self::Struct(fields) => fields.iter().map(|(_, t)| t.clone()).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Unit => 0,
+            Self::Tuple(types) => types.len(),
+            Self::Struct(fields) => fields.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn substitute(&self, subst: &Substitution) -> Self {
+        match self {
+            Self::Unit => Self::Unit,
+            Self::Tuple(types) => Self::Tuple(types.iter().map(|t| t.substitute(subst)).collect()),
+            Self::Struct(fields) => Self::Struct(
+                fields
+                    .iter()
+                    .map(|(k, t)| (k.clone(), t.substitute(subst)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl EnumDef {
+    pub fn variant_index(&self, variant: &str) -> Option<usize> {
+        self.variants.iter().position(|v| v.name == variant)
+    }
+}
+
+impl BeatriceTranspiler {
+    pub(crate) fn enum_def(&self, name: &str) -> Option<&EnumDef> {
+        self.scopes()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.enum_def(name))
+    }
+
+    ///Generates the definition of an enum from its declaration. Its generic parameters must
+    ///already be usable as types. The fields whose types can't be resolved are reported and given
+    ///the Error type
+    pub(crate) fn ast_typeof_enum(&mut self, e: &AST) -> EnumDef {
+        let AST::Enum { name, variants, .. } = e else {
+            panic!("This is a bug. Expected to receive an enum");
+        };
+        let mut defs = Vec::with_capacity(variants.len());
+        for variant in variants {
+            let shape = match &variant.kind {
+                VariantKind::Unit => VariantShape::Unit,
+                VariantKind::Tuple(types) => {
+                    let mut out = Vec::with_capacity(types.len());
+                    for kind in types {
+                        let kind = self.t_abstract_from_primitive(kind);
+                        out.push(self.recover(kind));
+                    }
+                    VariantShape::Tuple(out)
+                }
+                VariantKind::Struct(fields) => {
+                    let mut out = Vec::with_capacity(fields.len());
+                    for field in fields {
+                        let kind = self.t_abstract_from_primitive(&field.kindof);
+                        out.push((field.key.clone(), self.recover(kind)));
+                    }
+                    VariantShape::Struct(out)
+                }
+            };
+            defs.push(VariantDef {
+                name: variant.name.clone(),
+                shape,
+            });
+        }
+        EnumDef {
+            name: name.clone(),
+            variants: defs,
+        }
+    }
+
+    ///Finds the variant of an enum type, returning its tag and the types of its fields once the
+    ///generic parameters of the enum are replaced by the ones of kind
+    pub(crate) fn variant_of(
+        &self,
+        kind: &BeatriceType,
+        variant: &str,
+    ) -> Result<(usize, VariantShape), TypeError> {
+        let BeatriceType::Enum { name, args } = kind else {
+            panic!("This is a bug. Expected to receive an enum type");
+        };
+        let def = self
+            .enum_def(name)
+            .ok_or(TypeError::NotRecognizedType(name.clone()))?;
+        let idx = def
+            .variant_index(variant)
+            .ok_or(TypeError::NotRecognizedVariant {
+                target: name.clone(),
+                variant: variant.to_string(),
+            })?;
+        let subst = self
+            .generics_of(name)
+            .into_iter()
+            .map(|param| param.name)
+            .zip(args.iter().cloned())
+            .collect::<Substitution>();
+        Ok((idx, def.variants[idx].shape.substitute(&subst)))
+    }
+
+    ///Generates the type of the construction of a variant, checking the values given to its
+    ///fields. The generic arguments are taken from expected when it's the same enum
+    pub(crate) fn ast_typeof_variant_expr(
+        &mut self,
+        ast: &AST,
+        expected: Option<&BeatriceType>,
+    ) -> Result<BeatriceType, TypeError> {
+        let AST::VariantExpr {
+            name,
+            type_args,
+            variant,
+            args,
+        } = ast
+        else {
+            panic!("This is a bug. Expected to receive a variant expression");
+        };
+        let target = format!("{name}::{variant}");
+        let def = self
+            .enum_def(name)
+            .ok_or(TypeError::NotRecognizedType(name.clone()))?;
+        let idx = def
+            .variant_index(variant)
+            .ok_or(TypeError::NotRecognizedVariant {
+                target: name.clone(),
+                variant: variant.clone(),
+            })?;
+        let shape = def.variants[idx].shape.clone();
+
+        //the values given to the fields, in the order the fields were declared
+        let values = match (&shape, args) {
+            (VariantShape::Unit, VariantArgs::Unit) => Vec::new(),
+            (VariantShape::Tuple(types), VariantArgs::Tuple(values))
+                if types.len() == values.len() =>
+            {
+                values.iter().collect()
+            }
+            (VariantShape::Struct(fields), VariantArgs::Struct(values)) => {
+                let mut named = values
+                    .iter()
+                    .map(|v| (v.key.as_str(), &v.value))
+                    .collect::<HashMap<_, _>>();
+                let mut ordered = Vec::with_capacity(fields.len());
+                let mut missing = Vec::new();
+                for (key, _) in fields {
+                    match named.remove(key.as_str()) {
+                        Some(value) => ordered.push(value),
+                        None => missing.push(key.clone()),
+                    }
+                }
+                if let Some(value) = values.iter().find(|v| named.contains_key(v.key.as_str())) {
+                    return Err(TypeError::InvalidFieldName {
+                        field: value.key.clone(),
+                        target_struct: target,
+                    });
+                }
+                if !missing.is_empty() {
+                    return Err(TypeError::NotCorrectFields {
+                        fields: missing,
+                        target,
+                    });
+                }
+                ordered
+            }
+            _ => {
+                return Err(TypeError::WrongVariantFields {
+                    target,
+                    expected: shape.len(),
+                    received: match args {
+                        VariantArgs::Unit => 0,
+                        VariantArgs::Tuple(values) => values.len(),
+                        VariantArgs::Struct(values) => values.len(),
+                    },
+                });
+            }
+        };
+        let mut value_types = Vec::with_capacity(values.len());
+        for value in values {
+            value_types.push(self.ast_typeof_expression(value)?);
+        }
+
+        let generics = self.generics_of(name);
+        let subst = if generics.is_empty() && type_args.is_empty() {
+            Substitution::new()
+        } else if type_args.is_empty() {
+            let mut subst = Substitution::new();
+            //the Empty of let x: List<int> = List::Empty tells nothing about T by itself
+            if let Some(BeatriceType::Enum {
+                name: expected_name,
+                args,
+            }) = expected
+                && expected_name == name
+            {
+                for (param, arg) in generics.iter().zip(args) {
+                    subst.insert(param.name.clone(), arg.clone());
+                }
+            }
+            for (field, value) in shape.field_types().iter().zip(&value_types) {
+                field.unify(value, &mut subst);
+            }
+            self.check_instantiation(name, &generics, &subst)?;
+            subst
+        } else {
+            self.explicit_substitution(name, &generics, type_args)?
+        };
+        let shape = shape.substitute(&subst);
+        for (idx, (expected, received)) in
+            shape.field_types().into_iter().zip(value_types).enumerate()
+        {
+            if !expected.accepts(&received) {
+                return Err(match &shape {
+                    VariantShape::Struct(fields) => TypeError::InvalidFieldValue {
+                        target,
+                        field: fields[idx].0.clone(),
+                        expected: Box::new(expected),
+                        received: Box::new(received),
+                    },
+                    _ => TypeError::UnexpectedType { expected, received },
+                });
+            }
+        }
+        Ok(BeatriceType::Enum {
+            name: name.clone(),
+            args: generics.iter().map(|g| subst[&g.name].clone()).collect(),
+        })
+    }
+}
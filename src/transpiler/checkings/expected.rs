@@ -0,0 +1,139 @@
+//Checking an expression against the type it's expected to have, as the annotation of a let, the
+//return type of its function or the explicit type argument of a generic parameter. The
+//expected type flows into the expression, so values that can't be typed by themselves, such as the
+//Maybe::None of a Maybe<T>, get their type from it
+
+use crate::{
+    parser::{AST, Program},
+    transpiler::{BeatriceType, TypeError, transpiler::BeatriceTranspiler},
+};
+
+impl BeatriceTranspiler {
+    ///Whether expr is a number literal that can be a value of the sized numeric kind, as the 255
+    ///of an u8 or the 0.5 of an f32. The literals that fit are emitted as values of kind
+    pub(crate) fn literal_fits(&mut self, expr: &AST, kind: &BeatriceType) -> bool {
+        let fits = match (expr, kind) {
+            (AST::Int(i), _) if let Some((min, max)) = kind.integer_range() => {
+                (min..=max).contains(&i128::from(*i))
+            }
+            (AST::Float(_), BeatriceType::F32) => true,
+            _ => false,
+        };
+        if fits {
+            self.define_numeric(expr, kind.clone());
+        }
+        fits
+    }
+
+    ///The type of expr where a value of type expected is, which is expected itself for the number
+    ///literals that fit in it and the type of expr otherwise
+    pub(crate) fn ast_typeof_expected(
+        &mut self,
+        expr: &AST,
+        expected: &BeatriceType,
+    ) -> Result<BeatriceType, TypeError> {
+        if self.literal_fits(expr, expected) {
+            Ok(expected.clone())
+        } else {
+            self.ast_typeof_expression(expr)
+        }
+    }
+
+    ///Checks that expr can be used where a value of type expected is, giving the type the value
+    ///ends up having. Int literals are accepted as floats, as both are js numbers, and number
+    ///literals by the sized types they fit in
+    pub(crate) fn ast_check_expression(
+        &mut self,
+        expr: &AST,
+        expected: &BeatriceType,
+    ) -> Result<BeatriceType, TypeError> {
+        let received = match (expr, expected) {
+            _ if self.literal_fits(expr, expected) => expected.clone(),
+            (AST::Int(_), BeatriceType::Float | BeatriceType::F32) => {
+                self.define_numeric(expr, expected.clone());
+                expected.clone()
+            }
+            //let p: Pair<int>? = Pair { ... } builds the Pair<int> held by the option
+            (
+                AST::Int(_)
+                | AST::Tuple(_)
+                | AST::StructExpr { .. }
+                | AST::VariantExpr { .. }
+                | AST::Ok(_)
+                | AST::Err(_),
+                BeatriceType::Option(inner),
+            ) => BeatriceType::option(self.ast_check_expression(expr, inner)?),
+            (AST::Tuple(values), BeatriceType::Tuple(types)) if values.len() == types.len() => {
+                let mut checked = Vec::with_capacity(values.len());
+                for (value, kind) in values.iter().zip(types) {
+                    checked.push(self.ast_check_expression(value, kind)?);
+                }
+                BeatriceType::Tuple(checked)
+            }
+            (AST::Some(value), BeatriceType::Option(inner)) => {
+                BeatriceType::option(self.ast_check_expression(value, inner)?)
+            }
+            (AST::None, BeatriceType::Option(_)) => expected.clone(),
+            (AST::Ok(value), BeatriceType::Result { ok, err }) => BeatriceType::Result {
+                ok: Box::new(self.ast_check_expression(value, ok)?),
+                err: err.clone(),
+            },
+            (AST::Err(value), BeatriceType::Result { ok, err }) => BeatriceType::Result {
+                ok: ok.clone(),
+                err: Box::new(self.ast_check_expression(value, err)?),
+            },
+            (AST::StructExpr { .. }, _) => self.ast_typeof_struct_expr(expr, Some(expected))?,
+            (AST::VariantExpr { .. }, _) => self.ast_typeof_variant_expr(expr, Some(expected))?,
+            (AST::Block(block), _) if !block.body().is_empty() => {
+                self.enter_scope();
+                let checked = self.ast_check_block(block, expected);
+                self.exit_scope();
+                checked?
+            }
+            (
+                AST::If {
+                    expr: condition,
+                    block,
+                    elseblock: Some(elsebranch),
+                },
+                _,
+            ) => {
+                self.generate_metadata(condition)?;
+                let (block_narrowing, else_narrowing) = self.narrowings(condition);
+                self.with_narrowing(block_narrowing, |t| t.ast_check_expression(block, expected))?;
+                self.with_narrowing(else_narrowing, |t| {
+                    t.ast_check_expression(elsebranch, expected)
+                })?
+            }
+            _ => {
+                self.generate_metadata(expr)?;
+                self.ast_typeof_expression(expr)?
+            }
+        };
+        if expected.accepts(&received) {
+            self.define_type(expr, received);
+            Ok(expected.clone())
+        } else {
+            Err(TypeError::UnexpectedType {
+                expected: expected.clone(),
+                received,
+            })
+        }
+    }
+
+    ///Checks the statements of a block, its last expression being its value
+    fn ast_check_block(
+        &mut self,
+        block: &Program,
+        expected: &BeatriceType,
+    ) -> Result<BeatriceType, TypeError> {
+        let mut statements = block.statements();
+        let (last, _) = statements
+            .next_back()
+            .expect("This is a bug. Expected a non empty block");
+        for (expr, span) in statements {
+            self.check_statement(expr, Some(span));
+        }
+        self.ast_check_expression(last, expected)
+    }
+}
@@ -0,0 +1,137 @@
+//The jumps, return, break and panic, leave the code they are in, as does a loop without a break.
+//They never give a value, so they have the Never type, accepted as any other. After checking a
+//function, its body is walked to find whether every path through it jumps out of it: the
+//statements following one that always does can't run, and a body that can end without returning
+//doesn't give a value to the functions returning one.
+
+use crate::{
+    parser::{AST, Operator, Program},
+    transpiler::{BeatriceType, TypeError, transpiler::BeatriceTranspiler},
+};
+
+impl BeatriceTranspiler {
+    ///Checks that the value of a return can be returned by the enclosing function
+    pub(crate) fn ast_typeof_return(
+        &mut self,
+        value: Option<&AST>,
+    ) -> Result<BeatriceType, TypeError> {
+        let expected = self
+            .enclosing_return_type()
+            .cloned()
+            .expect("This is a bug. Returns are only parsed inside functions");
+        match value {
+            //the returned value is expected to have the return type, as the 5 of a float function
+            Some(value) => {
+                self.ast_check_expression(value, &expected)?;
+            }
+            None if !expected.accepts(&BeatriceType::Void) => {
+                return Err(TypeError::UnexpectedType {
+                    expected,
+                    received: BeatriceType::Void,
+                });
+            }
+            None => {}
+        }
+        Ok(BeatriceType::Never)
+    }
+
+    ///Checks the body of a loop. A loop without a break never ends, so it has the Never type
+    pub(crate) fn ast_typeof_loop(&mut self, body: &AST) -> Result<BeatriceType, TypeError> {
+        self.enter_loop();
+        let checked = self.generate_metadata(body);
+        let breaks = self.exit_loop();
+        checked?;
+        Ok(if breaks {
+            BeatriceType::Void
+        } else {
+            BeatriceType::Never
+        })
+    }
+
+    pub(crate) fn ast_typeof_break(&mut self) -> Result<BeatriceType, TypeError> {
+        if !self.break_loop() {
+            return Err(TypeError::BreakOutsideLoop);
+        }
+        Ok(BeatriceType::Never)
+    }
+
+    pub(crate) fn ast_typeof_panic(&mut self, message: &AST) -> Result<BeatriceType, TypeError> {
+        self.ast_check_expression(message, &BeatriceType::String)?;
+        Ok(BeatriceType::Never)
+    }
+
+    ///Whether every path through a checked block jumps out of it. The first statement after one
+    ///that always does is reported as unreachable
+    pub(crate) fn block_diverges(&mut self, block: &Program) -> bool {
+        let mut diverges = false;
+        for (ast, span) in block.statements() {
+            if diverges {
+                let previous = self.set_span(span);
+                self.report(TypeError::UnreachableCode);
+                self.set_span(previous);
+                break;
+            }
+            diverges = self.diverges(ast);
+        }
+        diverges
+    }
+
+    ///Whether every path through a checked statement jumps out of it, walking the blocks inside
+    ///it. Besides the jumps and the loops without a break, only the statements holding them can
+    fn diverges(&mut self, ast: &AST) -> bool {
+        match ast {
+            AST::Return(value) => {
+                if let Some(value) = value {
+                    self.diverges(value);
+                }
+                true
+            }
+            AST::Panic(message) => {
+                self.diverges(message);
+                true
+            }
+            AST::Break => true,
+            AST::Loop(body) => {
+                self.diverges(body);
+                self.type_of(ast) == Some(&BeatriceType::Never)
+            }
+            AST::Block(block) => self.block_diverges(block),
+            AST::If {
+                expr,
+                block,
+                elseblock,
+            } => {
+                let condition = self.diverges(expr);
+                let block = self.diverges(block);
+                let elseblock = elseblock
+                    .as_ref()
+                    .is_some_and(|elseblock| self.diverges(elseblock));
+                condition || block && elseblock
+            }
+            AST::Match { expr, arms } => {
+                let matched = self.diverges(expr);
+                let mut arms_diverge = !arms.is_empty();
+                for arm in arms {
+                    if let Some(guard) = &arm.guard {
+                        self.diverges(guard);
+                    }
+                    arms_diverge &= self.diverges(&arm.body);
+                }
+                matched || arms_diverge
+            }
+            //the right side of &&, || and ?? isn't always evaluated
+            AST::BinExpr(lhs, rhs, operator) => {
+                let lhs = self.diverges(lhs);
+                let rhs = self.diverges(rhs);
+                lhs || rhs
+                    && !matches!(
+                        operator,
+                        Operator::And(_) | Operator::Or(_) | Operator::Coalesce
+                    )
+            }
+            AST::VarDecl { body, .. } => self.diverges(body),
+            AST::Attributed { ast, .. } => self.diverges(ast),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+
+use crate::{
+    parser::{AST, GenericParam, TypeAst},
+    transpiler::{BeatriceType, Bound, TypeError, transpiler::BeatriceTranspiler},
+};
+
+///The types each generic parameter was instantiated with
+pub type Substitution = HashMap<String, BeatriceType>;
+
+///A generic parameter after its bounds were resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeParam {
+    pub name: String,
+    pub bounds: Vec<Bound>,
+}
+
+impl Bound {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "Numeric" => Self::Numeric,
+            "Integer" => Self::Integer,
+            "Floating" => Self::Floating,
+            _ => return None,
+        })
+    }
+
+    ///Whether every type satisfying self also satisfies other
+    pub fn implies(self, other: Bound) -> bool {
+        self == other || other == Bound::Numeric
+    }
+
+    fn is_satisfied_by_concrete(self, kind: &BeatriceType) -> bool {
+        match self {
+            Self::Numeric => {
+                Self::Integer.is_satisfied_by_concrete(kind)
+                    || Self::Floating.is_satisfied_by_concrete(kind)
+            }
+            Self::Integer => kind.integer_range().is_some(),
+            Self::Floating => matches!(kind, BeatriceType::Float | BeatriceType::F32),
+        }
+    }
+}
+
+impl BeatriceType {
+    ///Whether the type is the one of an expression that failed to check
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Error)
+    }
+
+    ///The smallest and largest values of an integer type, None for the other types
+    pub fn integer_range(&self) -> Option<(i128, i128)> {
+        Some(match self {
+            Self::Int => (i64::MIN.into(), i64::MAX.into()),
+            Self::I8 => (i8::MIN.into(), i8::MAX.into()),
+            Self::I16 => (i16::MIN.into(), i16::MAX.into()),
+            Self::I32 => (i32::MIN.into(), i32::MAX.into()),
+            Self::U8 => (0, u8::MAX.into()),
+            Self::U16 => (0, u16::MAX.into()),
+            Self::U32 => (0, u32::MAX.into()),
+            Self::U64 => (0, u64::MAX.into()),
+            _ => return None,
+        })
+    }
+
+    ///Replaces every generic parameter in self by the type it was instantiated with
+    pub fn substitute(&self, subst: &Substitution) -> BeatriceType {
+        match self {
+            Self::TypeVar(name) => subst.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Self::Function {
+                params,
+                return_type,
+            } => Self::Function {
+                params: params.iter().map(|p| p.substitute(subst)).collect(),
+                return_type: Box::new(return_type.substitute(subst)),
+            },
+            Self::Struct { id, name, args } => Self::Struct {
+                id: *id,
+                name: name.clone(),
+                args: args.iter().map(|a| a.substitute(subst)).collect(),
+            },
+            Self::Enum { name, args } => Self::Enum {
+                name: name.clone(),
+                args: args.iter().map(|a| a.substitute(subst)).collect(),
+            },
+            Self::Tuple(types) => Self::Tuple(types.iter().map(|t| t.substitute(subst)).collect()),
+            Self::Option(inner) => Self::option(inner.substitute(subst)),
+            Self::Result { ok, err } => Self::Result {
+                ok: Box::new(ok.substitute(subst)),
+                err: Box::new(err.substitute(subst)),
+            },
+            Self::Void
+            | Self::Int
+            | Self::Float
+            | Self::I8
+            | Self::I16
+            | Self::I32
+            | Self::U8
+            | Self::U16
+            | Self::U32
+            | Self::U64
+            | Self::F32
+            | Self::String
+            | Self::Bool
+            | Self::Never
+            | Self::Error => self.clone(),
+        }
+    }
+
+    ///Infers the generic parameters of pattern by walking it together with actual. Parameters
+    ///already inferred are left as they are, mismatches are reported later when the substituted
+    ///pattern is compared against actual
+    pub fn unify(&self, actual: &BeatriceType, subst: &mut Substitution) {
+        match (self, actual) {
+            //None tells nothing about the type of the value it would hold
+            (_, Self::Never) => {}
+            (Self::TypeVar(name), _) => {
+                subst.entry(name.clone()).or_insert_with(|| actual.clone());
+            }
+            (
+                Self::Function {
+                    params,
+                    return_type,
+                },
+                Self::Function {
+                    params: aparams,
+                    return_type: areturn,
+                },
+            ) => {
+                for (p, a) in params.iter().zip(aparams) {
+                    p.unify(a, subst);
+                }
+                return_type.unify(areturn, subst);
+            }
+            (
+                Self::Struct { id, args, .. },
+                Self::Struct {
+                    id: aid,
+                    args: aargs,
+                    ..
+                },
+            ) if id == aid => {
+                for (p, a) in args.iter().zip(aargs) {
+                    p.unify(a, subst);
+                }
+            }
+            (
+                Self::Enum { name, args },
+                Self::Enum {
+                    name: aname,
+                    args: aargs,
+                },
+            ) if name == aname => {
+                for (p, a) in args.iter().zip(aargs) {
+                    p.unify(a, subst);
+                }
+            }
+            (Self::Tuple(types), Self::Tuple(atypes)) => {
+                for (p, a) in types.iter().zip(atypes) {
+                    p.unify(a, subst);
+                }
+            }
+            (Self::Option(inner), Self::Option(actual)) => inner.unify(actual, subst),
+            (Self::Result { ok, err }, Self::Result { ok: aok, err: aerr }) => {
+                ok.unify(aok, subst);
+                err.unify(aerr, subst);
+            }
+            //a value given where an option is expected
+            (Self::Option(inner), _) => inner.unify(actual, subst),
+            _ => {}
+        }
+    }
+}
+
+impl BeatriceTranspiler {
+    ///Resolves the bounds of the generic parameters of a declaration
+    pub(crate) fn t_abstract_generics(
+        generics: &[GenericParam],
+    ) -> Result<Vec<TypeParam>, TypeError> {
+        generics
+            .iter()
+            .map(|g| {
+                let bounds = g
+                    .bounds
+                    .iter()
+                    .map(|b| Bound::from_name(b).ok_or(TypeError::NotRecognizedBound(b.clone())))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(TypeParam {
+                    name: g.name.clone(),
+                    bounds,
+                })
+            })
+            .collect()
+    }
+
+    ///Enters a new scope where the given generic parameters can be used as types
+    pub(crate) fn enter_generic_scope(&mut self, params: &[TypeParam]) {
+        self.enter_scope();
+        for param in params {
+            self.current_scope_mut()
+                .define_type_param(param.name.clone(), param.bounds.clone());
+        }
+    }
+
+    ///The generic parameters of the given function, struct or enum. Empty if it isn't generic
+    pub(crate) fn generics_of(&self, name: &str) -> Vec<TypeParam> {
+        self.scopes()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.generics_of(name))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn type_param_bounds(&self, name: &str) -> Option<&Vec<Bound>> {
+        self.scopes()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.type_param_bounds(name))
+    }
+
+    pub(crate) fn satisfies(&self, kind: &BeatriceType, bound: Bound) -> bool {
+        if let BeatriceType::TypeVar(name) = kind {
+            self.type_param_bounds(name)
+                .is_some_and(|bounds| bounds.iter().any(|b| b.implies(bound)))
+        } else if kind.is_error() {
+            true
+        } else {
+            bound.is_satisfied_by_concrete(kind)
+        }
+    }
+
+    ///Whether the js operators compute the values of kind as they are. The body of a generic
+    ///function is emitted once, working on js numbers without adapting its operations, so only
+    ///such types can be given to bounds asking for numbers. Bigints aren't js numbers, and the
+    ///operations of the sized integers and f32s must wrap or be rounded
+    fn computed_unadapted(&self, kind: &BeatriceType) -> bool {
+        match kind {
+            BeatriceType::Int => !self.is_bigint(kind),
+            BeatriceType::F32 => false,
+            _ => kind.integer_range().is_none(),
+        }
+    }
+
+    ///Checks that every generic parameter of target was instantiated and that the types given to
+    ///them satisfy their bounds
+    pub(crate) fn check_instantiation(
+        &self,
+        target: &str,
+        params: &[TypeParam],
+        subst: &Substitution,
+    ) -> Result<(), TypeError> {
+        for param in params {
+            let Some(kind) = subst.get(&param.name) else {
+                return Err(TypeError::CannotInferGeneric {
+                    target: target.to_string(),
+                    param: param.name.clone(),
+                });
+            };
+            if let Some(bound) = param
+                .bounds
+                .iter()
+                .find(|b| !self.satisfies(kind, **b) || !self.computed_unadapted(kind))
+            {
+                return Err(TypeError::UnsatisfiedBound {
+                    param: param.name.clone(),
+                    bound: *bound,
+                    received: kind.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    ///Builds the substitution of target from the generic arguments explicitly written by the
+    ///user, such as the <int> of id<int>(5) or Pair<int>
+    pub(crate) fn explicit_substitution(
+        &self,
+        target: &str,
+        params: &[TypeParam],
+        type_args: &[TypeAst],
+    ) -> Result<Substitution, TypeError> {
+        if params.len() != type_args.len() {
+            return Err(TypeError::WrongGenericsAmount {
+                target: target.to_string(),
+                expected: params.len(),
+                received: type_args.len(),
+            });
+        }
+        let mut subst = Substitution::with_capacity(params.len());
+        for (param, arg) in params.iter().zip(type_args) {
+            subst.insert(param.name.clone(), self.t_abstract_from_primitive(arg)?);
+        }
+        self.check_instantiation(target, params, &subst)?;
+        Ok(subst)
+    }
+
+    ///Generates the type of a call to a generic function, checking its arguments against the
+    ///parameters after instantiating them
+    pub(crate) fn ast_typeof_generic_call(
+        &mut self,
+        call: &AST,
+        generics: &[TypeParam],
+    ) -> Result<BeatriceType, TypeError> {
+        let AST::FunctionCall {
+            name,
+            type_args,
+            args,
+            named,
+            ..
+        } = call
+        else {
+            panic!("This is a bug. Expected to receive a function call");
+        };
+        let BeatriceType::Function {
+            params,
+            return_type,
+        } = self.typeof_callee(call)?
+        else {
+            panic!("This is a bug. typeof_callee returned something that isn't a function");
+        };
+        let sources = self.resolve_call_args(call, params.len())?;
+        //explicit type arguments give the parameters their types before the arguments are checked,
+        //so id<float>(5) passes 5 as a float
+        if !type_args.is_empty() {
+            let subst = self.explicit_substitution(name, generics, type_args)?;
+            for (param, source) in params.iter().zip(sources) {
+                if let Some(arg) = source.arg(args, named) {
+                    self.ast_check_expression(arg, &param.substitute(&subst))?;
+                }
+            }
+            return Ok(return_type.substitute(&subst));
+        }
+        //the parameters given an argument, with the type of the argument
+        let mut given = Vec::with_capacity(params.len());
+        for (param, source) in params.iter().zip(sources) {
+            if let Some(arg) = source.arg(args, named) {
+                given.push((param, arg, self.ast_typeof_expression(arg)?));
+            }
+        }
+        let mut subst = Substitution::new();
+        for (param, _, arg_type) in &given {
+            param.unify(arg_type, &mut subst);
+        }
+        self.check_instantiation(name, generics, &subst)?;
+        for (param, arg, arg_type) in given {
+            let expected = param.substitute(&subst);
+            if !expected.accepts(&arg_type) && !self.literal_fits(arg, &expected) {
+                return Err(TypeError::UnexpectedType {
+                    expected,
+                    received: arg_type,
+                });
+            }
+        }
+        Ok(return_type.substitute(&subst))
+    }
+}
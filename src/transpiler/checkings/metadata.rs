@@ -1,76 +1,94 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::VecDeque;
 
 use crate::{
     parser::{AST, TypeAst},
-    transpiler::{BeatriceType, TypeError, transpiler::BeatriceTranspiler},
+    transpiler::{BeatriceType, TypeError, resolver::SymbolKind, transpiler::BeatriceTranspiler},
+};
+
+use super::{
+    generics::{Substitution, TypeParam},
+    structs::StructDef,
 };
 
 impl BeatriceTranspiler {
-    fn ast_typeof_expression(&mut self, expr: &AST) -> Result<BeatriceType, TypeError> {
+    ///The type of the value of expr. Every expression is typed once, and the type it was given is
+    ///reused after that, so typing a tree doesn't walk its subtrees again
+    pub(crate) fn ast_typeof_expression(&mut self, expr: &AST) -> Result<BeatriceType, TypeError> {
+        if let Some(kind) = self.type_of(expr) {
+            return Ok(kind.clone());
+        }
+        let kind = self.ast_typeof_unchecked(expr)?;
+        self.define_type(expr, kind.clone());
+        Ok(kind)
+    }
+
+    fn ast_typeof_unchecked(&mut self, expr: &AST) -> Result<BeatriceType, TypeError> {
         let v = match expr {
             AST::Float(_) => BeatriceType::Float,
+            //a literal given no other type is an int
+            AST::Int(i) if i64::try_from(*i).is_err() => {
+                return Err(TypeError::LiteralOutOfRange {
+                    literal: *i,
+                    kind: BeatriceType::Int,
+                });
+            }
             AST::Int(_) => BeatriceType::Int,
+            AST::Str(_) => BeatriceType::String,
+            AST::Bool(_) => BeatriceType::Bool,
+            AST::Interpolated(parts) => self.ast_typeof_interpolated(parts)?,
+            AST::VarDecl {
+                kindof: Some(kindof),
+                ..
+            } => self.t_abstract_from_primitive(kindof)?,
             AST::VarDecl { body, .. } => self.ast_typeof_expression(body)?,
-            AST::Identifier(s) => self.typeof_var(s)?,
-            AST::BinExpr(lhs, ..) => self.ast_typeof_expression(lhs)?,
-            AST::Function { .. } => self.ast_typeof_function(expr)?,
-            AST::Return(r) => self.ast_typeof_expression(r)?,
-            AST::FunctionCall { name, .. } => {
-                //i dont think is needed to do the args checking again, so im not gonna do. If
-                //finding some bugs, imma implement it
-                let BeatriceType::Function { return_type, .. } = self.typeof_function(name)? else {
-                    panic!(
-                        "This is a bug. typeof_function method expected to return a function or error, but it returned anything else"
-                    );
-                };
-                *return_type.clone()
+            AST::Identifier(_) => self.typeof_identifier(expr)?,
+            AST::BinExpr(..) => self.ast_typeof_binexpr(expr)?,
+            AST::Function { .. } => self.ast_typeof_function(expr),
+            AST::Return(value) => self.ast_typeof_return(value.as_deref())?,
+            AST::Break => self.ast_typeof_break()?,
+            AST::Panic(message) => self.ast_typeof_panic(message)?,
+            AST::FunctionCall { .. } if !self.callee_generics(expr).is_empty() => {
+                let generics = self.callee_generics(expr);
+                self.ast_typeof_generic_call(expr, &generics)?
             }
-            AST::Struct { .. } => self.ast_typeof_struct(expr)?,
-            AST::StructExpr { name, fields } => {
-                let field_values = fields;
-
-                let BeatriceType::Struct { fields, order } = self.typeof_struct(name)? else {
-                    panic!("This is a bug. Expected typeof struct to return a struct type");
-                };
-                let mut flags = Vec::with_capacity(order.len());
-                for (idx, field) in field_values.iter().enumerate() {
-                    if let Some(field_type) = fields.get(&field.key) {
-                        flags.push(order[idx].clone());
-                        let expr_type = self.ast_typeof_expression(&field.value)?;
-                        if *field_type != expr_type {
-                            return Err(TypeError::InvalidFieldValue {
-                                target: name.clone(),
-                                field: field.key.clone(),
-                                received: expr_type,
-                                expected: field_type.clone(),
-                            });
+            AST::FunctionCall {
+                name,
+                type_args,
+                args,
+                named,
+                ..
+            } => {
+                let (params, return_type) = match self.typeof_callee(expr)? {
+                    BeatriceType::Function {
+                        params,
+                        return_type,
+                    } => (params, return_type),
+                    //the callee failed to check, so only the arguments can have errors of their own
+                    BeatriceType::Error => {
+                        for arg in args.iter().chain(named.iter().map(|arg| &arg.value)) {
+                            self.ast_typeof_expression(arg)?;
                         }
-                    } else {
-                        return Err(TypeError::InvalidFieldName {
-                            field: field.key.clone(),
-                            target_struct: name.clone(),
-                        });
+                        return Ok(BeatriceType::Error);
                     }
+                    _ => panic!(
+                        "This is a bug. typeof_callee method expected to return a function or error, but it returned anything else"
+                    ),
+                };
+                //neither a function without generics nor a variable holding one takes type arguments
+                self.explicit_substitution(name, &[], type_args)?;
+                //the arguments are checked along the call, as it's only typed once
+                let sources = self.resolve_call_args(expr, params.len())?;
+                for (param, source) in params.iter().zip(sources) {
+                    let Some(arg) = source.arg(args, named) else {
+                        continue;
+                    };
+                    self.ast_check_expression(arg, param)?;
                 }
-                if flags.len() != order.len() {
-                    return Err(TypeError::NotCorrectFields {
-                        fields: order
-                            .iter()
-                            .filter_map(|f| {
-                                if !flags.contains(f) {
-                                    Some(f.clone())
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect::<Vec<String>>(),
-                        target: name.clone(),
-                    });
-                } else {
-                    BeatriceType::Struct { fields, order }
-                }
+                *return_type
             }
-            AST::Block(exprs) => {
+            AST::StructExpr { .. } => self.ast_typeof_struct_expr(expr, None)?,
+            AST::Block(block) => {
+                let exprs = block.body();
                 let Some(last) = exprs.back() else {
                     return Ok(BeatriceType::Void);
                 };
@@ -83,49 +101,153 @@ impl BeatriceTranspiler {
                 self.ast_typeof_expression(last)?
             }
             AST::If {
-                block, elseblock, ..
+                expr,
+                block,
+                elseblock,
             } => {
+                //an if giving a value, such as an argument, isn't checked as a statement first
+                self.ast_typeof_expression(expr)?;
                 if let Some(elsebranch) = elseblock {
-                    let blocktype = self.ast_typeof_expression(block)?;
-                    let elsetype = self.ast_typeof_expression(elsebranch)?;
-                    if blocktype != elsetype {
-                        return Err(TypeError::IfElseWrong {
-                            ifbranch: blocktype,
-                            elsebranch: elsetype,
-                        });
-                    } else {
-                        blocktype
-                    }
+                    let (block_narrowing, else_narrowing) = self.narrowings(expr);
+                    let blocktype =
+                        self.with_narrowing(block_narrowing, |t| t.ast_typeof_expression(block))?;
+                    let elsetype = self
+                        .with_narrowing(else_narrowing, |t| t.ast_typeof_expression(elsebranch))?;
+                    blocktype.join(&elsetype).ok_or(TypeError::IfElseWrong {
+                        ifbranch: blocktype,
+                        elsebranch: elsetype,
+                    })?
                 } else {
                     BeatriceType::Void
                 }
             }
-            AST::Loop(body) => BeatriceType::Void,
+            AST::Loop(body) => self.ast_typeof_loop(body)?,
+            AST::Struct { .. } | AST::Enum { .. } => BeatriceType::Void,
+            AST::VariantExpr { .. } => self.ast_typeof_variant_expr(expr, None)?,
+            AST::Match { .. } => self.ast_typeof_match(expr)?,
+            AST::Attributed { ast, .. } => self.ast_typeof_expression(ast)?,
+            AST::Import { .. } | AST::Export(_) => BeatriceType::Void,
+            AST::Tuple(values) => {
+                let mut types = Vec::with_capacity(values.len());
+                for value in values {
+                    types.push(self.ast_typeof_expression(value)?);
+                }
+                BeatriceType::Tuple(types)
+            }
+            AST::TupleIndex { .. } | AST::FieldAccess { .. } => self.ast_typeof_access(expr)?,
+            AST::Some(value) => BeatriceType::option(self.ast_typeof_expression(value)?),
+            AST::None => BeatriceType::Option(Box::new(BeatriceType::Never)),
+            AST::Ok(value) => BeatriceType::Result {
+                ok: Box::new(self.ast_typeof_expression(value)?),
+                err: Box::new(BeatriceType::Never),
+            },
+            AST::Err(value) => BeatriceType::Result {
+                ok: Box::new(BeatriceType::Never),
+                err: Box::new(self.ast_typeof_expression(value)?),
+            },
+            AST::Try(value) => self.ast_typeof_try(value)?,
+            AST::Cast { .. } => self.ast_typeof_cast(expr)?,
         };
         Ok(v)
     }
 
-    ///Generates a beatrice type based on a struct declaration
-    pub(crate) fn ast_typeof_struct(&mut self, s: &AST) -> Result<BeatriceType, TypeError> {
-        let AST::Struct { fields, .. } = s else {
-            panic!("This ia a bug. Expected to receive a struct");
+    ///Generates the type of a struct literal. The generic arguments of a generic struct are
+    ///inferred from expected first, if there's one, and then from the values of the fields
+    pub(crate) fn ast_typeof_struct_expr(
+        &mut self,
+        expr: &AST,
+        expected: Option<&BeatriceType>,
+    ) -> Result<BeatriceType, TypeError> {
+        let AST::StructExpr {
+            name,
+            type_args,
+            fields: field_values,
+        } = expr
+        else {
+            panic!("This is a bug. Expected to receive a struct expression");
         };
-        let (fields, order) = {
-            let mut order = Vec::with_capacity(fields.len());
-            let mut mapfields = HashMap::with_capacity(fields.len());
-            for field in fields {
-                order.push(field.key.clone());
-                mapfields.insert(
-                    field.key.clone(),
-                    Self::t_abstract_from_primitive(&field.kindof)?,
-                );
+        let generics = self.generics_of(name);
+        //the generic parameters stand for themselves until they are inferred
+        let mut stype = self.struct_type(
+            name,
+            generics
+                .iter()
+                .map(|g| BeatriceType::TypeVar(g.name.clone()))
+                .collect(),
+        )?;
+        //type arguments given to a struct without generics are reported by the explicit substitution
+        if !generics.is_empty() || !type_args.is_empty() {
+            let subst = if type_args.is_empty() {
+                let fields = self.struct_def_of(&stype)?.fields;
+                let mut subst = Substitution::new();
+                if let Some(expected) = expected {
+                    stype.unify(expected, &mut subst);
+                }
+                for field in field_values {
+                    if let Some(field_type) = fields.get(&field.key) {
+                        let value_type = self.ast_typeof_expression(&field.value)?;
+                        field_type.unify(&value_type, &mut subst);
+                    }
+                }
+                self.check_instantiation(name, &generics, &subst)?;
+                subst
+            } else {
+                self.explicit_substitution(name, &generics, type_args)?
+            };
+            stype = stype.substitute(&subst);
+        }
+        let StructDef { fields, order, .. } = self.struct_def_of(&stype)?;
+        let mut flags = Vec::with_capacity(order.len());
+        for (idx, field) in field_values.iter().enumerate() {
+            if let Some(field_type) = fields.get(&field.key) {
+                flags.push(order[idx].clone());
+                let expr_type = match expected {
+                    //the expected type of the struct flows into its fields
+                    Some(_) => match self.ast_check_expression(&field.value, field_type) {
+                        Err(TypeError::UnexpectedType { .. }) => {
+                            self.ast_typeof_expression(&field.value)?
+                        }
+                        checked => checked?,
+                    },
+                    None => self.ast_typeof_expected(&field.value, field_type)?,
+                };
+                if !field_type.accepts(&expr_type) {
+                    return Err(TypeError::InvalidFieldValue {
+                        target: name.clone(),
+                        field: field.key.clone(),
+                        received: Box::new(expr_type),
+                        expected: Box::new(field_type.clone()),
+                    });
+                }
+            } else {
+                return Err(TypeError::InvalidFieldName {
+                    field: field.key.clone(),
+                    target_struct: name.clone(),
+                });
             }
-            (mapfields, order)
-        };
-        Ok(BeatriceType::Struct { fields, order })
+        }
+        if flags.len() != order.len() {
+            Err(TypeError::NotCorrectFields {
+                fields: order
+                    .iter()
+                    .filter_map(|f| {
+                        if !flags.contains(f) {
+                            Some(f.clone())
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Vec<String>>(),
+                target: name.clone(),
+            })
+        } else {
+            Ok(stype)
+        }
     }
-    ///Generates a beatrice type based on a function declaration
-    pub(crate) fn ast_typeof_function(&mut self, f: &AST) -> Result<BeatriceType, TypeError> {
+
+    ///Generates a beatrice type based on a function declaration. The parameters and return type
+    ///that can't be resolved are reported and given the Error type
+    pub(crate) fn ast_typeof_function(&mut self, f: &AST) -> BeatriceType {
         let AST::Function {
             params, returntype, ..
         } = f
@@ -134,24 +256,55 @@ impl BeatriceTranspiler {
         };
         let mut fparams = VecDeque::with_capacity(params.len());
         for param in params {
-            fparams.push_back(Self::t_abstract_from_primitive(&param.kindof)?);
+            let param_type = self.t_abstract_from_primitive(&param.kindof);
+            fparams.push_back(self.recover(param_type));
         }
-        let rtype = Self::t_abstract_from_primitive(returntype)?;
-        Ok(BeatriceType::Function {
+        let rtype = self.t_abstract_from_primitive(returntype);
+        BeatriceType::Function {
             params: fparams,
-            return_type: Box::new(rtype),
-        })
+            return_type: Box::new(self.recover(rtype)),
+        }
     }
 
     ///Following the pattern of t_abstract<name> this is the function that generates an
     ///BeatriceType based on a primitive TypeAst type generate on parsing
-    pub(crate) fn t_abstract_from_primitive(datatype: &TypeAst) -> Result<BeatriceType, TypeError> {
+    pub(crate) fn t_abstract_from_primitive(
+        &self,
+        datatype: &TypeAst,
+    ) -> Result<BeatriceType, TypeError> {
         let v = match datatype {
             TypeAst::Primitive(s) => match s.as_ref() {
                 "void" => BeatriceType::Void,
-                "int" => BeatriceType::Int,
-                "float" => BeatriceType::Float,
-                _ => return Err(TypeError::NotRecognizedType(s.clone())),
+                "int" | "i64" => BeatriceType::Int,
+                "float" | "f64" => BeatriceType::Float,
+                "i8" => BeatriceType::I8,
+                "i16" => BeatriceType::I16,
+                "i32" => BeatriceType::I32,
+                "u8" => BeatriceType::U8,
+                "u16" => BeatriceType::U16,
+                "u32" => BeatriceType::U32,
+                "u64" => BeatriceType::U64,
+                "f32" => BeatriceType::F32,
+                "string" => BeatriceType::String,
+                "bool" => BeatriceType::Bool,
+                _ if self.type_param_bounds(s).is_some() => BeatriceType::TypeVar(s.clone()),
+                _ => {
+                    let generics = self.generics_of(s);
+                    if !generics.is_empty() {
+                        return Err(TypeError::WrongGenericsAmount {
+                            target: s.clone(),
+                            expected: generics.len(),
+                            received: 0,
+                        });
+                    }
+                    if self.enum_def(s).is_some() {
+                        return Ok(BeatriceType::Enum {
+                            name: s.clone(),
+                            args: Vec::new(),
+                        });
+                    }
+                    return self.struct_type(s, Vec::new());
+                }
             },
             TypeAst::Function {
                 params,
@@ -159,27 +312,69 @@ impl BeatriceTranspiler {
             } => {
                 let mut fparams = VecDeque::with_capacity(params.len());
                 for param in params {
-                    fparams.push_back(Self::t_abstract_from_primitive(param)?);
+                    fparams.push_back(self.t_abstract_from_primitive(param)?);
                 }
-                let rtype = Self::t_abstract_from_primitive(return_type)?;
+                let rtype = self.t_abstract_from_primitive(return_type)?;
                 BeatriceType::Function {
                     params: fparams,
                     return_type: Box::new(rtype),
                 }
             }
+            TypeAst::Generic { name, args } if self.enum_def(name).is_some() => {
+                let generics = self.generics_of(name);
+                let subst = self.explicit_substitution(name, &generics, args)?;
+                BeatriceType::Enum {
+                    name: name.clone(),
+                    args: generics.iter().map(|g| subst[&g.name].clone()).collect(),
+                }
+            }
+            TypeAst::Option(inner) => BeatriceType::option(self.t_abstract_from_primitive(inner)?),
+            TypeAst::Generic { name, args } if name == "Option" && args.len() == 1 => {
+                BeatriceType::option(self.t_abstract_from_primitive(&args[0])?)
+            }
+            TypeAst::Generic { name, args } if name == "Result" && args.len() == 2 => {
+                BeatriceType::Result {
+                    ok: Box::new(self.t_abstract_from_primitive(&args[0])?),
+                    err: Box::new(self.t_abstract_from_primitive(&args[1])?),
+                }
+            }
+            TypeAst::Tuple(types) => BeatriceType::Tuple(
+                types
+                    .iter()
+                    .map(|t| self.t_abstract_from_primitive(t))
+                    .collect::<Result<_, _>>()?,
+            ),
+            TypeAst::Generic { name, args } => {
+                let generics = self.generics_of(name);
+                let subst = self.explicit_substitution(name, &generics, args)?;
+                self.struct_type(
+                    name,
+                    generics.iter().map(|g| subst[&g.name].clone()).collect(),
+                )?
+            }
         };
         Ok(v)
     }
 
-    pub(crate) fn typeof_var(&self, identifier: &str) -> Result<BeatriceType, TypeError> {
-        for scope in self.scopes() {
-            if scope.has_variable_or_function(identifier) {
-                return Ok(scope.kindof(identifier)?.clone());
+    ///The type of the variable or function an identifier was resolved to
+    pub(crate) fn typeof_identifier(&self, identifier: &AST) -> Result<BeatriceType, TypeError> {
+        let AST::Identifier(name) = identifier else {
+            panic!("This is a bug. Expected to receive an identifier");
+        };
+        let not_recognized = || TypeError::NotRecognizedVar(name.clone());
+        //the names that couldn't be resolved were already reported by the resolver
+        let Some(id) = self.resolution().use_of(identifier) else {
+            return Ok(BeatriceType::Error);
+        };
+        match self.resolution().symbol(id).kind {
+            SymbolKind::Function => self.typeof_function(name),
+            SymbolKind::Variable | SymbolKind::Field => {
+                self.symbol_type(id).cloned().ok_or_else(not_recognized)
             }
         }
-        Err(TypeError::NotRecognizedVar(identifier.to_string()))
     }
 
+    ///The type of a declared function
     pub(crate) fn typeof_function(&self, identifier: &str) -> Result<BeatriceType, TypeError> {
         for scope in self.scopes() {
             if scope.has_function(identifier) {
@@ -189,13 +384,41 @@ impl BeatriceTranspiler {
         Err(TypeError::NotRecognizedVar(identifier.to_string()))
     }
 
-    pub(crate) fn typeof_struct(&self, identifier: &str) -> Result<BeatriceType, TypeError> {
-        for scope in self.scopes() {
-            if scope.has_struct(identifier) {
-                return Ok(scope.kindof(identifier)?.clone());
-            }
+    ///Whether a call calls a variable holding a function, such as a parameter of type
+    ///(int): int, instead of a declared function
+    pub(crate) fn calls_variable(&self, call: &AST) -> bool {
+        self.resolution()
+            .use_of(call)
+            .is_some_and(|id| self.resolution().symbol(id).kind != SymbolKind::Function)
+    }
+
+    ///The type of the function a call calls. A variable holding a function hides the declared
+    ///function with its name
+    pub(crate) fn typeof_callee(&self, call: &AST) -> Result<BeatriceType, TypeError> {
+        let AST::FunctionCall { name, .. } = call else {
+            panic!("This is a bug. Expected to receive a function call");
+        };
+        if self.resolution().use_of(call).is_none() {
+            return Ok(BeatriceType::Error);
+        }
+        if !self.calls_variable(call) {
+            return self.typeof_function(name);
         }
-        Err(TypeError::NotRecognizedType(identifier.to_string()))
+        match self
+            .resolution()
+            .use_of(call)
+            .and_then(|id| self.symbol_type(id))
+        {
+            Some(kind @ (BeatriceType::Function { .. } | BeatriceType::Error)) => Ok(kind.clone()),
+            _ => Err(TypeError::NotRecognizedVar(name.clone())),
+        }
+    }
+
+    ///The generic parameters of the function a call calls. Variables holding functions have none
+    pub(crate) fn callee_generics(&self, call: &AST) -> Vec<TypeParam> {
+        self.callee(call)
+            .map(|callee| callee.generics.clone())
+            .unwrap_or_default()
     }
 
     pub(crate) fn generate_metadata(&mut self, ast: &AST) -> Result<(), TypeError> {
@@ -203,50 +426,63 @@ impl BeatriceTranspiler {
             AST::Function {
                 name, body, params, ..
             } => {
-                let ftype = self.ast_typeof_function(ast)?;
+                //the functions of a program are declared before checking it, so it's only
+                //declared here when checked alone
+                if !self.current_scope().has_function(name) {
+                    self.declare_function(ast);
+                }
+                //a function whose generics couldn't be declared was already reported
+                let BeatriceType::Function {
+                    params: param_types,
+                    return_type,
+                } = self.typeof_function(name)?
+                else {
+                    return Ok(());
+                };
+                let type_params = self.generics_of(name);
+                self.enter_generic_scope(&type_params);
                 self.current_scope_mut()
-                    .define_function(name.clone(), ftype.clone());
-                self.enter_scope();
+                    .set_return_type(*return_type.clone());
 
-                for param in params {
-                    let param_type = Self::t_abstract_from_primitive(&param.kindof)?;
-                    self.current_scope_mut()
-                        .define_variable(param.key.clone(), param_type);
-                }
-                let mut n = 0;
-                for ast in body.body() {
-                    if let AST::Return(ast) = ast {
-                        n += 1;
-                        let rtype = self.ast_typeof_expression(ast)?;
-                        let BeatriceType::Function { return_type, .. } = &ftype else {
-                            unreachable!();
-                        };
-                        if **return_type != rtype {
-                            return Err(TypeError::UnexpectedType {
-                                expected: *return_type.clone(),
-                                received: rtype,
-                            }); //change to unexpected type.
+                for (param, param_type) in params.iter().zip(&param_types) {
+                    let checked = self.recovering(None, |t| {
+                        //defaults are evaluated when the function is called, so they can use the
+                        //parameters before them
+                        if let Some(default) = &param.default {
+                            t.ast_check_expression(default, param_type)?;
                         }
-                    } else {
-                        self.generate_metadata(ast)?;
+                        t.check_irrefutable_pattern(&param.pattern, param_type)
+                    });
+                    if checked.is_none() {
+                        self.poison(&param.pattern);
                     }
                 }
-                if n == 0 {
-                    if let BeatriceType::Function { return_type, .. } = &ftype {
-                        if BeatriceType::Void != **return_type {
-                            return Err(TypeError::UnexpectedType {
-                                expected: *return_type.clone(),
-                                received: BeatriceType::Void,
-                            });
-                        }
-                    }
+                for (ast, span) in body.statements() {
+                    self.check_statement(ast, Some(span));
+                }
+                self.exit_scope();
+                //a body that can end without a return gives no value
+                if !self.block_diverges(body)
+                    && !matches!(*return_type, BeatriceType::Void | BeatriceType::Error)
+                {
+                    return Err(TypeError::MissingReturn(*return_type));
                 }
             }
-            AST::Identifier(s) => {
-                self.typeof_var(s)?;
+            AST::Identifier(_) => {
+                self.ast_typeof_expression(ast)?;
             }
-            AST::Int(_) | AST::Float(_) => {}
-            AST::VarDecl { varname, body, .. } => {
+            AST::Int(_) | AST::Float(_) | AST::Str(_) | AST::Bool(_) => {}
+            AST::VarDecl {
+                pattern,
+                kindof: Some(kindof),
+                body,
+                ..
+            } => {
+                let expected = self.t_abstract_from_primitive(kindof)?;
+                let kind = self.ast_check_expression(body, &expected)?;
+                self.check_irrefutable_pattern(pattern, &kind)?;
+            }
+            AST::VarDecl { pattern, body, .. } => {
                 let kind = if matches!(&**body, AST::Block(_) | AST::If { .. }) {
                     self.enter_scope();
                     self.generate_metadata(body)?;
@@ -254,35 +490,42 @@ impl BeatriceTranspiler {
                     self.exit_scope();
                     t
                 } else {
+                    self.generate_metadata(body)?;
                     self.ast_typeof_expression(body)?
                 };
-                self.current_scope_mut()
-                    .define_variable(varname.clone(), kind);
+                self.check_irrefutable_pattern(pattern, &kind)?;
             }
 
             AST::BinExpr(lhs, rhs, _) => {
                 //checks if the bin expr is valid
                 self.generate_metadata(lhs)?;
                 self.generate_metadata(rhs)?;
+                self.ast_typeof_expression(ast)?;
             }
-            AST::Return(r) => self.generate_metadata(r)?,
-            AST::FunctionCall { name, args } => {
-                let BeatriceType::Function { params, .. } = self.typeof_function(name)? else {
-                    panic!("What in the fuck? This isn't a function");
-                };
-                for (idx, param) in params.iter().enumerate() {
-                    let arg_type = self.ast_typeof_expression(&args[idx])?;
-                    if arg_type != *param {
-                        return Err(TypeError::UnexpectedType {
-                            expected: param.clone(),
-                            received: arg_type,
-                        });
-                    }
-                }
+            AST::Return(_) | AST::Break | AST::Panic(_) | AST::Loop(_) => {
+                self.ast_typeof_expression(ast)?;
             }
-            AST::Struct { name, .. } => {
-                let stype = self.ast_typeof_struct(ast)?;
-                self.current_scope_mut().define_struct(name.clone(), stype);
+            AST::Cast { expr, .. } => {
+                self.generate_metadata(expr)?;
+                self.ast_typeof_expression(ast)?;
+            }
+            AST::FunctionCall { .. } => {
+                self.ast_typeof_expression(ast)?;
+            }
+            AST::Struct {
+                name,
+                fields,
+                attributes,
+                ..
+            } => {
+                //the types of a program are declared before checking it, so it's only declared here
+                //when checked alone
+                if !self.current_scope().has_struct(name) {
+                    self.declare_types([(ast, self.span())]);
+                }
+                if let Some(display) = attributes.iter().find(|a| a.is("Display")) {
+                    self.check_display(name, fields, display)?;
+                }
             }
             AST::StructExpr { .. } => {
                 self.ast_typeof_expression(ast)?;
@@ -293,17 +536,38 @@ impl BeatriceTranspiler {
                 elseblock,
             } => {
                 self.generate_metadata(expr)?;
-                self.generate_metadata(block)?;
+                let (block_narrowing, else_narrowing) = self.narrowings(expr);
+                self.with_narrowing(block_narrowing, |t| t.generate_metadata(block))?;
                 if let Some(elsebranch) = elseblock {
-                    self.generate_metadata(elsebranch)?;
+                    self.with_narrowing(else_narrowing, |t| t.generate_metadata(elsebranch))?;
+                }
+            }
+            AST::Block(block) => {
+                for (expr, span) in block.statements() {
+                    self.check_statement(expr, Some(span));
                 }
             }
-            AST::Block(exprs) => {
-                for expr in exprs {
-                    self.generate_metadata(expr)?;
+            AST::Enum { name, .. } => {
+                if self.current_scope().enum_def(name).is_none() {
+                    self.declare_types([(ast, self.span())]);
                 }
             }
-            AST::Loop(ast) => self.generate_metadata(ast)?,
+            AST::VariantExpr { .. }
+            | AST::Match { .. }
+            | AST::Tuple(_)
+            | AST::TupleIndex { .. }
+            | AST::FieldAccess { .. }
+            | AST::Some(_)
+            | AST::None
+            | AST::Interpolated(_)
+            | AST::Ok(_)
+            | AST::Err(_)
+            | AST::Try(_) => {
+                self.ast_typeof_expression(ast)?;
+            }
+            AST::Attributed { ast, .. } | AST::Export(ast) => self.generate_metadata(ast)?,
+            //imports are declared along the other globals, before any statement is checked
+            AST::Import { .. } => {}
         };
         Ok(())
     }
@@ -1,2 +1,16 @@
+pub mod arguments;
+pub mod casts;
 pub mod checker;
+pub mod declarations;
+pub mod enums;
+pub mod expected;
+pub mod flow;
+pub mod generics;
 pub mod metadata;
+pub mod operators;
+pub mod options;
+pub mod patterns;
+pub mod recovery;
+pub mod results;
+pub mod strings;
+pub mod structs;
@@ -0,0 +1,174 @@
+//The types binary operators take and give. Arithmetic needs both operands to be the same numeric
+//type, so an int is never mixed with a float without a conversion. As every number is a js
+//number, the operations whose result could be out of their type are adapted when emitted: the
+//divisions and powers of ints are truncated, their bitwise operators are computed on 64 bits
+//instead of the 32 js keeps, the operations of sized integers wrap around their range and the
+//ones of f32s are rounded to single precision. In bigint mode, ints are bigints wrapping around
+//64 bits
+
+use crate::{
+    parser::{AST, Operator},
+    transpiler::{BeatriceType, Bound, TypeError, transpiler::BeatriceTranspiler},
+};
+
+impl BeatriceTranspiler {
+    ///Whether values of kind satisfy bound, or are strings when strings is true
+    fn operand_is(&self, kind: &BeatriceType, bound: Bound, strings: bool) -> bool {
+        (strings && *kind == BeatriceType::String) || self.satisfies(kind, bound)
+    }
+
+    ///The type given by operator when used on values of the types lhs and rhs. None if it can't
+    ///be used on them
+    fn operation_type(
+        &self,
+        operator: &Operator,
+        lhs: &BeatriceType,
+        rhs: &BeatriceType,
+    ) -> Option<BeatriceType> {
+        let same = lhs == rhs;
+        match operator {
+            //strings can be concatenated
+            Operator::Add(false) if same && self.operand_is(lhs, Bound::Numeric, true) => {
+                Some(lhs.clone())
+            }
+            //a generic number must be known to be an integer, whose divisions and powers are
+            //truncated, or a float, whose aren't
+            Operator::Slash(false) | Operator::Pow(false)
+                if same && matches!(lhs, BeatriceType::TypeVar(_)) =>
+            {
+                (self.satisfies(lhs, Bound::Integer) || self.satisfies(lhs, Bound::Floating))
+                    .then(|| lhs.clone())
+            }
+            Operator::Sub(false)
+            | Operator::Star(false)
+            | Operator::Slash(false)
+            | Operator::Pow(false)
+            | Operator::Mod(false)
+                if same && self.operand_is(lhs, Bound::Numeric, false) =>
+            {
+                Some(lhs.clone())
+            }
+            Operator::BitAnd(false)
+            | Operator::BitOr(false)
+            | Operator::Xor(false)
+            | Operator::BitLeft(false)
+            | Operator::BitRight(false)
+                if same && self.operand_is(lhs, Bound::Integer, false) =>
+            {
+                Some(lhs.clone())
+            }
+            Operator::Lt(_) | Operator::Gt(_)
+                if same && self.operand_is(lhs, Bound::Numeric, true) =>
+            {
+                Some(BeatriceType::Bool)
+            }
+            //x != None compares an int? with the None option
+            Operator::Eq(true) | Operator::Bang(true) if lhs.join(rhs).is_some() => {
+                Some(BeatriceType::Bool)
+            }
+            Operator::And(false) | Operator::Or(false)
+                if *lhs == BeatriceType::Bool && *rhs == BeatriceType::Bool =>
+            {
+                Some(BeatriceType::Bool)
+            }
+            _ => None,
+        }
+    }
+
+    ///Whether the js result of operator on values of kind must be adapted to be a value of kind
+    fn needs_adaptation(&self, operator: &Operator, kind: &BeatriceType) -> bool {
+        let arithmetic = matches!(
+            operator,
+            Operator::Add(false)
+                | Operator::Sub(false)
+                | Operator::Star(false)
+                | Operator::Slash(false)
+                | Operator::Pow(false)
+        );
+        match kind {
+            //bigints wrap as the 64 bit integers they are, and their shifts are bounded to 64
+            //bits. Their divisions are already truncated
+            _ if self.is_bigint(kind) => {
+                arithmetic
+                    || matches!(
+                        operator,
+                        Operator::BitLeft(false) | Operator::BitRight(false)
+                    )
+            }
+            //ints are too wide to wrap, only their fractional part is dropped. Their bitwise
+            //operators are computed on 64 bits, as the ones of js keep 32
+            BeatriceType::Int => matches!(
+                operator,
+                Operator::Slash(false)
+                    | Operator::Pow(false)
+                    | Operator::BitLeft(false)
+                    | Operator::BitRight(false)
+                    | Operator::BitAnd(false)
+                    | Operator::BitOr(false)
+                    | Operator::Xor(false)
+            ),
+            BeatriceType::F32 => arithmetic,
+            //js bitwise operators already give an i32
+            BeatriceType::I32 => arithmetic,
+            //and the values they give from smaller integers are in their range, but shifting left
+            BeatriceType::I8 | BeatriceType::I16 | BeatriceType::U8 | BeatriceType::U16 => {
+                arithmetic || matches!(operator, Operator::BitLeft(false))
+            }
+            //but an u32 can be above the largest i32, and an u64 also goes negative
+            BeatriceType::U32 | BeatriceType::U64 => !matches!(operator, Operator::Mod(false)),
+            _ => false,
+        }
+    }
+
+    ///Generates the types of the sides of a binary expression. A literal takes the type of the
+    ///other side, so x + 1 works for any numeric x. That side is typed first, as the literal may
+    ///only fit in its type, as 2^64 - 1 does in an u64
+    fn ast_typeof_operands(
+        &mut self,
+        lhs: &AST,
+        rhs: &AST,
+    ) -> Result<(BeatriceType, BeatriceType), TypeError> {
+        if let AST::Int(_) | AST::Float(_) = lhs {
+            let rhs_type = self.ast_typeof_expression(rhs)?;
+            if self.literal_fits(lhs, &rhs_type) {
+                return Ok((rhs_type.clone(), rhs_type));
+            }
+            return Ok((self.ast_typeof_expression(lhs)?, rhs_type));
+        }
+        let lhs_type = self.ast_typeof_expression(lhs)?;
+        if self.literal_fits(rhs, &lhs_type) {
+            return Ok((lhs_type.clone(), lhs_type));
+        }
+        Ok((lhs_type, self.ast_typeof_expression(rhs)?))
+    }
+
+    ///Generates the type of a binary expression
+    pub(crate) fn ast_typeof_binexpr(&mut self, expr: &AST) -> Result<BeatriceType, TypeError> {
+        let AST::BinExpr(lhs, rhs, operator) = expr else {
+            panic!("This is a bug. Expected receiving a BinExpr");
+        };
+        if let Operator::Coalesce = operator {
+            return self.ast_typeof_coalesce(lhs, rhs);
+        }
+        let (lhs_type, rhs_type) = self.ast_typeof_operands(lhs, rhs)?;
+        if lhs_type.is_error() || rhs_type.is_error() {
+            return Ok(BeatriceType::Error);
+        }
+        let Some(kind) = self.operation_type(operator, &lhs_type, &rhs_type) else {
+            return Err(TypeError::InvalidOperands {
+                operator: operator.clone(),
+                lhs: lhs_type,
+                rhs: rhs_type,
+            });
+        };
+        //a generic integer is emitted as an int, nothing tells which integer it is
+        let numeric = match &kind {
+            BeatriceType::TypeVar(_) if self.satisfies(&kind, Bound::Integer) => BeatriceType::Int,
+            _ => kind.clone(),
+        };
+        if self.needs_adaptation(operator, &numeric) {
+            self.define_numeric(expr, numeric);
+        }
+        Ok(kind)
+    }
+}
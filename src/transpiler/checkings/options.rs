@@ -0,0 +1,211 @@
+//Options are emitted as the value they hold or undefined, so an option of an option can't tell
+//Some(None) from None. Because of that, T?? is the same type as T?
+
+use crate::{
+    parser::{AST, Operator},
+    transpiler::{BeatriceType, TypeError, resolver::SymbolId, transpiler::BeatriceTranspiler},
+};
+
+///A variable known to hold a value inside some block, with the type of the value
+pub(crate) type Narrowing = Option<(SymbolId, BeatriceType)>;
+
+impl BeatriceType {
+    ///The type of the options holding values of inner
+    pub fn option(inner: BeatriceType) -> Self {
+        match inner {
+            Self::Option(_) => inner,
+            _ => Self::Option(Box::new(inner)),
+        }
+    }
+
+    ///Whether a value of type received can be used where one of this type is expected. A T can be
+    ///used as a T?, but a T? can't be used as a T
+    pub fn accepts(&self, received: &BeatriceType) -> bool {
+        match (self, received) {
+            (_, Self::Never) | (Self::Error, _) | (_, Self::Error) => true,
+            (Self::Option(expected), Self::Option(received)) => expected.accepts(received),
+            (Self::Option(expected), received) => expected.accepts(received),
+            (Self::Result { ok, err }, Self::Result { ok: rok, err: rerr }) => {
+                ok.accepts(rok) && err.accepts(rerr)
+            }
+            (Self::Tuple(expected), Self::Tuple(received)) => {
+                expected.len() == received.len()
+                    && expected.iter().zip(received).all(|(e, r)| e.accepts(r))
+            }
+            _ => self == received,
+        }
+    }
+
+    ///The type of a value that may come from either branch, such as an if and its else. None
+    ///if the branches disagree
+    pub fn join(&self, other: &BeatriceType) -> Option<BeatriceType> {
+        if self.accepts(other) {
+            return Some(self.clone());
+        }
+        if other.accepts(self) {
+            return Some(other.clone());
+        }
+        //if x { Ok(1) } else { Err(2) } is a Result<int, int>
+        if let (
+            Self::Result { ok, err },
+            Self::Result {
+                ok: other_ok,
+                err: other_err,
+            },
+        ) = (self, other)
+        {
+            return Some(Self::Result {
+                ok: Box::new(ok.join(other_ok)?),
+                err: Box::new(err.join(other_err)?),
+            });
+        }
+        //if x { 1 } else { None } is an int?
+        if matches!(self, Self::Option(_)) || matches!(other, Self::Option(_)) {
+            let (lhs, rhs) = (Self::option(self.clone()), Self::option(other.clone()));
+            if lhs.accepts(&rhs) {
+                return Some(lhs);
+            }
+            if rhs.accepts(&lhs) {
+                return Some(rhs);
+            }
+        }
+        None
+    }
+}
+
+impl BeatriceTranspiler {
+    ///Generates the type of the value an expression accesses a field or element of. `?.` needs an
+    ///option, which is looked into
+    fn ast_typeof_accessed(
+        &mut self,
+        expr: &AST,
+        optional: bool,
+    ) -> Result<BeatriceType, TypeError> {
+        match self.ast_typeof_expression(expr)? {
+            BeatriceType::Error => Ok(BeatriceType::Error),
+            BeatriceType::Option(inner) if optional => Ok(*inner),
+            target if optional => Err(TypeError::ExpectedOption(target)),
+            target => Ok(target),
+        }
+    }
+
+    ///Generates the type of an access such as point.x, pair.0, point?.x or pair?.0. The `?.` ones
+    ///give an option, as the accessed value may be None
+    pub(crate) fn ast_typeof_access(&mut self, ast: &AST) -> Result<BeatriceType, TypeError> {
+        let (field_type, optional) = match ast {
+            AST::FieldAccess {
+                expr,
+                field,
+                optional,
+            } => match self.ast_typeof_accessed(expr, *optional)? {
+                BeatriceType::Error => return Ok(BeatriceType::Error),
+                target @ BeatriceType::Struct { .. }
+                    if let Some(kind) = self.struct_def_of(&target)?.fields.remove(field) =>
+                {
+                    (kind, *optional)
+                }
+                target => {
+                    return Err(TypeError::InvalidFieldAccess {
+                        target,
+                        field: field.clone(),
+                    });
+                }
+            },
+            AST::TupleIndex {
+                expr,
+                index,
+                optional,
+            } => match self.ast_typeof_accessed(expr, *optional)? {
+                BeatriceType::Error => return Ok(BeatriceType::Error),
+                BeatriceType::Tuple(mut types) if *index < types.len() => {
+                    (types.swap_remove(*index), *optional)
+                }
+                target => {
+                    return Err(TypeError::InvalidTupleIndex {
+                        target,
+                        index: *index,
+                    });
+                }
+            },
+            _ => panic!("This is a bug. Expected to receive a field access or a tuple index"),
+        };
+        Ok(if optional {
+            BeatriceType::option(field_type)
+        } else {
+            field_type
+        })
+    }
+
+    ///Generates the type of lhs ?? rhs. lhs must be an option, and the result is only an option
+    ///if rhs is one too
+    pub(crate) fn ast_typeof_coalesce(
+        &mut self,
+        lhs: &AST,
+        rhs: &AST,
+    ) -> Result<BeatriceType, TypeError> {
+        let inner = match self.ast_typeof_expression(lhs)? {
+            BeatriceType::Option(inner) => inner,
+            BeatriceType::Error => {
+                self.ast_typeof_expression(rhs)?;
+                return Ok(BeatriceType::Error);
+            }
+            lhs_type => return Err(TypeError::ExpectedOption(lhs_type)),
+        };
+        //rhs is expected to be a value of the option, as the 5 of a float?, but it can also be
+        //another option
+        let rhs_type = match self.ast_check_expression(rhs, &inner) {
+            Err(TypeError::UnexpectedType {
+                received: received @ BeatriceType::Option(_),
+                ..
+            }) => received,
+            checked => checked?,
+        };
+        inner.join(&rhs_type).ok_or(TypeError::UnexpectedType {
+            expected: *inner,
+            received: rhs_type,
+        })
+    }
+
+    ///The variable an if condition such as `x != None` proves to hold a value, with the type of
+    ///the value. The first one is proved inside the if block and the second one inside the else
+    pub(crate) fn narrowings(&self, condition: &AST) -> (Narrowing, Narrowing) {
+        let AST::BinExpr(lhs, rhs, op) = condition else {
+            return (None, None);
+        };
+        let identifier = match (&**lhs, &**rhs) {
+            (identifier @ AST::Identifier(_), AST::None)
+            | (AST::None, identifier @ AST::Identifier(_)) => identifier,
+            _ => return (None, None),
+        };
+        let Some(id) = self.resolution().use_of(identifier) else {
+            return (None, None);
+        };
+        let Some(BeatriceType::Option(inner)) = self.symbol_type(id) else {
+            return (None, None);
+        };
+        let narrowing = Some((id, *inner.clone()));
+        match op {
+            Operator::Bang(true) => (narrowing, None),
+            Operator::Eq(true) => (None, narrowing),
+            _ => (None, None),
+        }
+    }
+
+    ///Runs f while the variable is known to hold a value, if there's one
+    pub(crate) fn with_narrowing<T>(
+        &mut self,
+        narrowing: Narrowing,
+        f: impl FnOnce(&mut Self) -> Result<T, TypeError>,
+    ) -> Result<T, TypeError> {
+        let Some((id, kind)) = narrowing else {
+            return f(self);
+        };
+        //only variables with a type are narrowed, so there's always one to restore
+        let previous = self.define_symbol_type(id, kind);
+        let out = f(self);
+        if let Some(previous) = previous {
+            self.define_symbol_type(id, previous);
+        }
+        out
+    }
+}
@@ -0,0 +1,483 @@
+use std::collections::HashSet;
+
+use crate::{
+    parser::{AST, KeyPatternPair, Pattern, VariantPattern},
+    transpiler::{BeatriceType, TypeError, transpiler::BeatriceTranspiler},
+};
+
+use super::enums::VariantShape;
+
+///A pattern reduced to what matters for exhaustiveness. Literals and ranges never cover all the
+///values of a number, so they are all alike. true and false are the two constructors of bool
+#[derive(Debug, Clone)]
+enum Space {
+    Any,
+    Literal,
+    ///One of the ways to build a value, such as the tag of a variant, with the spaces of its
+    ///fields in the order they were declared
+    Constructor(usize, Vec<Space>),
+}
+
+impl BeatriceTranspiler {
+    ///Checks the patterns of the fields of a struct or struct-like variant against the declared
+    ///fields
+    fn check_fields_pattern(
+        &mut self,
+        target: String,
+        types: &[(String, BeatriceType)],
+        fields: &[KeyPatternPair],
+        rest: bool,
+    ) -> Result<(), TypeError> {
+        for field in fields {
+            let Some((_, kind)) = types.iter().find(|(k, _)| *k == field.key) else {
+                return Err(TypeError::InvalidFieldName {
+                    field: field.key.clone(),
+                    target_struct: target,
+                });
+            };
+            self.check_pattern(&field.pattern, kind)?;
+        }
+        let missing = types
+            .iter()
+            .filter(|(k, _)| !fields.iter().any(|f| f.key == *k))
+            .map(|(k, _)| k.clone())
+            .collect::<Vec<_>>();
+        if !rest && !missing.is_empty() {
+            return Err(TypeError::NotCorrectFields {
+                fields: missing,
+                target,
+            });
+        }
+        Ok(())
+    }
+
+    ///Checks that pattern can match values of type expected, defining the types of the variables
+    ///it binds
+    pub(crate) fn check_pattern(
+        &mut self,
+        pattern: &Pattern,
+        expected: &BeatriceType,
+    ) -> Result<(), TypeError> {
+        let invalid = || TypeError::InvalidPattern {
+            pattern: pattern.to_string(),
+            expected: expected.clone(),
+        };
+        //nothing is known of the values of a type that failed to check
+        if expected.is_error() {
+            self.poison(pattern);
+            return Ok(());
+        }
+        match pattern {
+            Pattern::Wildcard => {}
+            Pattern::Binding(_) => {
+                let Some(id) = self.resolution().binding_of(pattern) else {
+                    panic!("This is a bug. Expected every binding to be resolved");
+                };
+                self.define_symbol_type(id, expected.clone());
+            }
+            Pattern::Int(i)
+                if expected
+                    .integer_range()
+                    .is_some_and(|(min, max)| (min..=max).contains(i)) => {}
+            Pattern::Bool(_) if *expected == BeatriceType::Bool => {}
+            Pattern::Float(_) if *expected == BeatriceType::Float => {}
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                self.check_pattern(start, expected)?;
+                self.check_pattern(end, expected)?;
+                let empty = match (&**start, &**end) {
+                    (Pattern::Int(s), Pattern::Int(e)) => s > e || (s == e && !inclusive),
+                    (Pattern::Float(s), Pattern::Float(e)) => s > e || (s == e && !inclusive),
+                    _ => false,
+                };
+                if empty {
+                    return Err(invalid());
+                }
+            }
+            Pattern::Variant {
+                name,
+                variant,
+                fields,
+            } => {
+                if !matches!(expected, BeatriceType::Enum { name: ename, .. } if ename == name) {
+                    return Err(invalid());
+                }
+                let target = format!("{name}::{variant}");
+                let (_, shape) = self.variant_of(expected, variant)?;
+                match (&shape, fields) {
+                    (VariantShape::Unit, VariantPattern::Unit) => {}
+                    (VariantShape::Tuple(types), VariantPattern::Tuple(patterns))
+                        if types.len() == patterns.len() =>
+                    {
+                        for (pattern, kind) in patterns.iter().zip(types) {
+                            self.check_pattern(pattern, kind)?;
+                        }
+                    }
+                    (VariantShape::Struct(types), VariantPattern::Struct { fields, rest }) => {
+                        self.check_fields_pattern(target, types, fields, *rest)?
+                    }
+                    _ => {
+                        return Err(TypeError::WrongVariantFields {
+                            target,
+                            expected: shape.len(),
+                            received: match fields {
+                                VariantPattern::Unit => 0,
+                                VariantPattern::Tuple(patterns) => patterns.len(),
+                                VariantPattern::Struct { fields, .. } => fields.len(),
+                            },
+                        });
+                    }
+                }
+            }
+            Pattern::Struct { name, fields, rest } => {
+                let def = self
+                    .struct_def(name)
+                    .ok_or(TypeError::NotRecognizedType(name.clone()))?;
+                //only instances of the named struct can be destructured with its name, even if
+                //another struct has the same fields
+                if !matches!(expected, BeatriceType::Struct { id, .. } if *id == def.id) {
+                    return Err(invalid());
+                }
+                let types = self.struct_def_of(expected)?.ordered_fields();
+                self.check_fields_pattern(name.clone(), &types, fields, *rest)?;
+            }
+            Pattern::Tuple(patterns) => {
+                let BeatriceType::Tuple(types) = expected else {
+                    return Err(invalid());
+                };
+                if types.len() != patterns.len() {
+                    return Err(invalid());
+                }
+                for (pattern, kind) in patterns.iter().zip(types) {
+                    self.check_pattern(pattern, kind)?;
+                }
+            }
+            Pattern::Some(pattern) => {
+                let BeatriceType::Option(inner) = expected else {
+                    return Err(invalid());
+                };
+                self.check_pattern(pattern, inner)?;
+            }
+            Pattern::None if matches!(expected, BeatriceType::Option(_)) => {}
+            Pattern::Ok(inner) | Pattern::Err(inner) => {
+                let BeatriceType::Result { ok, err } = expected else {
+                    return Err(invalid());
+                };
+                let kind = if let Pattern::Ok(_) = pattern {
+                    ok
+                } else {
+                    err
+                };
+                self.check_pattern(inner, kind)?;
+            }
+            //there are no array types yet, so no value can be destructured with them
+            Pattern::Array(_)
+            | Pattern::Bool(_)
+            | Pattern::Int(_)
+            | Pattern::Float(_)
+            | Pattern::None => {
+                return Err(invalid());
+            }
+        }
+        Ok(())
+    }
+
+    ///Checks a pattern that must match every value of the type, as the ones of `let` and
+    ///parameters, defining the variables it binds
+    pub(crate) fn check_irrefutable_pattern(
+        &mut self,
+        pattern: &Pattern,
+        kind: &BeatriceType,
+    ) -> Result<(), TypeError> {
+        self.check_pattern(pattern, kind)?;
+        let rows = [vec![self.pattern_space(pattern, kind)?]];
+        if let Some(mut missing) = self.missing_values(&rows, std::slice::from_ref(kind))? {
+            return Err(TypeError::RefutablePattern {
+                pattern: pattern.to_string(),
+                missing: missing.remove(0),
+            });
+        }
+        Ok(())
+    }
+
+    ///Reduces an already checked pattern to its space
+    fn pattern_space(&self, pattern: &Pattern, kind: &BeatriceType) -> Result<Space, TypeError> {
+        if kind.is_error() {
+            return Ok(Space::Any);
+        }
+        Ok(match pattern {
+            Pattern::Wildcard | Pattern::Binding(_) => Space::Any,
+            Pattern::Bool(b) => Space::Constructor(usize::from(*b), Vec::new()),
+            Pattern::Int(_) | Pattern::Float(_) | Pattern::Range { .. } => Space::Literal,
+            Pattern::Variant {
+                variant, fields, ..
+            } => {
+                let (idx, shape) = self.variant_of(kind, variant)?;
+                let spaces = match (&shape, fields) {
+                    (VariantShape::Tuple(types), VariantPattern::Tuple(patterns)) => patterns
+                        .iter()
+                        .zip(types)
+                        .map(|(p, t)| self.pattern_space(p, t))
+                        .collect::<Result<_, _>>()?,
+                    (VariantShape::Struct(types), VariantPattern::Struct { fields, .. }) => {
+                        self.fields_space(types, fields)?
+                    }
+                    _ => Vec::new(),
+                };
+                Space::Constructor(idx, spaces)
+            }
+            Pattern::Struct { fields, .. } => {
+                let types = self.struct_def_of(kind)?.ordered_fields();
+                Space::Constructor(0, self.fields_space(&types, fields)?)
+            }
+            Pattern::Tuple(patterns) => {
+                let BeatriceType::Tuple(types) = kind else {
+                    unreachable!()
+                };
+                Space::Constructor(
+                    0,
+                    patterns
+                        .iter()
+                        .zip(types)
+                        .map(|(p, t)| self.pattern_space(p, t))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            Pattern::Array(_) => Space::Literal,
+            Pattern::None => Space::Constructor(0, Vec::new()),
+            Pattern::Some(pattern) => {
+                let BeatriceType::Option(inner) = kind else {
+                    unreachable!()
+                };
+                Space::Constructor(1, vec![self.pattern_space(pattern, inner)?])
+            }
+            Pattern::Ok(inner) | Pattern::Err(inner) => {
+                let BeatriceType::Result { ok, err } = kind else {
+                    unreachable!()
+                };
+                if let Pattern::Ok(_) = pattern {
+                    Space::Constructor(0, vec![self.pattern_space(inner, ok)?])
+                } else {
+                    Space::Constructor(1, vec![self.pattern_space(inner, err)?])
+                }
+            }
+        })
+    }
+
+    fn fields_space(
+        &self,
+        types: &[(String, BeatriceType)],
+        fields: &[KeyPatternPair],
+    ) -> Result<Vec<Space>, TypeError> {
+        types
+            .iter()
+            .map(|(key, t)| match fields.iter().find(|f| f.key == *key) {
+                Some(field) => self.pattern_space(&field.pattern, t),
+                None => Ok(Space::Any),
+            })
+            .collect()
+    }
+
+    ///The ways to build a value of the type, with the types of their fields. None for types
+    ///whose values can't be listed, such as numbers
+    fn constructors(
+        &self,
+        kind: &BeatriceType,
+    ) -> Result<Option<Vec<Vec<BeatriceType>>>, TypeError> {
+        Ok(match kind {
+            //false and true, with no fields
+            BeatriceType::Bool => Some(vec![Vec::new(), Vec::new()]),
+            BeatriceType::Enum { name, .. } => {
+                let def = self
+                    .enum_def(name)
+                    .ok_or(TypeError::NotRecognizedType(name.clone()))?;
+                let mut out = Vec::with_capacity(def.variants.len());
+                for variant in &def.variants {
+                    out.push(self.variant_of(kind, &variant.name)?.1.field_types());
+                }
+                Some(out)
+            }
+            BeatriceType::Struct { .. } => {
+                let def = self.struct_def_of(kind)?;
+                Some(vec![
+                    def.ordered_fields().into_iter().map(|(_, t)| t).collect(),
+                ])
+            }
+            BeatriceType::Tuple(types) => Some(vec![types.clone()]),
+            BeatriceType::Option(inner) => Some(vec![Vec::new(), vec![*inner.clone()]]),
+            BeatriceType::Result { ok, err } => Some(vec![vec![*ok.clone()], vec![*err.clone()]]),
+            _ => None,
+        })
+    }
+
+    ///Writes a pattern of the values built by the given constructor, with the patterns of its
+    ///fields
+    fn constructor_witness(&self, kind: &BeatriceType, idx: usize, fields: Vec<String>) -> String {
+        match kind {
+            BeatriceType::Enum { name, .. } => {
+                let variant = &self.enum_def(name).unwrap().variants[idx];
+                match &variant.shape {
+                    VariantShape::Unit => format!("{name}::{}", variant.name),
+                    VariantShape::Tuple(_) => {
+                        format!("{name}::{}({})", variant.name, fields.join(", "))
+                    }
+                    VariantShape::Struct(types) => {
+                        let fields = types
+                            .iter()
+                            .zip(fields)
+                            .map(|((key, _), field)| format!("{key}: {field}"))
+                            .collect::<Vec<_>>();
+                        format!("{name}::{} {{ {} }}", variant.name, fields.join(", "))
+                    }
+                }
+            }
+            BeatriceType::Struct { name, .. } => {
+                let def = self.struct_def_of(kind).unwrap();
+                let fields = def
+                    .order
+                    .iter()
+                    .zip(fields)
+                    .map(|(key, field)| format!("{key}: {field}"))
+                    .collect::<Vec<_>>();
+                format!("{name} {{ {} }}", fields.join(", "))
+            }
+            BeatriceType::Bool => (idx == 1).to_string(),
+            BeatriceType::Tuple(_) if fields.len() == 1 => format!("({},)", fields[0]),
+            BeatriceType::Tuple(_) => format!("({})", fields.join(", ")),
+            BeatriceType::Option(_) if idx == 0 => "None".to_string(),
+            BeatriceType::Option(_) => format!("Some({})", fields[0]),
+            BeatriceType::Result { .. } if idx == 0 => format!("Ok({})", fields[0]),
+            BeatriceType::Result { .. } => format!("Err({})", fields[0]),
+            _ => unreachable!(),
+        }
+    }
+
+    ///Searches for values, one per column of rows, that none of the rows match. Returns patterns
+    ///of such values if there are any. This is the usefulness algorithm for a row of wildcards:
+    ///a column whose rows use every constructor of its type is split by constructor, any other
+    ///column only keeps the rows that match everything on it
+    fn missing_values(
+        &self,
+        rows: &[Vec<Space>],
+        kinds: &[BeatriceType],
+    ) -> Result<Option<Vec<String>>, TypeError> {
+        let Some((kind, rest_kinds)) = kinds.split_first() else {
+            return Ok(rows.is_empty().then(Vec::new));
+        };
+        let used = rows
+            .iter()
+            .filter_map(|row| match &row[0] {
+                Space::Constructor(idx, _) => Some(*idx),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        let constructors = self.constructors(kind)?;
+
+        if let Some(constructors) = &constructors
+            && used.len() == constructors.len()
+        {
+            for (idx, field_types) in constructors.iter().enumerate() {
+                let arity = field_types.len();
+                let specialized = rows
+                    .iter()
+                    .filter_map(|row| {
+                        let fields = match &row[0] {
+                            Space::Constructor(i, fields) if *i == idx => fields.clone(),
+                            Space::Any => vec![Space::Any; arity],
+                            _ => return None,
+                        };
+                        Some(fields.into_iter().chain(row[1..].iter().cloned()).collect())
+                    })
+                    .collect::<Vec<Vec<Space>>>();
+                let field_kinds = field_types
+                    .iter()
+                    .chain(rest_kinds)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if let Some(mut witness) = self.missing_values(&specialized, &field_kinds)? {
+                    let rest = witness.split_off(arity);
+                    let mut out = vec![self.constructor_witness(kind, idx, witness)];
+                    out.extend(rest);
+                    return Ok(Some(out));
+                }
+            }
+            return Ok(None);
+        }
+
+        let default = rows
+            .iter()
+            .filter(|row| matches!(row[0], Space::Any))
+            .map(|row| row[1..].to_vec())
+            .collect::<Vec<_>>();
+        let Some(rest) = self.missing_values(&default, rest_kinds)? else {
+            return Ok(None);
+        };
+        let unused = constructors
+            .iter()
+            .flatten()
+            .enumerate()
+            .find(|(idx, _)| !used.contains(idx));
+        let head = match unused {
+            Some((idx, field_types)) if !used.is_empty() => {
+                self.constructor_witness(kind, idx, vec!["_".to_string(); field_types.len()])
+            }
+            _ => "_".to_string(),
+        };
+        let mut out = vec![head];
+        out.extend(rest);
+        Ok(Some(out))
+    }
+
+    ///Generates the type of a match, checking the patterns and guards of the arms, that every arm
+    ///has the same type and that every possible value is matched by some arm
+    pub(crate) fn ast_typeof_match(&mut self, ast: &AST) -> Result<BeatriceType, TypeError> {
+        let AST::Match { expr, arms } = ast else {
+            panic!("This is a bug. Expected to receive a match");
+        };
+        self.generate_metadata(expr)?;
+        let kind = self.ast_typeof_expression(expr)?;
+        let mut match_type: Option<BeatriceType> = None;
+        let mut rows = Vec::with_capacity(arms.len());
+        for (idx, arm) in arms.iter().enumerate() {
+            self.enter_scope();
+            self.check_pattern(&arm.pattern, &kind)?;
+            if let Some(guard) = &arm.guard {
+                self.generate_metadata(guard)?;
+            }
+            if !matches!(arm.body, AST::Match { .. }) {
+                self.generate_metadata(&arm.body)?;
+            }
+            let arm_type = self.ast_typeof_expression(&arm.body)?;
+            self.exit_scope();
+            match &match_type {
+                Some(expected) => match expected.join(&arm_type) {
+                    Some(joined) => match_type = Some(joined),
+                    None => {
+                        return Err(TypeError::MatchArmWrong {
+                            arm: idx,
+                            expected: Box::new(expected.clone()),
+                            received: Box::new(arm_type),
+                        });
+                    }
+                },
+                None => match_type = Some(arm_type),
+            }
+            //guarded arms may not match, so they don't cover anything
+            if arm.guard.is_none() {
+                rows.push(vec![self.pattern_space(&arm.pattern, &kind)?]);
+            }
+        }
+        //nothing tells which values a type that failed to check has
+        if !kind.is_error()
+            && let Some(mut missing) = self.missing_values(&rows, &[kind])?
+        {
+            return Err(TypeError::NonExhaustiveMatch {
+                missing: missing.remove(0),
+            });
+        }
+        Ok(match_type.unwrap_or(BeatriceType::Void))
+    }
+}
@@ -0,0 +1,69 @@
+//A type error doesn't stop the check of a program. It's reported, and the statement it was found
+//in is given the Error type, as are the variables the statement declares. Every check accepts
+//values of the Error type and gives the Error type back without reporting anything, so an error is
+//reported once, and not again by every expression using its value.
+
+use crate::{
+    parser::{AST, Pattern, Span},
+    transpiler::{BeatriceType, TypeError, transpiler::BeatriceTranspiler},
+};
+
+impl BeatriceTranspiler {
+    ///The checked type, or the Error type after reporting why it couldn't be checked
+    pub(crate) fn recover(&mut self, checked: Result<BeatriceType, TypeError>) -> BeatriceType {
+        checked.unwrap_or_else(|error| {
+            self.report(error);
+            BeatriceType::Error
+        })
+    }
+
+    ///Runs check for the statement starting at span, or for the one being checked if there's
+    ///none, reporting its error instead of returning it. The scopes check entered are left
+    pub(crate) fn recovering<T>(
+        &mut self,
+        span: Option<Span>,
+        check: impl FnOnce(&mut Self) -> Result<T, TypeError>,
+    ) -> Option<T> {
+        let previous = span.map(|span| self.set_span(span));
+        let depth = self.scopes().len();
+        let out = match check(self) {
+            Ok(out) => Some(out),
+            Err(error) => {
+                self.report(error);
+                while self.scopes().len() > depth {
+                    self.exit_scope();
+                }
+                None
+            }
+        };
+        if let Some(previous) = previous {
+            self.set_span(previous);
+        }
+        out
+    }
+
+    ///Checks a statement, reporting its error instead of returning it. A statement that fails is
+    ///given the Error type, as are the variables it declares
+    pub(crate) fn check_statement(&mut self, ast: &AST, span: Option<Span>) {
+        if self
+            .recovering(span, |t| t.generate_metadata(ast))
+            .is_none()
+        {
+            self.define_type(ast, BeatriceType::Error);
+            if let AST::VarDecl { pattern, .. } = ast {
+                self.poison(pattern);
+            }
+        }
+    }
+
+    ///Gives the Error type to the variables of a pattern that weren't given a type
+    pub(crate) fn poison(&mut self, pattern: &Pattern) {
+        for binding in pattern.binding_patterns() {
+            if let Some(id) = self.resolution().binding_of(binding)
+                && self.symbol_type(id).is_none()
+            {
+                self.define_symbol_type(id, BeatriceType::Error);
+            }
+        }
+    }
+}
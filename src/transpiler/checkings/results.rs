@@ -0,0 +1,32 @@
+use crate::{
+    parser::AST,
+    transpiler::{BeatriceType, TypeError, transpiler::BeatriceTranspiler},
+};
+
+impl BeatriceTranspiler {
+    ///The return type of the innermost function being checked, if any
+    pub(crate) fn enclosing_return_type(&self) -> Option<&BeatriceType> {
+        self.scopes()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.return_type())
+    }
+
+    ///Generates the type of expr?, the value of the result expr. The enclosing function must
+    ///return a result able to hold its error, as it is returned when there's one
+    pub(crate) fn ast_typeof_try(&mut self, expr: &AST) -> Result<BeatriceType, TypeError> {
+        let (ok, err) = match self.ast_typeof_expression(expr)? {
+            BeatriceType::Result { ok, err } => (ok, err),
+            BeatriceType::Error => return Ok(BeatriceType::Error),
+            kind => return Err(TypeError::ExpectedResult(kind)),
+        };
+        match self.enclosing_return_type() {
+            Some(BeatriceType::Result { err: expected, .. }) if expected.accepts(&err) => Ok(*ok),
+            Some(BeatriceType::Error) => Ok(*ok),
+            return_type => Err(TypeError::IncompatibleTry {
+                return_type: return_type.cloned().unwrap_or(BeatriceType::Void),
+                error: *err,
+            }),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//Interpolated values are formatted by the type they have, which is only known while checking. The
+//helper formatting each one is kept by the transpiler until the string is lowered.
+
+use std::collections::VecDeque;
+
+use crate::{
+    parser::{Attribute, KeyTypePair, StringPart},
+    transpiler::{BeatriceType, TypeError, transpiler::BeatriceTranspiler},
+};
+
+use super::structs::StructDef;
+
+impl BeatriceTranspiler {
+    ///The name of the struct with the @Display attribute that values of kind are instances of
+    pub(crate) fn display_struct_of(&self, kind: &BeatriceType) -> Option<String> {
+        let BeatriceType::Struct { id, name, .. } = kind else {
+            return None;
+        };
+        self.scopes()
+            .iter()
+            .rev()
+            .find(|scope| scope.struct_def(name).is_some_and(|def| def.id == *id))
+            .filter(|scope| scope.has_display(name))
+            .map(|_| name.clone())
+    }
+
+    ///The JS function formatting values of kind, as `{}` when debug is false and as `{:?}`
+    ///otherwise. None for values that JS already formats, such as numbers
+    pub(crate) fn formatter(
+        &self,
+        kind: &BeatriceType,
+        debug: bool,
+    ) -> Result<Option<String>, TypeError> {
+        match kind {
+            BeatriceType::Float | BeatriceType::F32 | BeatriceType::Bool | BeatriceType::Error => {
+                Ok(None)
+            }
+            _ if kind.integer_range().is_some() => Ok(None),
+            BeatriceType::String if debug => Ok(Some("JSON.stringify".to_string())),
+            BeatriceType::String => Ok(None),
+            BeatriceType::Struct { .. } if let Some(name) = self.display_struct_of(kind) => {
+                let helper = if debug { "debug" } else { "display" };
+                Ok(Some(format!("{name}${helper}")))
+            }
+            _ => Err(TypeError::NotDisplayable {
+                kind: kind.clone(),
+                debug,
+            }),
+        }
+    }
+
+    ///Checks that the value of every expression of an interpolated string can be formatted
+    pub(crate) fn ast_typeof_interpolated(
+        &mut self,
+        parts: &[StringPart],
+    ) -> Result<BeatriceType, TypeError> {
+        for part in parts {
+            if let StringPart::Expr { expr, debug } = part {
+                self.generate_metadata(expr)?;
+                let kind = self.ast_typeof_expression(expr)?;
+                if let Some(helper) = self.formatter(&kind, *debug)? {
+                    self.define_formatter(expr, helper);
+                }
+            }
+        }
+        Ok(BeatriceType::String)
+    }
+
+    ///Checks the @Display attribute of a struct, making it displayable. Its fields must be
+    ///displayable too, and its argument, if any, must be a string formatting the struct with its
+    ///fields as variables
+    pub(crate) fn check_display(
+        &mut self,
+        name: &str,
+        field_decls: &VecDeque<KeyTypePair>,
+        display: &Attribute,
+    ) -> Result<(), TypeError> {
+        let StructDef { fields, order, .. } = self
+            .struct_def(name)
+            .cloned()
+            .ok_or(TypeError::NotRecognizedType(name.to_string()))?;
+        for field in &order {
+            self.formatter(&fields[field], true)?;
+        }
+        self.current_scope_mut().define_display(name.to_string());
+        let Some(format) = display.args.front() else {
+            return Ok(());
+        };
+        for field in field_decls {
+            if let Some(id) = self.resolution().field_of(field) {
+                self.define_symbol_type(id, fields[&field.key].clone());
+            }
+        }
+        match self.ast_typeof_expression(format)? {
+            BeatriceType::String => Ok(()),
+            received => Err(TypeError::UnexpectedType {
+                expected: BeatriceType::String,
+                received,
+            }),
+        }
+    }
+}
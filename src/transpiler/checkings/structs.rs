@@ -0,0 +1,193 @@
+//Structs are nominal: their types are told apart by the declaration they come from, not by their
+//fields, so two structs with the same fields are still different types. The fields are kept by
+//the scope declaring the struct, which lets a struct refer to itself, as `next: Node?` does.
+
+use std::collections::HashMap;
+
+use crate::{
+    parser::AST,
+    transpiler::{BeatriceType, TypeError, transpiler::BeatriceTranspiler},
+};
+
+use super::generics::Substitution;
+
+///A struct declaration after the types of its fields were resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructDef {
+    pub id: usize,
+    pub name: String,
+    pub fields: HashMap<String, BeatriceType>,
+    ///The fields in the order they were declared
+    pub order: Vec<String>,
+}
+
+impl StructDef {
+    ///A struct without fields
+    pub fn new(id: usize, name: String) -> Self {
+        Self {
+            id,
+            name,
+            fields: HashMap::new(),
+            order: Vec::new(),
+        }
+    }
+
+    ///The fields with their types, in the order they were declared
+    pub fn ordered_fields(&self) -> Vec<(String, BeatriceType)> {
+        self.order
+            .iter()
+            .map(|k| (k.clone(), self.fields[k].clone()))
+            .collect()
+    }
+
+    fn substitute(&self, subst: &Substitution) -> Self {
+        Self {
+            id: self.id,
+            name: self.name.clone(),
+            fields: self
+                .fields
+                .iter()
+                .map(|(k, t)| (k.clone(), t.substitute(subst)))
+                .collect(),
+            order: self.order.clone(),
+        }
+    }
+}
+
+impl BeatriceTranspiler {
+    ///The struct declared with the given name, the innermost one if it was declared many times
+    pub(crate) fn struct_def(&self, name: &str) -> Option<&StructDef> {
+        self.scopes()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.struct_def(name))
+    }
+
+    ///The type of the instances of the struct with the given name, with the generic arguments
+    ///given
+    pub(crate) fn struct_type(
+        &self,
+        name: &str,
+        args: Vec<BeatriceType>,
+    ) -> Result<BeatriceType, TypeError> {
+        let def = self
+            .struct_def(name)
+            .ok_or(TypeError::NotRecognizedType(name.to_string()))?;
+        Ok(BeatriceType::Struct {
+            id: def.id,
+            name: def.name.clone(),
+            args,
+        })
+    }
+
+    ///The declaration of a struct type, with its generic parameters replaced by the arguments of
+    ///kind
+    pub(crate) fn struct_def_of(&self, kind: &BeatriceType) -> Result<StructDef, TypeError> {
+        let BeatriceType::Struct { id, name, args } = kind else {
+            panic!("This is a bug. Expected to receive a struct type");
+        };
+        let def = self
+            .scopes()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.struct_def(name).filter(|def| def.id == *id))
+            .ok_or(TypeError::NotRecognizedType(name.clone()))?;
+        let subst = self
+            .generics_of(name)
+            .into_iter()
+            .map(|param| param.name)
+            .zip(args.iter().cloned())
+            .collect::<Substitution>();
+        Ok(def.substitute(&subst))
+    }
+
+    ///Generates the definition of a struct from its declaration. Its generic parameters must
+    ///already be usable as types, and the struct itself must already be declared, with the id
+    ///given to it. The fields whose types can't be resolved are reported and given the Error type
+    pub(crate) fn ast_typeof_struct(&mut self, s: &AST, id: usize) -> StructDef {
+        let AST::Struct { name, fields, .. } = s else {
+            panic!("This ia a bug. Expected to receive a struct");
+        };
+        let mut def = StructDef {
+            id,
+            name: name.clone(),
+            fields: HashMap::with_capacity(fields.len()),
+            order: Vec::with_capacity(fields.len()),
+        };
+        for field in fields {
+            def.order.push(field.key.clone());
+            let kind = self.t_abstract_from_primitive(&field.kindof);
+            def.fields.insert(field.key.clone(), self.recover(kind));
+        }
+        def
+    }
+
+    ///Checks that a struct doesn't hold itself by value, through its fields or the ones of the
+    ///structs and tuples in them, as its values would never end. Options, results and enums can
+    ///hold it, as they have values without it. A cycle through many structs is only reported for
+    ///the first of them checked: cycles has the ids of the structs of each one already reported
+    pub(crate) fn check_finite_struct(
+        &self,
+        def: &StructDef,
+        cycles: &mut Vec<Vec<usize>>,
+    ) -> Result<(), TypeError> {
+        let mut path = Vec::new();
+        let mut visited = Vec::new();
+        for (field, kind) in def.ordered_fields() {
+            path.push(field);
+            let mut cycle = vec![def.id];
+            if self.holds_struct(&kind, def.id, &mut path, &mut cycle, &mut visited)? {
+                cycle.sort_unstable();
+                if cycles.contains(&cycle) {
+                    return Ok(());
+                }
+                cycles.push(cycle);
+                return Err(TypeError::InfinitelySized {
+                    target: def.name.clone(),
+                    fields: path,
+                });
+            }
+            path.pop();
+        }
+        Ok(())
+    }
+
+    ///Whether a value of kind holds the struct with the given id by value. path ends up with the
+    ///fields leading to it, and held with the ids of the structs they go through
+    fn holds_struct(
+        &self,
+        kind: &BeatriceType,
+        id: usize,
+        path: &mut Vec<String>,
+        held: &mut Vec<usize>,
+        visited: &mut Vec<BeatriceType>,
+    ) -> Result<bool, TypeError> {
+        let fields = match kind {
+            BeatriceType::Struct { id: target, .. } if *target == id => return Ok(true),
+            //a struct reached twice without holding id the first time won't the second one
+            BeatriceType::Struct { .. } if visited.contains(kind) => return Ok(false),
+            BeatriceType::Struct { id: through, .. } => {
+                visited.push(kind.clone());
+                held.push(*through);
+                self.struct_def_of(kind)?.ordered_fields()
+            }
+            BeatriceType::Tuple(types) => types
+                .iter()
+                .enumerate()
+                .map(|(idx, kind)| (idx.to_string(), kind.clone()))
+                .collect(),
+            _ => return Ok(false),
+        };
+        for (field, kind) in fields {
+            path.push(field);
+            if self.holds_struct(&kind, id, path, held, visited)? {
+                return Ok(true);
+            }
+            path.pop();
+        }
+        if let BeatriceType::Struct { .. } = kind {
+            held.pop();
+        }
+        Ok(false)
+    }
+}
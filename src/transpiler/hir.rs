@@ -0,0 +1,309 @@
+//The typed intermediate representation a checked program is lowered into, and js is emitted from.
+//It mirrors the AST, but every expression carries its type and every name the symbol it was
+//resolved to, along the choices the checker made for it, such as where each argument of a call
+//comes from or the helper formatting an interpolated value. Emitting it needs nothing else from
+//the checker, so optimisations and other backends can be written on top of it.
+
+use crate::parser::Operator;
+
+use super::{BeatriceType, resolver::SymbolId};
+
+#[derive(Debug, Clone)]
+pub struct Expr {
+    pub kind: ExprKind,
+    ///The type of the value of the expression. Void for statements and declarations
+    pub ty: BeatriceType,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExprKind {
+    ///A variable or a function, with the name it's emitted with
+    Var {
+        symbol: SymbolId,
+        name: String,
+    },
+    ///A number literal. Its type is the numeric type it was given
+    Int(u64),
+    Float(f64),
+    Bool(bool),
+    ///"text", with its escapes as written
+    Str(String),
+    Interpolated(Vec<StringPart>),
+    ///`numeric` is the type the js number the operation gives must be brought back to, such as
+    ///the u8 of a sum that may pass 255
+    Binary {
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+        operator: Operator,
+        numeric: Option<BeatriceType>,
+    },
+    ///expr as T. `numeric` is as in Binary, and `bigint` the types it converts from and to when
+    ///only one of them is a js bigint
+    Cast {
+        expr: Box<Expr>,
+        numeric: Option<BeatriceType>,
+        bigint: Option<(BeatriceType, BeatriceType)>,
+    },
+    Let {
+        pattern: Pattern,
+        mutable: bool,
+        body: Box<Expr>,
+    },
+    ///A call to the function or variable of the symbol. The arguments are in the order of the
+    ///parameters, None for the ones left out to take their default value. `written` holds the
+    ///parameters given an argument in the order their arguments were written, which is the order
+    ///they are evaluated in
+    Call {
+        callee: SymbolId,
+        name: String,
+        args: Vec<Option<Expr>>,
+        written: Vec<usize>,
+    },
+    ///Has the type of its value, void when it has none
+    Return(Option<Box<Expr>>),
+    Break,
+    Panic(Box<Expr>),
+    ///A struct literal, with its fields in the order they were written
+    StructLit {
+        name: String,
+        fields: Vec<(String, Expr)>,
+    },
+    ///The construction of an enum variant, with the tag it's told apart by
+    Variant {
+        name: String,
+        variant: String,
+        tag: usize,
+        args: VariantArgs,
+    },
+    ///`variants` is the amount of variants of the enum matched, when an arm has a variant
+    ///pattern
+    Match {
+        expr: Box<Expr>,
+        arms: Vec<MatchArm>,
+        variants: Option<usize>,
+    },
+    Tuple(Vec<Expr>),
+    TupleIndex {
+        expr: Box<Expr>,
+        index: usize,
+        optional: bool,
+    },
+    FieldAccess {
+        expr: Box<Expr>,
+        field: String,
+        optional: bool,
+    },
+    Some(Box<Expr>),
+    None,
+    Ok(Box<Expr>),
+    Err(Box<Expr>),
+    Try(Box<Expr>),
+    If {
+        cond: Box<Expr>,
+        block: Box<Expr>,
+        elseblock: Option<Box<Expr>>,
+    },
+    Block(Vec<Expr>),
+    ///`breaks` when a break leaves it, so it's labeled for the break to name it
+    Loop {
+        body: Box<Expr>,
+        breaks: bool,
+    },
+    Function(Function),
+    ///The helpers formatting a struct with @Display
+    Display(Display),
+    ///A struct without @Display or an enum, which don't exist at runtime
+    TypeDecl,
+    ///An import of the names that exist at runtime, with the name each one is given here
+    Import {
+        path: String,
+        names: Vec<Import>,
+    },
+    Export(Box<Expr>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    ///The name the function is emitted with, which differs from its own when js reserves it
+    pub emitted: String,
+    pub params: Vec<Param>,
+    pub body: Vec<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub pattern: Pattern,
+    pub ty: BeatriceType,
+    ///The value given to the parameter when a call leaves it out
+    pub default: Option<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Display {
+    pub name: String,
+    pub fields: Vec<DisplayField>,
+    ///The string formatting the struct with its fields as variables, if @Display was given one
+    pub format: Option<Box<Expr>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DisplayField {
+    pub name: String,
+    ///The name of the variable holding the field in the helpers
+    pub emitted: String,
+    ///The helper formatting the field when debugging the struct, if it needs one
+    pub formatter: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub name: String,
+    pub emitted: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum StringPart {
+    Literal(String),
+    ///An interpolated value, with the helper formatting it if it needs one
+    Expr {
+        expr: Box<Expr>,
+        formatter: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum VariantArgs {
+    Unit,
+    Tuple(Vec<Expr>),
+    Struct(Vec<(String, Expr)>),
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Expr,
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Wildcard,
+    ///Binds the value to the variable of the symbol, with the name it's emitted with
+    Binding {
+        symbol: SymbolId,
+        name: String,
+    },
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    ///A range of literals, with its bounds as written
+    Range {
+        start: String,
+        end: String,
+        inclusive: bool,
+    },
+    ///A variant of an enum, by the tag it's told apart by
+    Variant {
+        tag: usize,
+        fields: VariantPattern,
+    },
+    ///A struct with patterns for some of its fields
+    Struct(Vec<(String, Pattern)>),
+    Tuple(Vec<Pattern>),
+    Array(Vec<Pattern>),
+    Some(Box<Pattern>),
+    None,
+    Ok(Box<Pattern>),
+    Err(Box<Pattern>),
+}
+
+#[derive(Debug, Clone)]
+pub enum VariantPattern {
+    Unit,
+    Tuple(Vec<Pattern>),
+    Struct(Vec<(String, Pattern)>),
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, ty: BeatriceType) -> Self {
+        Self { kind, ty }
+    }
+
+    pub fn is_block(&self) -> bool {
+        matches!(self.kind, ExprKind::Block(_))
+    }
+
+    ///Whether evaluating it can't do anything besides giving its value, so it can be evaluated
+    ///earlier or later than written
+    pub fn is_pure(&self) -> bool {
+        match &self.kind {
+            ExprKind::Var { .. }
+            | ExprKind::Int(_)
+            | ExprKind::Float(_)
+            | ExprKind::Bool(_)
+            | ExprKind::Str(_)
+            | ExprKind::None => true,
+            ExprKind::Some(expr)
+            | ExprKind::FieldAccess { expr, .. }
+            | ExprKind::TupleIndex { expr, .. } => expr.is_pure(),
+            _ => false,
+        }
+    }
+
+    ///Whether it's a return, a break or a panic, which are statements leaving the code they are
+    ///in instead of values
+    pub fn is_jump(&self) -> bool {
+        matches!(
+            self.kind,
+            ExprKind::Return(_) | ExprKind::Break | ExprKind::Panic(_)
+        )
+    }
+}
+
+impl Pattern {
+    ///The Binding patterns inside the pattern, in the order they were written
+    pub fn bindings(&self) -> Vec<&Pattern> {
+        let mut out = Vec::new();
+        self.collect_bindings(&mut out);
+        out
+    }
+
+    fn collect_bindings<'a>(&'a self, out: &mut Vec<&'a Pattern>) {
+        match self {
+            Self::Binding { .. } => out.push(self),
+            Self::Variant {
+                fields: VariantPattern::Tuple(patterns),
+                ..
+            }
+            | Self::Tuple(patterns)
+            | Self::Array(patterns) => {
+                for pattern in patterns {
+                    pattern.collect_bindings(out);
+                }
+            }
+            Self::Variant {
+                fields: VariantPattern::Struct(fields),
+                ..
+            }
+            | Self::Struct(fields) => {
+                for (_, pattern) in fields {
+                    pattern.collect_bindings(out);
+                }
+            }
+            Self::Some(pattern) | Self::Ok(pattern) | Self::Err(pattern) => {
+                pattern.collect_bindings(out)
+            }
+            Self::Wildcard
+            | Self::Bool(_)
+            | Self::Int(_)
+            | Self::Float(_)
+            | Self::Range { .. }
+            | Self::Variant {
+                fields: VariantPattern::Unit,
+                ..
+            }
+            | Self::None => {}
+        }
+    }
+}
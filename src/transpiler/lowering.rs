@@ -0,0 +1,407 @@
+//A checked program is lowered into the HIR before being emitted. Everything the checker learned
+//about a node, from its type to the side tables keyed by its NodeId, is moved into the node of
+//the HIR standing for it, so nothing has to be typed or looked up again while emitting it.
+
+use crate::parser::{self, AST, Program};
+
+use super::{
+    BeatriceType,
+    checkings::arguments::ArgSource,
+    hir::{
+        self, Display, DisplayField, Expr, ExprKind, Function, Import, MatchArm, Param, Pattern,
+        VariantPattern,
+    },
+    transpilation::names::escape,
+    transpiler::BeatriceTranspiler,
+};
+
+impl BeatriceTranspiler {
+    ///Lowers the program just checked by this transpiler into the HIR. Its nodes are looked up
+    ///in the tables the checker filled, so it must be the same program
+    pub(crate) fn lower_program(&mut self, program: &Program) -> Vec<Expr> {
+        program
+            .statements()
+            .map(|(ast, span)| {
+                self.set_span(span);
+                self.lower(ast)
+            })
+            .collect()
+    }
+
+    ///The type an expression was given while checking it. Blocks and ifs whose value isn't used
+    ///are only checked as statements, so they are void. An expression the checker left without a
+    ///type is a bug of the checker, which is recorded to be reported, and lowered as an Error
+    fn lowered_type(&self, ast: &AST) -> BeatriceType {
+        match (self.type_of(ast), ast) {
+            (Some(kind), _) => kind.clone(),
+            (None, AST::Block(_) | AST::If { .. }) => BeatriceType::Void,
+            (None, _) => {
+                self.record_untyped();
+                BeatriceType::Error
+            }
+        }
+    }
+
+    fn lower(&self, ast: &AST) -> Expr {
+        let void = |kind| Expr::new(kind, BeatriceType::Void);
+        let boxed = |ast: &AST| Box::new(self.lower(ast));
+        let kind = match ast {
+            AST::Identifier(_) => {
+                let symbol = self.symbol_of(ast);
+                ExprKind::Var {
+                    symbol,
+                    name: self.resolution().symbol(symbol).emitted.clone(),
+                }
+            }
+            AST::Int(i) => {
+                let kind = self.numeric_of(ast).cloned().unwrap_or(BeatriceType::Int);
+                return Expr::new(ExprKind::Int(*i), kind);
+            }
+            AST::Float(f) => {
+                let kind = self.numeric_of(ast).cloned().unwrap_or(BeatriceType::Float);
+                return Expr::new(ExprKind::Float(*f), kind);
+            }
+            AST::Bool(b) => return Expr::new(ExprKind::Bool(*b), BeatriceType::Bool),
+            AST::Str(s) => return Expr::new(ExprKind::Str(s.clone()), BeatriceType::String),
+            AST::Interpolated(parts) => ExprKind::Interpolated(
+                parts
+                    .iter()
+                    .map(|part| match part {
+                        parser::StringPart::Literal(text) => hir::StringPart::Literal(text.clone()),
+                        parser::StringPart::Expr { expr, .. } => hir::StringPart::Expr {
+                            expr: boxed(expr),
+                            formatter: self.formatter_of(expr).cloned(),
+                        },
+                    })
+                    .collect(),
+            ),
+            AST::BinExpr(lhs, rhs, operator) => ExprKind::Binary {
+                lhs: boxed(lhs),
+                rhs: boxed(rhs),
+                operator: operator.clone(),
+                numeric: self.numeric_of(ast).cloned(),
+            },
+            AST::Cast { expr, .. } => ExprKind::Cast {
+                expr: boxed(expr),
+                numeric: self.numeric_of(ast).cloned(),
+                bigint: self.bigint_cast_of(ast).cloned(),
+            },
+            AST::VarDecl {
+                pattern,
+                mutable,
+                body,
+                ..
+            } => {
+                return void(ExprKind::Let {
+                    pattern: self.lower_pattern(pattern),
+                    mutable: *mutable,
+                    body: boxed(body),
+                });
+            }
+            AST::Function { .. } => return void(ExprKind::Function(self.lower_function(ast))),
+            AST::FunctionCall { args, named, .. } => {
+                let callee = self.symbol_of(ast);
+                let (lowered, written) = match self.call_args_of(ast) {
+                    Some(sources) => {
+                        //the positional arguments are written before the named ones
+                        let mut written = sources
+                            .iter()
+                            .enumerate()
+                            .filter_map(|(param, source)| match source {
+                                ArgSource::Positional(idx) => Some((*idx, param)),
+                                ArgSource::Named(idx) => Some((args.len() + idx, param)),
+                                ArgSource::Default => None,
+                            })
+                            .collect::<Vec<_>>();
+                        written.sort_unstable();
+                        let lowered = sources
+                            .iter()
+                            .map(|source| source.arg(args, named).map(|arg| self.lower(arg)))
+                            .collect();
+                        (
+                            lowered,
+                            written.into_iter().map(|(_, param)| param).collect(),
+                        )
+                    }
+                    None => (
+                        args.iter().map(|arg| Some(self.lower(arg))).collect(),
+                        (0..args.len()).collect(),
+                    ),
+                };
+                ExprKind::Call {
+                    callee,
+                    name: self.resolution().symbol(callee).emitted.clone(),
+                    args: lowered,
+                    written,
+                }
+            }
+            AST::Return(value) => {
+                let value = value.as_deref().map(|value| self.lower(value));
+                let kind = value
+                    .as_ref()
+                    .map_or(BeatriceType::Void, |value| value.ty.clone());
+                return Expr::new(ExprKind::Return(value.map(Box::new)), kind);
+            }
+            AST::Break => ExprKind::Break,
+            AST::Panic(message) => ExprKind::Panic(boxed(message)),
+            AST::Struct { .. } => return void(self.lower_struct(ast)),
+            AST::Enum { .. } => return void(ExprKind::TypeDecl),
+            AST::StructExpr { name, fields, .. } => ExprKind::StructLit {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|field| (field.key.clone(), self.lower(&field.value)))
+                    .collect(),
+            },
+            AST::VariantExpr {
+                name,
+                variant,
+                args,
+                ..
+            } => ExprKind::Variant {
+                name: name.clone(),
+                variant: variant.clone(),
+                tag: self.variant_tag(name, variant),
+                args: match args {
+                    parser::VariantArgs::Unit => hir::VariantArgs::Unit,
+                    parser::VariantArgs::Tuple(values) => {
+                        hir::VariantArgs::Tuple(values.iter().map(|v| self.lower(v)).collect())
+                    }
+                    parser::VariantArgs::Struct(fields) => hir::VariantArgs::Struct(
+                        fields
+                            .iter()
+                            .map(|field| (field.key.clone(), self.lower(&field.value)))
+                            .collect(),
+                    ),
+                },
+            },
+            AST::Match { expr, arms } => {
+                let variants = arms.iter().find_map(|arm| match &arm.pattern {
+                    parser::Pattern::Variant { name, .. } => {
+                        self.enum_def(name).map(|def| def.variants.len())
+                    }
+                    _ => None,
+                });
+                ExprKind::Match {
+                    expr: boxed(expr),
+                    arms: arms
+                        .iter()
+                        .map(|arm| MatchArm {
+                            pattern: self.lower_pattern(&arm.pattern),
+                            guard: arm.guard.as_ref().map(|guard| self.lower(guard)),
+                            body: self.lower(&arm.body),
+                        })
+                        .collect(),
+                    variants,
+                }
+            }
+            AST::Tuple(values) => ExprKind::Tuple(values.iter().map(|v| self.lower(v)).collect()),
+            AST::TupleIndex {
+                expr,
+                index,
+                optional,
+            } => ExprKind::TupleIndex {
+                expr: boxed(expr),
+                index: *index,
+                optional: *optional,
+            },
+            AST::FieldAccess {
+                expr,
+                field,
+                optional,
+            } => ExprKind::FieldAccess {
+                expr: boxed(expr),
+                field: field.clone(),
+                optional: *optional,
+            },
+            AST::Some(value) => ExprKind::Some(boxed(value)),
+            AST::None => ExprKind::None,
+            AST::Ok(value) => ExprKind::Ok(boxed(value)),
+            AST::Err(value) => ExprKind::Err(boxed(value)),
+            AST::Try(value) => ExprKind::Try(boxed(value)),
+            AST::If {
+                expr,
+                block,
+                elseblock,
+            } => ExprKind::If {
+                cond: boxed(expr),
+                block: boxed(block),
+                elseblock: elseblock.as_deref().map(boxed),
+            },
+            AST::Block(block) => {
+                ExprKind::Block(block.body().iter().map(|e| self.lower(e)).collect())
+            }
+            AST::Loop(body) => ExprKind::Loop {
+                body: boxed(body),
+                breaks: self.lowered_type(ast) != BeatriceType::Never,
+            },
+            AST::Import { .. } => return void(self.lower_import(ast)),
+            AST::Export(decl) => return void(ExprKind::Export(boxed(decl))),
+            AST::Attributed { ast, .. } => return self.lower(ast),
+        };
+        Expr::new(kind, self.lowered_type(ast))
+    }
+
+    ///The symbol an identifier or a call was resolved to
+    fn symbol_of(&self, ast: &AST) -> usize {
+        self.resolution()
+            .use_of(ast)
+            .expect("This is a bug. Names are resolved before being lowered")
+    }
+
+    fn lower_function(&self, f: &AST) -> Function {
+        let AST::Function {
+            name, params, body, ..
+        } = f
+        else {
+            panic!("This is a bug. Expected to receive a function");
+        };
+        let Ok(BeatriceType::Function {
+            params: param_types,
+            ..
+        }) = self.typeof_function(name)
+        else {
+            panic!("This is a bug. Functions are declared before being lowered");
+        };
+        Function {
+            name: name.clone(),
+            emitted: escape(name),
+            params: params
+                .iter()
+                .zip(param_types)
+                .map(|(param, ty)| Param {
+                    pattern: self.lower_pattern(&param.pattern),
+                    ty,
+                    default: param.default.as_ref().map(|default| self.lower(default)),
+                })
+                .collect(),
+            body: body.body().iter().map(|ast| self.lower(ast)).collect(),
+        }
+    }
+
+    ///Lowers a struct into its @Display helpers, if it has them
+    fn lower_struct(&self, ast: &AST) -> ExprKind {
+        let AST::Struct {
+            name, attributes, ..
+        } = ast
+        else {
+            panic!("This is a bug. Expected to receive a struct");
+        };
+        let Some(display) = attributes.iter().find(|a| a.is("Display")) else {
+            return ExprKind::TypeDecl;
+        };
+        let Some(def) = self.struct_def(name) else {
+            panic!("This is a bug. Expected a checked struct");
+        };
+        let fields = def
+            .ordered_fields()
+            .into_iter()
+            .map(|(field, kind)| DisplayField {
+                emitted: escape(&field),
+                formatter: self
+                    .formatter(&kind, true)
+                    .expect("This is a bug. The fields of a struct with @Display are displayable"),
+                name: field,
+            })
+            .collect();
+        ExprKind::Display(Display {
+            name: name.clone(),
+            fields,
+            format: display
+                .args
+                .front()
+                .map(|format| Box::new(self.lower(format))),
+        })
+    }
+
+    ///Lowers an import into the names it imports at runtime. Structs and enums are only types,
+    ///so only functions and the helpers of structs with @Display are imported
+    fn lower_import(&self, ast: &AST) -> ExprKind {
+        let AST::Import { names, path } = ast else {
+            panic!("This is a bug. Expected to receive an import");
+        };
+        let scope = self.current_scope();
+        let mut imports = Vec::with_capacity(names.len());
+        for name in names {
+            if scope.has_function(name) {
+                imports.push(Import {
+                    name: name.clone(),
+                    emitted: escape(name),
+                });
+            } else if scope.has_display(name) {
+                for helper in [format!("{name}$debug"), format!("{name}$display")] {
+                    imports.push(Import {
+                        name: helper.clone(),
+                        emitted: helper,
+                    });
+                }
+            }
+        }
+        ExprKind::Import {
+            path: path.clone(),
+            names: imports,
+        }
+    }
+
+    fn lower_pattern(&self, pattern: &parser::Pattern) -> Pattern {
+        let lower_all = |patterns: &[parser::Pattern]| {
+            patterns
+                .iter()
+                .map(|pattern| self.lower_pattern(pattern))
+                .collect()
+        };
+        let lower_fields = |fields: &[parser::KeyPatternPair]| {
+            fields
+                .iter()
+                .map(|field| (field.key.clone(), self.lower_pattern(&field.pattern)))
+                .collect()
+        };
+        match pattern {
+            parser::Pattern::Wildcard => Pattern::Wildcard,
+            parser::Pattern::Binding(_) => {
+                let Some(symbol) = self.resolution().binding_of(pattern) else {
+                    panic!("This is a bug. Names are resolved before being lowered");
+                };
+                Pattern::Binding {
+                    symbol,
+                    name: self.resolution().symbol(symbol).emitted.clone(),
+                }
+            }
+            parser::Pattern::Bool(b) => Pattern::Bool(*b),
+            parser::Pattern::Int(i) => Pattern::Int(*i),
+            parser::Pattern::Float(f) => Pattern::Float(*f),
+            parser::Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => Pattern::Range {
+                start: start.to_string(),
+                end: end.to_string(),
+                inclusive: *inclusive,
+            },
+            parser::Pattern::Variant {
+                name,
+                variant,
+                fields,
+            } => Pattern::Variant {
+                tag: self.variant_tag(name, variant),
+                fields: match fields {
+                    parser::VariantPattern::Unit => VariantPattern::Unit,
+                    parser::VariantPattern::Tuple(patterns) => {
+                        VariantPattern::Tuple(lower_all(patterns))
+                    }
+                    parser::VariantPattern::Struct { fields, .. } => {
+                        VariantPattern::Struct(lower_fields(fields))
+                    }
+                },
+            },
+            parser::Pattern::Struct { fields, .. } => Pattern::Struct(lower_fields(fields)),
+            parser::Pattern::Tuple(patterns) => Pattern::Tuple(lower_all(patterns)),
+            parser::Pattern::Array(patterns) => Pattern::Array(lower_all(patterns)),
+            parser::Pattern::Some(pattern) => Pattern::Some(Box::new(self.lower_pattern(pattern))),
+            parser::Pattern::None => Pattern::None,
+            parser::Pattern::Ok(pattern) => Pattern::Ok(Box::new(self.lower_pattern(pattern))),
+            parser::Pattern::Err(pattern) => Pattern::Err(Box::new(self.lower_pattern(pattern))),
+        }
+    }
+}
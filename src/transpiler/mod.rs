@@ -1,24 +1,88 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::VecDeque;
+
+use crate::parser::{Operator, Span};
 
 pub mod checkings;
+pub mod hir;
+pub mod lowering;
+pub mod resolver;
 pub mod scope;
 pub mod transpilation;
+#[allow(clippy::module_inception)]
 pub mod transpiler;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BeatriceType {
     Void,
+    ///A js number holding an integer, exact up to 2^53. i64 is another name of it. Its bitwise
+    ///operators work on 64 bits, not on the 32 of js
     Int,
+    ///A js number. f64 is another name of it
     Float,
+    ///The sized integers. Their operations wrap around their range as in two's complement, so an
+    ///u8 255 + 1 is 0
+    I8,
+    I16,
+    I32,
+    U8,
+    U16,
+    U32,
+    ///An unsigned int, exact up to 2^53 as int is. Its operations wrap around 64 bits, so 0 - 1
+    ///is 2^64 - 1 rounded to a js number
+    U64,
+    ///A float rounded to single precision after every operation
+    F32,
+    String,
+    Bool,
     Function {
         params: VecDeque<BeatriceType>,
         return_type: Box<BeatriceType>,
     },
+    ///A struct, by the id of its declaration, with the types its generic parameters were
+    ///instantiated with. Structs with the same fields are still different types
     Struct {
-        fields: HashMap<String, BeatriceType>,
-        order: Vec<String>,
+        id: usize,
+        name: String,
+        args: Vec<BeatriceType>,
+    },
+    ///An enum, by name, with the types its generic parameters were instantiated with
+    Enum {
+        name: String,
+        args: Vec<BeatriceType>,
+    },
+    ///(int, float)
+    Tuple(Vec<BeatriceType>),
+    ///int?, either an int or None
+    Option(Box<BeatriceType>),
+    ///Result<int, float>, either an int value or a float error
+    Result {
+        ok: Box<BeatriceType>,
+        err: Box<BeatriceType>,
     },
+    ///The type of values that can't exist, such as the one None would hold. Also the type of the
+    ///expressions that never give a value, as a return or a loop without a break
+    Never,
+    ///The type of an expression that failed to check. It's accepted as any type and accepts any
+    ///type, so the error is reported once instead of again by everything using the expression
+    Error,
+    ///A generic parameter. Only seen inside the declaration that introduced it, as every use of
+    ///the declaration replaces it by the type it was instantiated with
+    TypeVar(String),
+}
+///The conditions a generic parameter can be given with `where T extends <Bound>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Numeric,
+    Integer,
+    Floating,
+}
+///A type error, with where the statement it was found in starts
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub error: TypeError,
 }
+
 #[derive(Debug)]
 pub enum TypeError {
     NotRecognizedVar(String),
@@ -35,8 +99,8 @@ pub enum TypeError {
     InvalidFieldValue {
         target: String,
         field: String,
-        expected: BeatriceType,
-        received: BeatriceType,
+        expected: Box<BeatriceType>,
+        received: Box<BeatriceType>,
     },
     NotCorrectFields {
         //for when not passing a field that is required. I must think in a better
@@ -48,4 +112,146 @@ pub enum TypeError {
         ifbranch: BeatriceType,
         elsebranch: BeatriceType,
     },
+    NotRecognizedBound(String),
+    UnsatisfiedBound {
+        param: String,
+        bound: Bound,
+        received: BeatriceType,
+    },
+    WrongGenericsAmount {
+        target: String,
+        expected: usize,
+        received: usize,
+    },
+    ///When a generic parameter is not used by any argument, so it must be given explicitly
+    CannotInferGeneric {
+        target: String,
+        param: String,
+    },
+    NotRecognizedVariant {
+        target: String,
+        variant: String,
+    },
+    ///When a variant is built or matched with a different amount of fields than declared. Unit
+    ///variants have no fields
+    WrongVariantFields {
+        target: String,
+        expected: usize,
+        received: usize,
+    },
+    ///When a pattern can never match a value of the type being matched
+    InvalidPattern {
+        pattern: String,
+        expected: BeatriceType,
+    },
+    ///Same as IfElseWrong, but for an arm of a match disagreeing with the first one. The types of
+    ///this variant and InvalidFieldValue are boxed to keep every TypeError small, as it's the error
+    ///of most checks
+    MatchArmWrong {
+        arm: usize,
+        expected: Box<BeatriceType>,
+        received: Box<BeatriceType>,
+    },
+    ///When some value is not matched by any arm. `missing` is a pattern of such values
+    NonExhaustiveMatch {
+        missing: String,
+    },
+    ///When the pattern of a `let` or a parameter doesn't match every value of its type
+    RefutablePattern {
+        pattern: String,
+        missing: String,
+    },
+    ///When a tuple element is accessed on something that isn't a tuple, or past its last element
+    InvalidTupleIndex {
+        target: BeatriceType,
+        index: usize,
+    },
+    ///When `?.` or `??` is used on something that isn't an option
+    ExpectedOption(BeatriceType),
+    ///When `?` is used on something that isn't a result
+    ExpectedResult(BeatriceType),
+    ///When `?` is used in a function that can't return the error, as it doesn't return a result
+    ///with a compatible error. `return_type` is void outside of functions
+    IncompatibleTry {
+        return_type: BeatriceType,
+        error: BeatriceType,
+    },
+    ///When a call leaves out the argument of a parameter without a default value
+    MissingArgument {
+        function: String,
+        param: String,
+    },
+    ///When a call gives the argument of a parameter twice, by position and by name or by name
+    ///twice
+    DuplicateArgument {
+        function: String,
+        argument: String,
+    },
+    ///When a binary operator is used on values it doesn't work with, such as 1 + 2.5
+    InvalidOperands {
+        operator: Operator,
+        lhs: BeatriceType,
+        rhs: BeatriceType,
+    },
+    ///When `as` converts between types that aren't both numeric, as in true as int
+    InvalidCast {
+        from: BeatriceType,
+        to: BeatriceType,
+    },
+    ///When an int literal doesn't fit in the type it's given, as 2^63 for an int
+    LiteralOutOfRange {
+        literal: u64,
+        kind: BeatriceType,
+    },
+    ///When a call gives more arguments than the function has parameters, or less than the ones
+    ///needed to reach its last parameter without a default value
+    ArityMismatch {
+        expected: usize,
+        received: usize,
+        span: Span,
+    },
+    ///When a call gives a named argument that isn't a parameter of the function
+    UnknownArgument {
+        function: String,
+        argument: String,
+    },
+    ///When an interpolated value can't be formatted, as `{}` when debug is false and as `{:?}`
+    ///otherwise. Only numbers, strings and structs with @Display can
+    NotDisplayable {
+        kind: BeatriceType,
+        debug: bool,
+    },
+    ///When a field is accessed on something that isn't a struct or doesn't have it
+    InvalidFieldAccess {
+        target: BeatriceType,
+        field: String,
+    },
+    ///When a struct holds itself by value through the given fields, so its values would never
+    ///end. It can hold itself through an option, as in `struct Node { next: Node?; }`
+    InfinitelySized {
+        target: String,
+        fields: Vec<String>,
+    },
+    ///When a variable is used above the `let` defining it, with no other variable with its name
+    ///defined before
+    UseBeforeDefinition(String),
+    ///When two parameters of a function, or two variables of a pattern, have the same name. Also
+    ///when two functions or two types are declared or imported with it
+    DuplicateDefinition(String),
+    ///When an import names a module the transpiler wasn't given
+    UnresolvedModule(String),
+    ///When an import asks for a name its module doesn't export
+    NotExported {
+        module: String,
+        name: String,
+    },
+    ///When a function returning a value has a path through its body that doesn't return one
+    MissingReturn(BeatriceType),
+    ///When a statement comes after one that always leaves the block, so it never runs
+    UnreachableCode,
+    ///When a break isn't inside a loop
+    BreakOutsideLoop,
+    ///When the checker left an expression without a type, which is a bug of the transpiler. It's
+    ///reported instead of emitting the expression with a wrong type
+    UntypedExpression,
 }
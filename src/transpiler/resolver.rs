@@ -0,0 +1,402 @@
+//Names are resolved once, before the program is checked. Every binding, from a parameter to the
+//variables of a match arm, gets a symbol of its own, and every identifier and call is resolved to
+//the symbol of the nearest binding with its name written before it. Later phases look symbols up
+//by the address of the node using them instead of looking names up through the scopes, so a
+//shadowed variable can never be mistaken for the one shadowing it. A name that can't be resolved is
+//reported and left without a symbol, and the rest of the program is still resolved.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::parser::{
+    AST, Attribute, FunctionParameter, KeyTypePair, Pattern, Program, Span, StringPart, VariantArgs,
+};
+
+use super::{Diagnostic, TypeError, transpilation::names};
+
+pub type SymbolId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    ///A global function, declared or imported
+    Function,
+    ///A parameter, or a variable bound by a `let` or a match arm
+    Variable,
+    ///A field of a struct, usable as a variable by the format of its @Display
+    Field,
+}
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    ///The name the symbol has in the generated js, which is its own name unless js reserves it
+    ///or the symbol shadows another one
+    pub emitted: String,
+}
+
+///The symbols of a program, with the nodes binding and using each one
+#[derive(Debug, Default)]
+pub struct Resolution {
+    symbols: Vec<Symbol>,
+    ///The symbol each identifier and call refers to, by the address of its node
+    uses: HashMap<*const AST, SymbolId>,
+    ///The symbol each Binding pattern introduces, by its address
+    bindings: HashMap<*const Pattern, SymbolId>,
+    ///The symbol each field of a struct with @Display is in its format, by its address
+    fields: HashMap<*const KeyTypePair, SymbolId>,
+}
+
+impl Resolution {
+    pub fn symbol(&self, id: SymbolId) -> &Symbol {
+        &self.symbols[id]
+    }
+
+    ///The global functions of the program, with their symbols
+    pub fn functions(&self) -> impl Iterator<Item = (SymbolId, &Symbol)> {
+        self.symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, symbol)| symbol.kind == SymbolKind::Function)
+    }
+
+    ///The symbol an identifier or a call refers to, if it was resolved
+    pub fn use_of(&self, ast: &AST) -> Option<SymbolId> {
+        self.uses.get(&(ast as *const AST)).copied()
+    }
+
+    ///The symbol a Binding pattern introduces, if it was resolved
+    pub fn binding_of(&self, pattern: &Pattern) -> Option<SymbolId> {
+        self.bindings.get(&(pattern as *const Pattern)).copied()
+    }
+
+    ///The symbol a field of a struct is in the format of its @Display, if it has one
+    pub fn field_of(&self, field: &KeyTypePair) -> Option<SymbolId> {
+        self.fields.get(&(field as *const KeyTypePair)).copied()
+    }
+
+    fn define(&mut self, name: &str, kind: SymbolKind, emitted: String) -> SymbolId {
+        self.symbols.push(Symbol {
+            name: name.to_string(),
+            kind,
+            emitted,
+        });
+        self.symbols.len() - 1
+    }
+}
+
+///The names visible in a block, or in the parameters of a function or a match arm
+#[derive(Default)]
+struct Frame {
+    bound: HashMap<String, SymbolId>,
+    ///The names the `let`s of the block bind, which can't be used before their `let`
+    declared: HashSet<String>,
+}
+
+pub struct Resolver {
+    resolution: Resolution,
+    frames: Vec<Frame>,
+    ///How many variables shadowing another one were given each name
+    renamed: HashMap<String, usize>,
+    errors: Vec<Diagnostic>,
+    ///Where the statement being resolved starts
+    span: Span,
+}
+
+impl Resolver {
+    ///A resolver whose global frame holds the given functions
+    pub fn new<'a>(functions: impl IntoIterator<Item = &'a String>) -> Self {
+        let mut resolution = Resolution::default();
+        let mut global = Frame::default();
+        for name in functions {
+            let id = resolution.define(name, SymbolKind::Function, names::escape(name));
+            global.bound.insert(name.clone(), id);
+        }
+        Self {
+            resolution,
+            frames: vec![global],
+            renamed: HashMap::new(),
+            errors: Vec::new(),
+            span: Span::default(),
+        }
+    }
+
+    ///Resolves every name of a program whose globals were declared, along the errors found
+    pub fn resolve(mut self, program: &Program) -> (Resolution, Vec<Diagnostic>) {
+        self.check_unique_globals(program);
+        for (ast, span) in program.statements() {
+            self.span = span;
+            self.resolve_ast(ast);
+        }
+        (self.resolution, self.errors)
+    }
+
+    fn report(&mut self, error: TypeError) {
+        self.errors.push(Diagnostic {
+            span: self.span,
+            error,
+        });
+    }
+
+    ///Reports every function, or type, declared or imported with the name of another one
+    fn check_unique_globals(&mut self, program: &Program) {
+        let mut imported = HashSet::new();
+        for (ast, span) in program.statements() {
+            if let AST::Import { names, .. } = ast {
+                self.span = span;
+                for name in names {
+                    if !imported.insert(name) {
+                        self.report(TypeError::DuplicateDefinition(name.clone()));
+                    }
+                }
+            }
+        }
+        let mut functions = HashSet::new();
+        let mut types = HashSet::new();
+        for (ast, span) in program.statements() {
+            let decl = match ast {
+                AST::Export(decl) => decl,
+                ast => ast,
+            };
+            let (AST::Function { name, .. } | AST::Struct { name, .. } | AST::Enum { name, .. }) =
+                decl
+            else {
+                continue;
+            };
+            let declared = if let AST::Function { .. } = decl {
+                &mut functions
+            } else {
+                &mut types
+            };
+            if imported.contains(name) || !declared.insert(name) {
+                self.span = span;
+                self.report(TypeError::DuplicateDefinition(name.clone()));
+            }
+        }
+    }
+
+    fn resolve_ast(&mut self, ast: &AST) {
+        match ast {
+            AST::Identifier(name) => {
+                if let Some(id) = self.lookup(name) {
+                    self.resolution.uses.insert(ast, id);
+                }
+            }
+            AST::Int(_) | AST::Float(_) | AST::Bool(_) | AST::Str(_) | AST::None => {}
+            AST::BinExpr(lhs, rhs, _) => {
+                self.resolve_ast(lhs);
+                self.resolve_ast(rhs);
+            }
+            AST::VarDecl { pattern, body, .. } => {
+                //the variables aren't bound yet in their own value
+                self.resolve_ast(body);
+                self.bind(pattern, false);
+            }
+            AST::Function { params, body, .. } => {
+                self.frames.push(Frame::default());
+                self.resolve_function(params, body);
+                self.frames.pop();
+            }
+            AST::FunctionCall {
+                name, args, named, ..
+            } => {
+                if let Some(id) = self.lookup(name) {
+                    self.resolution.uses.insert(ast, id);
+                }
+                for arg in args {
+                    self.resolve_ast(arg);
+                }
+                for arg in named {
+                    self.resolve_ast(&arg.value);
+                }
+            }
+            AST::Struct {
+                fields, attributes, ..
+            } => self.resolve_display(fields, attributes),
+            AST::Enum { .. } | AST::Import { .. } => {}
+            AST::StructExpr { fields, .. } => {
+                for field in fields {
+                    self.resolve_ast(&field.value);
+                }
+            }
+            AST::VariantExpr { args, .. } => match args {
+                VariantArgs::Unit => {}
+                VariantArgs::Tuple(values) => {
+                    for value in values {
+                        self.resolve_ast(value);
+                    }
+                }
+                VariantArgs::Struct(fields) => {
+                    for field in fields {
+                        self.resolve_ast(&field.value);
+                    }
+                }
+            },
+            AST::Match { expr, arms } => {
+                self.resolve_ast(expr);
+                for arm in arms {
+                    self.frames.push(Frame::default());
+                    self.bind(&arm.pattern, false);
+                    if let Some(guard) = &arm.guard {
+                        self.resolve_ast(guard);
+                    }
+                    self.resolve_ast(&arm.body);
+                    self.frames.pop();
+                }
+            }
+            AST::Tuple(values) => {
+                for value in values {
+                    self.resolve_ast(value);
+                }
+            }
+            AST::Interpolated(parts) => {
+                for part in parts {
+                    if let StringPart::Expr { expr, .. } = part {
+                        self.resolve_ast(expr);
+                    }
+                }
+            }
+            AST::If {
+                expr,
+                block,
+                elseblock,
+            } => {
+                self.resolve_ast(expr);
+                self.resolve_ast(block);
+                if let Some(elseblock) = elseblock {
+                    self.resolve_ast(elseblock);
+                }
+            }
+            AST::Block(block) => self.resolve_block(block),
+            AST::Return(value) => {
+                if let Some(value) = value {
+                    self.resolve_ast(value);
+                }
+            }
+            AST::Break => {}
+            AST::Panic(expr)
+            | AST::TupleIndex { expr, .. }
+            | AST::FieldAccess { expr, .. }
+            | AST::Some(expr)
+            | AST::Ok(expr)
+            | AST::Err(expr)
+            | AST::Try(expr)
+            | AST::Cast { expr, .. }
+            | AST::Loop(expr)
+            | AST::Export(expr)
+            | AST::Attributed { ast: expr, .. } => self.resolve_ast(expr),
+        }
+    }
+
+    ///Resolves the parameters of a function in the innermost frame, and then its body
+    fn resolve_function(&mut self, params: &VecDeque<FunctionParameter>, body: &Program) {
+        for param in params {
+            //defaults can use the parameters before them
+            if let Some(default) = &param.default {
+                self.resolve_ast(default);
+            }
+            let frame = self.frames.last().unwrap();
+            if let Some(name) = param
+                .pattern
+                .bindings()
+                .into_iter()
+                .find(|name| frame.bound.contains_key(*name))
+            {
+                self.report(TypeError::DuplicateDefinition(name.clone()));
+            }
+            self.bind(&param.pattern, true);
+        }
+        self.resolve_block(body);
+    }
+
+    ///Resolves the statements of a block inside a frame of its own
+    fn resolve_block(&mut self, block: &Program) {
+        let mut frame = Frame::default();
+        for expr in block.body() {
+            if let AST::VarDecl { pattern, .. } = expr {
+                frame
+                    .declared
+                    .extend(pattern.bindings().into_iter().cloned());
+            }
+        }
+        self.frames.push(frame);
+        let previous = self.span;
+        for (expr, span) in block.statements() {
+            self.span = span;
+            self.resolve_ast(expr);
+        }
+        self.span = previous;
+        self.frames.pop();
+    }
+
+    ///Resolves the format of the @Display of a struct, where its fields are variables
+    fn resolve_display(&mut self, fields: &VecDeque<KeyTypePair>, attributes: &[Attribute]) {
+        let Some(format) = attributes
+            .iter()
+            .find(|a| a.is("Display"))
+            .and_then(|display| display.args.front())
+        else {
+            return;
+        };
+        let mut frame = Frame::default();
+        for field in fields {
+            let id =
+                self.resolution
+                    .define(&field.key, SymbolKind::Field, names::escape(&field.key));
+            self.resolution.fields.insert(field, id);
+            frame.bound.insert(field.key.clone(), id);
+        }
+        self.frames.push(frame);
+        self.resolve_ast(format);
+        self.frames.pop();
+    }
+
+    ///Binds the variables of a pattern in the innermost frame, shadowing the ones with their names.
+    ///A name bound twice is reported, and its second binding shadows the first one
+    fn bind(&mut self, pattern: &Pattern, param: bool) {
+        let mut names = HashSet::new();
+        for binding in pattern.binding_patterns() {
+            let Pattern::Binding(name) = binding else {
+                unreachable!();
+            };
+            if !names.insert(name) {
+                self.report(TypeError::DuplicateDefinition(name.clone()));
+            }
+            let emitted = self.emitted_name(name, param);
+            let id = self.resolution.define(name, SymbolKind::Variable, emitted);
+            self.resolution.bindings.insert(binding, id);
+            self.frames
+                .last_mut()
+                .unwrap()
+                .bound
+                .insert(name.clone(), id);
+        }
+    }
+
+    ///The name a new variable is emitted with. A variable shadowing another one is renamed, as
+    ///js can't tell them apart in some blocks. Parameters can shadow functions, as a function
+    ///can't be called by its name anywhere its parameter is visible
+    fn emitted_name(&mut self, name: &str, param: bool) -> String {
+        let (global, locals) = self.frames.split_first().unwrap();
+        let shadows = locals.iter().any(|frame| frame.bound.contains_key(name))
+            || (!param && global.bound.contains_key(name));
+        if !shadows {
+            return names::escape(name);
+        }
+        let n = self.renamed.entry(name.to_string()).or_default();
+        *n += 1;
+        names::rename(name, *n)
+    }
+
+    ///The symbol of the nearest binding of name. None after reporting it if there's none
+    fn lookup(&mut self, name: &str) -> Option<SymbolId> {
+        if let Some(id) = self.frames.iter().rev().find_map(|f| f.bound.get(name)) {
+            return Some(*id);
+        }
+        if self.frames.iter().any(|f| f.declared.contains(name)) {
+            self.report(TypeError::UseBeforeDefinition(name.to_string()));
+        } else {
+            self.report(TypeError::NotRecognizedVar(name.to_string()));
+        }
+        None
+    }
+}
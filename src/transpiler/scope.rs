@@ -1,44 +1,127 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet};
 
-use crate::parser::KeyTypePair;
-
-use super::{BeatriceType, TypeError, checkings::checker::TypeChecker};
+use super::{
+    BeatriceType, Bound, TypeError,
+    checkings::{
+        arguments::ParamSpec, checker::TypeChecker, enums::EnumDef, generics::TypeParam,
+        structs::StructDef,
+    },
+};
 
 #[derive(Default, Debug)]
 pub struct Scope {
-    var_names: HashSet<String>,
     function_names: HashSet<String>,
-    struct_names: HashSet<String>,
     types: TypeChecker,
+    ///Generic parameters usable as types in this scope, with their bounds
+    type_params: HashMap<String, Vec<Bound>>,
+    ///Generic parameters of the functions and structs declared in this scope
+    generics: HashMap<String, Vec<TypeParam>>,
+    ///Parameters of the functions declared in this scope, to match the arguments of their calls
+    parameters: HashMap<String, Vec<ParamSpec>>,
+    structs: HashMap<String, StructDef>,
+    enums: HashMap<String, EnumDef>,
+    ///The structs with the @Display attribute
+    displays: HashSet<String>,
+    ///The return type of the function whose body this scope is
+    return_type: Option<BeatriceType>,
 }
 
 impl Scope {
     pub fn new() -> Self {
         Self {
-            var_names: HashSet::new(),
             function_names: HashSet::new(),
-            struct_names: HashSet::new(),
             types: TypeChecker::new(),
+            type_params: HashMap::new(),
+            generics: HashMap::new(),
+            parameters: HashMap::new(),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            displays: HashSet::new(),
+            return_type: None,
         }
     }
 
     #[inline]
-    pub fn define_variable(&mut self, varname: String, kind: BeatriceType) {
-        self.var_names.insert(varname.clone());
-        self.types.define(varname, kind);
+    pub fn define_type_param(&mut self, name: String, bounds: Vec<Bound>) {
+        self.type_params.insert(name, bounds);
+    }
+
+    #[inline]
+    pub fn define_generics(&mut self, name: String, params: Vec<TypeParam>) {
+        self.generics.insert(name, params);
+    }
+
+    #[inline]
+    pub fn define_parameters(&mut self, name: String, params: Vec<ParamSpec>) {
+        self.parameters.insert(name, params);
+    }
+
+    #[inline]
+    pub fn parameters_of(&self, name: &str) -> Option<&Vec<ParamSpec>> {
+        self.parameters.get(name)
+    }
+
+    #[inline]
+    pub fn type_param_bounds(&self, name: &str) -> Option<&Vec<Bound>> {
+        self.type_params.get(name)
+    }
+
+    #[inline]
+    pub fn generics_of(&self, name: &str) -> Option<&Vec<TypeParam>> {
+        self.generics.get(name)
     }
 
     #[inline]
     pub fn define_function(&mut self, name: String, kind: BeatriceType) {
-        assert!(matches!(kind, BeatriceType::Function { .. }));
+        assert!(matches!(
+            kind,
+            BeatriceType::Function { .. } | BeatriceType::Error
+        ));
         self.function_names.insert(name.clone());
         self.types.define(name, kind);
     }
     #[inline]
-    pub fn define_struct(&mut self, name: String, kind: BeatriceType) {
-        assert!(matches!(kind, BeatriceType::Struct { .. }));
-        self.struct_names.insert(name.to_string());
-        self.types.define(name, kind);
+    pub fn define_struct(&mut self, name: String, def: StructDef) {
+        self.structs.insert(name, def);
+    }
+
+    #[inline]
+    pub fn define_enum(&mut self, name: String, def: EnumDef) {
+        self.enums.insert(name, def);
+    }
+
+    #[inline]
+    pub fn define_display(&mut self, name: String) {
+        self.displays.insert(name);
+    }
+
+    #[inline]
+    pub fn has_display(&self, name: &str) -> bool {
+        self.displays.contains(name)
+    }
+
+    pub fn displays(&self) -> impl Iterator<Item = &String> {
+        self.displays.iter()
+    }
+
+    #[inline]
+    pub fn set_return_type(&mut self, kind: BeatriceType) {
+        self.return_type = Some(kind);
+    }
+
+    #[inline]
+    pub fn return_type(&self) -> Option<&BeatriceType> {
+        self.return_type.as_ref()
+    }
+
+    #[inline]
+    pub fn enum_def(&self, name: &str) -> Option<&EnumDef> {
+        self.enums.get(name)
+    }
+
+    #[inline]
+    pub fn struct_def(&self, name: &str) -> Option<&StructDef> {
+        self.structs.get(name)
     }
 
     #[inline]
@@ -48,16 +131,46 @@ impl Scope {
 
     #[inline]
     pub fn has_struct(&self, name: &str) -> bool {
-        self.struct_names.contains(name)
+        self.structs.contains_key(name)
     }
 
-    #[inline]
-    pub fn has_variable_or_function(&self, name: &str) -> bool {
-        self.var_names.contains(name) || self.function_names.contains(name)
+    pub fn functions(&self) -> impl Iterator<Item = &String> {
+        self.function_names.iter()
+    }
+
+    ///Copies into this scope the function, struct or enum of another scope with the given name,
+    ///along its generic parameters. Returns false if there is nothing with that name to copy
+    pub fn import(&mut self, from: &Scope, name: &str) -> bool {
+        let mut found = false;
+        if let Some(kind) = from.types.get(name)
+            && from.has_function(name)
+        {
+            self.define_function(name.to_string(), kind.clone());
+            if let Some(params) = from.parameters_of(name) {
+                self.define_parameters(name.to_string(), params.clone());
+            }
+            found = true;
+        }
+        //the struct keeps its id, so its instances have the same type in both modules
+        if let Some(def) = from.struct_def(name) {
+            self.define_struct(name.to_string(), def.clone());
+            if from.has_display(name) {
+                self.define_display(name.to_string());
+            }
+            found = true;
+        }
+        if let Some(def) = from.enum_def(name) {
+            self.define_enum(name.to_string(), def.clone());
+            found = true;
+        }
+        if found && let Some(generics) = from.generics_of(name) {
+            self.define_generics(name.to_string(), generics.clone());
+        }
+        found
     }
 
     #[inline]
-    /// Gets the typeof a variable. Throws if it does not exist
+    /// Gets the typeof a function. Throws if it does not exist
     pub fn kindof(&self, name: &str) -> Result<&BeatriceType, TypeError> {
         self.types
             .get(name)
@@ -1,20 +1,365 @@
-use crate::{parser::AST, transpiler::transpiler::BeatriceTranspiler};
+use crate::{
+    parser::Operator,
+    transpiler::{
+        BeatriceType,
+        hir::{Expr, ExprKind},
+        transpiler::BeatriceTranspiler,
+    },
+};
+
+///The precedence of an operator in js, the lower the tighter it binds. It isn't the one it has in
+///beatrice, where the bitwise operators bind tighter than the comparisons, and equality as tight
+///as the other comparisons
+fn js_precedence(operator: &Operator) -> u8 {
+    match operator {
+        Operator::Pow(false) => 1,
+        Operator::Star(false) | Operator::Slash(false) | Operator::Mod(false) => 2,
+        Operator::Add(false) | Operator::Sub(false) => 3,
+        Operator::BitLeft(false) | Operator::BitRight(false) => 4,
+        Operator::Lt(_) | Operator::Gt(_) => 5,
+        Operator::Eq(true) | Operator::Bang(true) => 6,
+        Operator::BitAnd(false) => 7,
+        Operator::Xor(false) => 8,
+        Operator::BitOr(false) => 9,
+        Operator::And(false) => 10,
+        Operator::Or(false) | Operator::Coalesce => 11,
+        _ => panic!("This is a bug. {operator} isn't a binary operator of an expression"),
+    }
+}
+
+///Whether an operand must be kept inside parenthesis whatever their precedences are. js rejects a
+///?? next to a && or a || without them
+fn must_group(operator: &Operator, operand: Option<&Operator>) -> bool {
+    let logical = |operator: &Operator| matches!(operator, Operator::And(_) | Operator::Or(_));
+    match operand {
+        Some(operand) => {
+            (*operator == Operator::Coalesce && logical(operand))
+                || (logical(operator) && *operand == Operator::Coalesce)
+        }
+        None => false,
+    }
+}
+
+///How a js number is brought back to the values of a numeric type
+enum Adaptation {
+    ///Applying a js operator, with the js precedence of the operator it is
+    Operator(&'static str, u8),
+    ///Calling a js function, written up to the argument it's given
+    Call(&'static str),
+    ///Wrapping around 64 bits through a bigint, with its BigInt.asIntN or BigInt.asUintN, for
+    ///an u64 or a float converted to an int out of bigint mode. A float is truncated first, and
+    ///NaN and the infinities become 0
+    Wrapping(&'static str),
+}
+
+impl Adaptation {
+    fn of(kind: &BeatriceType, bigint: bool) -> Option<Self> {
+        let shift = js_precedence(&Operator::BitLeft(false));
+        let and = js_precedence(&Operator::BitAnd(false));
+        Some(match kind {
+            BeatriceType::I8 => Self::Operator(" << 24 >> 24", shift),
+            BeatriceType::I16 => Self::Operator(" << 16 >> 16", shift),
+            BeatriceType::I32 => Self::Operator(" | 0", js_precedence(&Operator::BitOr(false))),
+            BeatriceType::U8 => Self::Operator(" & 0xFF", and),
+            BeatriceType::U16 => Self::Operator(" & 0xFFFF", and),
+            BeatriceType::U32 => Self::Operator(" >>> 0", shift),
+            BeatriceType::F32 => Self::Call("Math.fround("),
+            BeatriceType::Int if bigint => Self::Call("BigInt.asIntN(64, "),
+            BeatriceType::U64 if bigint => Self::Call("BigInt.asUintN(64, "),
+            BeatriceType::Int => Self::Call("Math.trunc("),
+            BeatriceType::U64 => Self::Wrapping("asUintN"),
+            _ => return None,
+        })
+    }
+
+    ///The adaptation of a value converted to kind. An int computed from ints only has to be
+    ///truncated, but a float converted to an int can be past 64 bits, or not be a number at all
+    fn of_cast(kind: &BeatriceType, bigint: bool) -> Option<Self> {
+        match kind {
+            BeatriceType::Int if !bigint => Some(Self::Wrapping("asIntN")),
+            _ => Self::of(kind, bigint),
+        }
+    }
+
+    ///Adapts content, an expression whose operator binding loosest has the given precedence
+    fn apply(&self, content: String, precedence: Option<u8>) -> (String, Option<u8>) {
+        match self {
+            Self::Operator(operation, own) => {
+                let content = if precedence.is_some_and(|p| p >= *own) {
+                    format!("({content}){operation}")
+                } else {
+                    format!("{content}{operation}")
+                };
+                (content, Some(*own))
+            }
+            Self::Call(function) => (format!("{function}{content})"), None),
+            Self::Wrapping(as_n) => (
+                format!("Number(BigInt.{as_n}(64, BigInt(Math.trunc({content}) % 2 ** 64 || 0)))"),
+                None,
+            ),
+        }
+    }
+}
 
 impl BeatriceTranspiler {
-    pub(crate) fn generate_binexpr_content(&mut self, ast: &AST) -> String {
-        let AST::BinExpr(lhs, rhs, operator) = ast else {
+    pub(crate) fn generate_binexpr_content(&mut self, expr: &Expr) -> String {
+        self.generate_operation(expr).0
+    }
+
+    pub(crate) fn generate_cast_content(&mut self, expr: &Expr) -> String {
+        self.generate_cast(expr).0
+    }
+
+    ///Generates a literal as a value of the type it was given, rounded when it's an f32 that can't
+    ///hold it as it is. Int literals are ints unless they were given another type
+    pub(crate) fn generate_number_content(&mut self, expr: &Expr) -> String {
+        let kind = &expr.ty;
+        match expr.kind {
+            ExprKind::Int(i) if *kind == BeatriceType::F32 && i as f32 as u64 != i => {
+                format!("Math.fround({i})")
+            }
+            ExprKind::Int(i) if self.is_bigint(kind) => format!("{i}n"),
+            ExprKind::Int(i) => i.to_string(),
+            ExprKind::Float(f) if *kind == BeatriceType::F32 && f as f32 as f64 != f => {
+                format!("Math.fround({f})")
+            }
+            ExprKind::Float(f) => f.to_string(),
+            _ => panic!("This is a bug. Expected receiving a number"),
+        }
+    }
+
+    ///Generates an operand of a binary expression, along the precedence of the operator binding
+    ///loosest in it. None when no operator around it can split it
+    fn generate_operand(&mut self, expr: &Expr) -> (String, Option<u8>) {
+        match expr.kind {
+            ExprKind::Binary { .. } => self.generate_operation(expr),
+            ExprKind::Cast { .. } => self.generate_cast(expr),
+            _ => (self.generate_expression_content(expr), None),
+        }
+    }
+
+    fn generate_operation(&mut self, expr: &Expr) -> (String, Option<u8>) {
+        let ExprKind::Binary {
+            lhs,
+            rhs,
+            operator,
+            numeric,
+        } = &expr.kind
+        else {
             panic!("This is a bug. Expected receiving a BinExpr");
         };
-        let rhs = if let AST::BinExpr(_, _, op) = &**rhs {
-            if op.precedence() > operator.precedence() {
-                format!("({})", self.generate_binexpr_content(rhs))
-            } else {
-                self.generate_binexpr_content(rhs)
+        let precedence = js_precedence(operator);
+        let operator_of = |operand: &Expr| match &operand.kind {
+            ExprKind::Binary { operator, .. } => Some(operator.clone()),
+            _ => None,
+        };
+        let (lhs_operator, rhs_operator) = (operator_of(lhs), operator_of(rhs));
+        //the parser already grouped the operands, so a side binding looser than the operator in
+        //js, or a right side binding as loose as it, must be kept inside parenthesis
+        let (lhs, lhs_precedence) = self.generate_operand(lhs);
+        let lhs = if lhs_precedence.is_some_and(|p| p > precedence)
+            || must_group(operator, lhs_operator.as_ref())
+        {
+            format!("({lhs})")
+        } else {
+            lhs
+        };
+        //the right side of &&, || and ?? is only evaluated when the left one doesn't decide
+        let lazy = matches!(
+            operator,
+            Operator::And(_) | Operator::Or(_) | Operator::Coalesce
+        );
+        let (rhs, rhs_precedence) = if lazy {
+            //the statements it hoists are written inside the if running it
+            self.increase_identation_level();
+            let ((rhs, rhs_precedence), hoisted) =
+                self.with_own_hoisted(|this| this.generate_operand(rhs));
+            self.decrease_identation_level();
+            if !hoisted.is_empty() {
+                return (
+                    self.generate_lazy_operation(operator, lhs, rhs, hoisted),
+                    None,
+                );
             }
+            (rhs, rhs_precedence)
         } else {
-            self.generate_expression_content(rhs)
+            self.generate_operand(rhs)
+        };
+        let rhs = if rhs_precedence.is_some_and(|p| p >= precedence)
+            || must_group(operator, rhs_operator.as_ref())
+        {
+            format!("({rhs})")
+        } else {
+            rhs
+        };
+        let Some(kind) = numeric else {
+            return (format!("{lhs} {operator} {rhs}"), Some(precedence));
+        };
+        let shift = js_precedence(&Operator::BitRight(false));
+        let bigint = self.is_bigint(kind);
+        match (operator, kind) {
+            //the product of two 32 bit integers can be past 2^53, where js numbers lose precision
+            (Operator::Star(false), BeatriceType::I32) => {
+                (format!("Math.imul({lhs}, {rhs})"), None)
+            }
+            (Operator::Star(false), BeatriceType::U32) => {
+                (format!("Math.imul({lhs}, {rhs}) >>> 0"), Some(shift))
+            }
+            //an u32 is shifted without keeping its sign
+            (Operator::BitRight(false), BeatriceType::U32) => {
+                (format!("{lhs} >>> {rhs}"), Some(shift))
+            }
+            //an u64 that isn't a bigint is computed on bigints, so it wraps exactly instead of
+            //going negative or past 2^64
+            (
+                Operator::Add(false)
+                | Operator::Sub(false)
+                | Operator::Star(false)
+                | Operator::Pow(false)
+                | Operator::BitLeft(false)
+                | Operator::BitRight(false)
+                | Operator::BitAnd(false)
+                | Operator::BitOr(false)
+                | Operator::Xor(false),
+                BeatriceType::U64,
+            ) if !bigint => (
+                Self::generate_on_bigints("asUintN", &lhs, operator, &rhs),
+                None,
+            ),
+            //the power of a sized integer can be past 2^53, where its js number was already rounded
+            //before wrapping, so it's computed on bigints. A negative exponent throws a RangeError,
+            //as it does in bigint mode
+            (Operator::Pow(false), _)
+                if *kind != BeatriceType::Int
+                    && let Some((min, max)) = kind.integer_range() =>
+            {
+                let bits = (max - min + 1).trailing_zeros();
+                let as_n = if min < 0 { "asIntN" } else { "asUintN" };
+                (
+                    format!("Number(BigInt.{as_n}({bits}, BigInt({lhs}) ** BigInt({rhs})))"),
+                    None,
+                )
+            }
+            //js bitwise operators only keep 32 bits of a number, so the ones of an int that isn't
+            //a bigint are computed on bigints too
+            (
+                Operator::BitLeft(false)
+                | Operator::BitRight(false)
+                | Operator::BitAnd(false)
+                | Operator::BitOr(false)
+                | Operator::Xor(false),
+                BeatriceType::Int,
+            ) if !bigint => (
+                Self::generate_on_bigints("asIntN", &lhs, operator, &rhs),
+                None,
+            ),
+            //as in rust, a bigint is shifted by the amount modulo its bits
+            (Operator::BitLeft(false) | Operator::BitRight(false), _) if bigint => {
+                let content = format!("{lhs} {operator} ({rhs} & 63n)");
+                match operator {
+                    Operator::BitLeft(false) => Adaptation::of(kind, true)
+                        .unwrap()
+                        .apply(content, Some(precedence)),
+                    _ => (content, Some(precedence)),
+                }
+            }
+            _ => {
+                let content = format!("{lhs} {operator} {rhs}");
+                match Adaptation::of(kind, bigint) {
+                    Some(adaptation) => adaptation.apply(content, Some(precedence)),
+                    None => (content, Some(precedence)),
+                }
+            }
+        }
+    }
+
+    ///Generates an operation of 64 bit integers that aren't bigints by converting its operands to
+    ///bigints, wrapping the result with as_n, asIntN or asUintN. As in rust, shifts are by the
+    ///amount modulo 64
+    fn generate_on_bigints(as_n: &str, lhs: &str, operator: &Operator, rhs: &str) -> String {
+        let rhs = match operator {
+            Operator::BitLeft(false) | Operator::BitRight(false) => {
+                format!("(BigInt({rhs}) & 63n)")
+            }
+            _ => format!("BigInt({rhs})"),
         };
-        let lhs = self.generate_expression_content(lhs);
-        format!("{lhs} {operator} {rhs}")
+        format!("Number(BigInt.{as_n}(64, BigInt({lhs}) {operator} {rhs}))")
+    }
+
+    ///Generates a &&, || or ?? whose right side hoists statements, such as the checks of a `?`.
+    ///They can only run when the left side doesn't decide the value, so the operation is an if
+    ///giving its value to a temporary
+    fn generate_lazy_operation(
+        &mut self,
+        operator: &Operator,
+        lhs: String,
+        rhs: String,
+        hoisted: String,
+    ) -> String {
+        let name = self.temporary("lazy");
+        let test = match operator {
+            Operator::And(_) => name.clone(),
+            Operator::Or(_) => format!("!{name}"),
+            _ => format!("{name} === undefined"),
+        };
+        self.increase_identation_level();
+        let body = self.indent(format!("{hoisted}{name} = {rhs};"));
+        self.decrease_identation_level();
+        self.hoist(format!("let {name} = {lhs};"));
+        self.hoist(format!("if({test}){{\n{body}\n{}}}", self.indent("")));
+        name
+    }
+
+    fn generate_cast(&mut self, cast: &Expr) -> (String, Option<u8>) {
+        let ExprKind::Cast {
+            expr,
+            numeric,
+            bigint,
+        } = &cast.kind
+        else {
+            panic!("This is a bug. Expected receiving a Cast");
+        };
+        let (content, precedence) = self.generate_operand(expr);
+        if let Some((from, to)) = bigint {
+            return (Self::generate_bigint_cast(from, to, content), None);
+        }
+        match numeric
+            .as_ref()
+            .and_then(|kind| Adaptation::of_cast(kind, self.is_bigint(kind)))
+        {
+            Some(adaptation) => adaptation.apply(content, precedence),
+            None => (content, precedence),
+        }
+    }
+
+    ///Converts content, a value of type from, to one of type to, when one is a bigint and the
+    ///other a js number
+    fn generate_bigint_cast(from: &BeatriceType, to: &BeatriceType, content: String) -> String {
+        match to {
+            BeatriceType::Int | BeatriceType::U64 => {
+                let unsigned = *to == BeatriceType::U64;
+                match from.integer_range() {
+                    //a negative integer given to an u64 wraps
+                    Some((min, _)) if unsigned && min < 0 => {
+                        format!("BigInt.asUintN(64, BigInt({content}))")
+                    }
+                    Some(_) => format!("BigInt({content})"),
+                    //a float must be made an integer first, and wraps when it's past 64 bits. The
+                    //remainder is exact, and NaN for NaN and the infinities, which become 0
+                    None => {
+                        let as_n = if unsigned { "asUintN" } else { "asIntN" };
+                        format!("BigInt.{as_n}(64, BigInt(Math.trunc({content}) % 2 ** 64 || 0))")
+                    }
+                }
+            }
+            //the bigint wraps before being a number, so it doesn't lose precision
+            _ if let Some((min, max)) = to.integer_range() => {
+                let bits = (max - min + 1).trailing_zeros();
+                let as_n = if min < 0 { "asIntN" } else { "asUintN" };
+                format!("Number(BigInt.{as_n}({bits}, {content}))")
+            }
+            BeatriceType::F32 => format!("Math.fround(Number({content}))"),
+            _ => format!("Number({content})"),
+        }
     }
 }
@@ -1,108 +1,141 @@
-use crate::{parser::AST, transpiler::transpiler::BeatriceTranspiler};
+use crate::transpiler::{
+    hir::{Expr, ExprKind},
+    transpiler::BeatriceTranspiler,
+};
 
 use super::TranspileCondition;
 
 impl BeatriceTranspiler {
     fn generate_if_branch(
         &mut self,
-        cond: &AST,
-        ast: &AST,
+        cond: &Expr,
+        block: &Expr,
         condition: &TranspileCondition,
     ) -> String {
         let cond = self.generate_expression_content(cond);
+        let hoisted = self.take_hoisted();
         let cond_value = match condition {
             TranspileCondition::Assign(s) => format!("{s} ="),
             TranspileCondition::Return => "return".to_string(),
             _ => panic!("If Expression should have a condition"),
         };
-        if !ast.is_blockexpr() {
-            let assign = self.generate_expression_content(ast);
-            let mut out = self.indent(format!("if({cond}) {cond_value} {assign};\n"));
+        if !block.is_block() {
+            let assign = self.generate_expression_content(block);
+            let assign = format!("{}{assign}", self.take_hoisted());
+            let mut out = self.indent(format!("{hoisted}if({cond}) {cond_value} {assign};\n"));
             out.push_str(&self.indent(""));
             out
         } else {
-            let mut content = self.indent(format!("if({cond}){{\n"));
+            let mut content = self.indent(format!("{hoisted}if({cond}){{\n"));
             self.increase_identation_level();
-            let AST::Block(exprs) = ast else {
+            let ExprKind::Block(exprs) = &block.kind else {
                 unreachable!()
             };
-            if let Some(last) = exprs.back() {
+            if let Some(last) = exprs.last() {
                 for (idx, expr) in exprs.iter().enumerate() {
                     if idx == exprs.len() - 1 {
                         break;
                     };
-                    let exprcontent = format!("{}\n", self.generate_expression_content(expr));
+                    let exprcontent = self.generate_statement_content(expr);
+                    let exprcontent = format!("{}{exprcontent}\n", self.take_hoisted());
                     content.push_str(&self.indent(exprcontent));
                 }
-                if matches!(last, AST::If { .. }) {
+                if matches!(last.kind, ExprKind::If { .. }) {
                     let exprassign =
                         format!("{};\n", self.generate_if_expr_assign(last, condition));
                     content.push_str(&exprassign);
+                } else if matches!(last.kind, ExprKind::Match { .. }) {
+                    let exprassign = self.generate_match(last, condition);
+                    content.push_str(&self.indent(format!("{exprassign}\n")));
                 } else {
                     let exprcontent = self.generate_expression_content(last);
-                    content.push_str(&self.indent(format!("{cond_value} {exprcontent};\n")));
+                    let hoisted = self.take_hoisted();
+                    //a jump leaves the branch instead of giving its value
+                    let exprcontent = if last.is_jump() {
+                        exprcontent
+                    } else {
+                        format!("{cond_value} {exprcontent};")
+                    };
+                    content.push_str(&self.indent(format!("{hoisted}{exprcontent}\n")));
                 };
             }
+            //the else branch is written after the closing brace
+            self.decrease_identation_level();
+            content.push_str(&self.indent("} "));
             content
         }
     }
-    fn generate_else_branch(&mut self, elseblock: &AST, condition: &TranspileCondition) -> String {
+    fn generate_else_branch(&mut self, elseblock: &Expr, condition: &TranspileCondition) -> String {
         let cond_value = match condition {
             TranspileCondition::Assign(s) => format!("{s} ="),
             TranspileCondition::Return => "return".to_string(),
             _ => panic!("If Expression should have a condition"),
         };
 
-        if elseblock.is_blockexpr() {
+        if elseblock.is_block() {
             let mut content = "else {\n".to_string();
             self.increase_identation_level();
 
-            let AST::Block(exprs) = elseblock else {
+            let ExprKind::Block(exprs) = &elseblock.kind else {
                 unreachable!()
             };
-            if let Some(last) = exprs.back() {
+            if let Some(last) = exprs.last() {
                 for (idx, expr) in exprs.iter().enumerate() {
                     if idx == exprs.len() - 1 {
                         break;
                     };
-                    let exprcontent = self.generate_expression_content(expr);
+                    let exprcontent = self.generate_statement_content(expr);
+                    let exprcontent = format!("{}{exprcontent}", self.take_hoisted());
                     content.push_str(&self.indent(exprcontent));
                     content.push('\n');
                 }
-                if matches!(last, AST::If { .. }) {
+                if matches!(last.kind, ExprKind::If { .. }) {
                     let exprassign = self.generate_if_expr_assign(last, condition);
                     content.push_str(&exprassign);
                     content.push('\n');
+                } else if matches!(last.kind, ExprKind::Match { .. }) {
+                    let exprassign = self.generate_match(last, condition);
+                    content.push_str(&self.indent(format!("{exprassign}\n")));
                 } else {
                     let exprcontent = self.generate_expression_content(last);
-                    content.push_str(&self.indent(format!("{cond_value} {exprcontent};\n")));
+                    let hoisted = self.take_hoisted();
+                    //a jump leaves the branch instead of giving its value
+                    let exprcontent = if last.is_jump() {
+                        exprcontent
+                    } else {
+                        format!("{cond_value} {exprcontent};")
+                    };
+                    content.push_str(&self.indent(format!("{hoisted}{exprcontent}\n")));
                 };
             }
             self.decrease_identation_level();
             content.push_str(&self.indent("}"));
             content
         } else {
-            format!(
-                "else {cond_value} {};",
-                self.generate_expression_content(elseblock)
-            )
+            let content = self.generate_expression_content(elseblock);
+            let hoisted = self.take_hoisted();
+            if hoisted.is_empty() {
+                format!("else {cond_value} {content};")
+            } else {
+                format!("else {{ {hoisted}{cond_value} {content}; }}")
+            }
         }
     }
     pub(crate) fn generate_if_expr_assign(
         &mut self,
-        ast: &AST,
+        expr: &Expr,
         condition: &TranspileCondition,
     ) -> String {
-        let AST::If {
-            expr,
+        let ExprKind::If {
+            cond,
             block,
             elseblock,
-        } = ast
+        } = &expr.kind
         else {
             unreachable!()
         };
         if let Some(elseblock) = elseblock {
-            let ifbranch = self.generate_if_branch(expr, block, condition);
+            let ifbranch = self.generate_if_branch(cond, block, condition);
             let elsebranch = self.generate_else_branch(elseblock, condition);
             format!("{ifbranch}{elsebranch}")
         } else {
@@ -111,19 +144,31 @@ impl BeatriceTranspiler {
             );
         }
     }
-    pub(crate) fn generate_if_expr(&mut self, ast: &AST) -> String {
-        let AST::If {
-            expr,
+    ///Generates an if giving its value inside another expression, such as an argument. The if is
+    ///hoisted before the statement, assigning a temporary which takes its place
+    pub(crate) fn generate_if_value(&mut self, expr: &Expr) -> String {
+        let name = self.temporary("if");
+        //the statements hoisted before belong to the statement, not to the condition of the if
+        let (content, hoisted) = self.with_own_hoisted(|this| {
+            this.generate_if_expr_assign(expr, &TranspileCondition::Assign(name.clone()))
+        });
+        self.hoist(format!("let {name};"));
+        self.hoist(format!("{hoisted}{}", content.trim_start()));
+        name
+    }
+    pub(crate) fn generate_if_expr(&mut self, expr: &Expr) -> String {
+        let ExprKind::If {
+            cond,
             block,
             elseblock,
-        } = ast
+        } = &expr.kind
         else {
             unreachable!()
         };
 
         let mut out = format!(
-            "if({}){};",
-            self.generate_expression_content(expr),
+            "if({}){}",
+            self.generate_expression_content(cond),
             self.generate_expression_content(block)
         );
         if let Some(elsebranch) = elseblock {
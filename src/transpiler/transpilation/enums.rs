@@ -0,0 +1,47 @@
+use crate::transpiler::{
+    hir::{Expr, ExprKind, VariantArgs},
+    transpiler::BeatriceTranspiler,
+};
+
+impl BeatriceTranspiler {
+    pub(crate) fn variant_tag(&self, name: &str, variant: &str) -> usize {
+        self.enum_def(name)
+            .and_then(|def| def.variant_index(variant))
+            .expect("This is a bug. Variants are checked before being lowered")
+    }
+    ///Generates a variant as an object holding its tag at `$` next to its fields, such as
+    ///{$:0,0:1.5} for Shape::Circle(1.5) or {$:1,w:1,h:2} for Shape::Rect { w: 1, h: 2 }
+    pub(crate) fn generate_variant_content(&mut self, expr: &Expr) -> String {
+        let ExprKind::Variant {
+            name,
+            variant,
+            tag,
+            args,
+        } = &expr.kind
+        else {
+            panic!("This is a bug. Expected to receive a variant expression");
+        };
+        let mut out = format!("/**{name}::{variant}*/ {{$:{tag}");
+        match args {
+            VariantArgs::Unit => {}
+            VariantArgs::Tuple(values) => {
+                for (idx, value) in values.iter().enumerate() {
+                    let content = self.generate_expression_content(value);
+                    out.push_str(&format!(",{idx}:{content}"));
+                }
+            }
+            VariantArgs::Struct(fields) => {
+                for (key, value) in fields {
+                    let content = self.generate_expression_content(value);
+                    if content == *key {
+                        out.push_str(&format!(",{content}"));
+                    } else {
+                        out.push_str(&format!(",{key}:{content}"));
+                    }
+                }
+            }
+        }
+        out.push('}');
+        out
+    }
+}
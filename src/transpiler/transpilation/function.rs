@@ -1,13 +1,93 @@
-use std::collections::VecDeque;
+use crate::transpiler::{
+    hir::{Expr, ExprKind, Param, Pattern},
+    transpiler::BeatriceTranspiler,
+};
 
-use crate::{parser::AST, transpiler::transpiler::BeatriceTranspiler};
+///The value given to a parameter left out in the middle of a call, so the function gives it its
+///default value. undefined can't be used, as it's the None given to optional parameters
+pub(crate) const DEFAULT_ARGUMENT: &str = "Symbol.for(\"beatrice.default\")";
 
 impl BeatriceTranspiler {
-    pub(crate) fn generate_fcall_content(&mut self, name: &str, args: &VecDeque<AST>) -> String {
-        let mut params = Vec::with_capacity(args.len());
-        for arg in args {
-            params.push(self.generate_expression_content(arg));
+    ///Generates a call, passing the arguments in the order of the parameters. When named arguments
+    ///were written in another order, the ones that may do something besides giving their value
+    ///are first assigned to temporaries in the order they were written, as in
+    ///($arg0 = one(), $arg1 = two(), f($arg1,$arg0))
+    pub(crate) fn generate_fcall_content(&mut self, call: &Expr) -> String {
+        let ExprKind::Call {
+            name,
+            args,
+            written,
+            ..
+        } = &call.kind
+        else {
+            panic!("This is a bug. Expected to receive a function call");
+        };
+        //the arguments left out at the end aren't passed at all
+        let given = args
+            .iter()
+            .rposition(Option::is_some)
+            .map_or(0, |last| last + 1);
+        let mut params = vec![DEFAULT_ARGUMENT.to_string(); given];
+        let mut assignments = Vec::new();
+        let reordered = !written.is_sorted();
+        for &param in written {
+            let Some(arg) = &args[param] else {
+                panic!("This is a bug. Only the parameters given an argument are written");
+            };
+            let content = self.generate_expression_content(arg);
+            params[param] = if reordered && !arg.is_pure() {
+                let temporary = self.temporary("arg");
+                self.hoist(format!("let {temporary};"));
+                assignments.push(format!("{temporary} = {content}"));
+                temporary
+            } else {
+                content
+            };
         }
-        format!("{name}({})", params.join(","))
+        let call = format!("{name}({})", params.join(","));
+        if assignments.is_empty() {
+            call
+        } else {
+            format!("({}, {call})", assignments.join(", "))
+        }
+    }
+
+    ///The name a parameter is received with. Destructured parameters with a default value are
+    ///received whole, as the default is given before destructuring them
+    pub(crate) fn generate_param_target(&self, idx: usize, param: &Param) -> String {
+        match &param.pattern {
+            Pattern::Wildcard => format!("_${idx}"),
+            Pattern::Binding { .. } => self.generate_pattern_target(&param.pattern),
+            _ if param.default.is_some() => format!("_${idx}"),
+            pattern => self.generate_pattern_target(pattern),
+        }
+    }
+
+    ///The statements starting a function that give its parameters their default values when a
+    ///call leaves them out
+    pub(crate) fn generate_defaults_content(&mut self, params: &[Param]) -> String {
+        let mut content = String::new();
+        for (idx, param) in params.iter().enumerate() {
+            let Some(default) = &param.default else {
+                continue;
+            };
+            let target = self.generate_param_target(idx, param);
+            let value = self.generate_expression_content(default);
+            let hoisted = self.take_hoisted();
+            let condition = format!("arguments.length <= {idx} || {target} === {DEFAULT_ARGUMENT}");
+            let statement = if hoisted.is_empty() {
+                format!("if({condition}) {target} = {value};")
+            } else {
+                format!("if({condition}){{ {hoisted}{target} = {value}; }}")
+            };
+            content.push_str(&self.indent(statement));
+            content.push('\n');
+            if !matches!(param.pattern, Pattern::Wildcard | Pattern::Binding { .. }) {
+                let pattern = self.generate_pattern_target(&param.pattern);
+                content.push_str(&self.indent(format!("let {pattern} = {target};")));
+                content.push('\n');
+            }
+        }
+        content
     }
 }
@@ -1,8 +1,16 @@
-use crate::{parser::AST, transpiler::transpiler::BeatriceTranspiler};
+use crate::transpiler::{hir::Expr, transpiler::BeatriceTranspiler};
 
 impl BeatriceTranspiler {
-    pub(crate) fn generate_loop_content(&mut self, body: &AST) -> String {
+    ///Generates a loop, labeled when a break leaves it. A break inside the switch of a match
+    ///would only leave the switch without the label
+    pub(crate) fn generate_loop_content(&mut self, body: &Expr, breaks: bool) -> String {
+        if !breaks {
+            let content = self.generate_expression_content(body);
+            return format!("for(;;){}", content);
+        }
+        let label = self.enter_loop_label();
         let content = self.generate_expression_content(body);
-        format!("for(;;){}", content)
+        self.exit_loop_label();
+        format!("{label}: for(;;){content}")
     }
 }
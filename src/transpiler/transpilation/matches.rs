@@ -0,0 +1,307 @@
+use crate::transpiler::{
+    hir::{Expr, ExprKind, MatchArm, Pattern, VariantPattern},
+    transpiler::BeatriceTranspiler,
+};
+
+use super::TranspileCondition;
+
+impl BeatriceTranspiler {
+    ///Whether pattern binds a variable emitted with the given name anywhere inside of it
+    fn binds_nested(pattern: &Pattern, name: &str) -> bool {
+        !matches!(pattern, Pattern::Binding { .. })
+            && pattern
+                .bindings()
+                .into_iter()
+                .any(|b| matches!(b, Pattern::Binding { name: bound, .. } if bound == name))
+    }
+    ///Collects the conditions the value at path must meet to match pattern, and the variables
+    ///the pattern binds with the paths of their values
+    fn pattern_tests(
+        &self,
+        pattern: &Pattern,
+        path: &str,
+        tests: &mut Vec<String>,
+        bindings: &mut Vec<(String, String)>,
+    ) {
+        match pattern {
+            Pattern::Wildcard => {}
+            Pattern::Binding { name, .. } => bindings.push((name.clone(), path.to_string())),
+            Pattern::Bool(b) => tests.push(format!("{path} === {b}")),
+            //bigints are equal to the numbers with their value only by ==
+            Pattern::Int(i) if self.bigint_mode() => tests.push(format!("{path} == {i}")),
+            Pattern::Int(i) => tests.push(format!("{path} === {i}")),
+            Pattern::Float(f) => tests.push(format!("{path} === {f}")),
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                tests.push(format!("{path} >= {start}"));
+                tests.push(format!(
+                    "{path} {} {end}",
+                    if *inclusive { "<=" } else { "<" }
+                ));
+            }
+            Pattern::Variant { tag, fields } => {
+                tests.push(format!("{path}.$ === {tag}"));
+                self.variant_fields_tests(fields, path, tests, bindings);
+            }
+            Pattern::Struct(fields) => {
+                for (key, pattern) in fields {
+                    let path = format!("{path}.{key}");
+                    self.pattern_tests(pattern, &path, tests, bindings);
+                }
+            }
+            Pattern::Tuple(patterns) | Pattern::Array(patterns) => {
+                if let Pattern::Array(_) = pattern {
+                    tests.push(format!("{path}.length === {}", patterns.len()));
+                }
+                for (idx, pattern) in patterns.iter().enumerate() {
+                    self.pattern_tests(pattern, &format!("{path}[{idx}]"), tests, bindings);
+                }
+            }
+            Pattern::Some(pattern) => {
+                tests.push(format!("{path} !== undefined"));
+                self.pattern_tests(pattern, path, tests, bindings);
+            }
+            Pattern::None => tests.push(format!("{path} === undefined")),
+            Pattern::Ok(pattern) => {
+                tests.push(format!("{path}.ok"));
+                self.pattern_tests(pattern, &format!("{path}.value"), tests, bindings);
+            }
+            Pattern::Err(pattern) => {
+                tests.push(format!("!{path}.ok"));
+                self.pattern_tests(pattern, &format!("{path}.error"), tests, bindings);
+            }
+        }
+    }
+    fn variant_fields_tests(
+        &self,
+        fields: &VariantPattern,
+        path: &str,
+        tests: &mut Vec<String>,
+        bindings: &mut Vec<(String, String)>,
+    ) {
+        match fields {
+            VariantPattern::Unit => {}
+            VariantPattern::Tuple(patterns) => {
+                for (idx, pattern) in patterns.iter().enumerate() {
+                    self.pattern_tests(pattern, &format!("{path}[{idx}]"), tests, bindings);
+                }
+            }
+            VariantPattern::Struct(fields) => {
+                for (key, pattern) in fields {
+                    let path = format!("{path}.{key}");
+                    self.pattern_tests(pattern, &path, tests, bindings);
+                }
+            }
+        }
+    }
+    ///Generates the body of an arm, giving its value as condition asks for and leaving the match
+    ///through exit
+    fn generate_arm_body(
+        &mut self,
+        body: &Expr,
+        condition: &TranspileCondition,
+        exit: &str,
+    ) -> String {
+        let mut out = String::new();
+        let last = if let ExprKind::Block(exprs) = &body.kind {
+            for expr in exprs.iter().take(exprs.len().saturating_sub(1)) {
+                let content = self.generate_statement_content(expr);
+                let content = format!("{}{content}", self.take_hoisted());
+                out.push_str(&self.indent(format!("{content}\n")));
+            }
+            exprs.last()
+        } else {
+            Some(body)
+        };
+        match (last, condition) {
+            (Some(last), _) if matches!(last.kind, ExprKind::Match { .. }) => {
+                let content = self.generate_match(last, condition);
+                out.push_str(&self.indent(format!("{content}\n")));
+            }
+            (Some(last), TranspileCondition::Assign(_) | TranspileCondition::Return)
+                if matches!(
+                    last.kind,
+                    ExprKind::If {
+                        elseblock: Some(_),
+                        ..
+                    }
+                ) =>
+            {
+                out.push_str(&self.generate_if_expr_assign(last, condition));
+                out.push('\n');
+            }
+            (Some(last), _) => {
+                let content = match condition {
+                    TranspileCondition::None => self.generate_statement_content(last),
+                    _ => self.generate_expression_content(last),
+                };
+                let hoisted = self.take_hoisted();
+                let content = match condition {
+                    //a jump leaves the arm instead of giving its value
+                    _ if last.is_jump() => format!("{content}\n"),
+                    TranspileCondition::Assign(s) => format!("{s} = {content};\n"),
+                    TranspileCondition::Return => format!("return {content};\n"),
+                    TranspileCondition::None => format!("{content};\n"),
+                };
+                out.push_str(&self.indent(format!("{hoisted}{content}")));
+            }
+            (None, _) => {}
+        }
+        if !exit.is_empty() {
+            out.push_str(&self.indent(format!("{exit}\n")));
+        }
+        out
+    }
+    ///Generates the arms in order, each one inside the tests of its pattern. Stops after an arm
+    ///that always matches, as the ones after it can't be reached. When skip_tag is set the tag of
+    ///the top variant patterns was already tested by a switch
+    fn generate_arms(
+        &mut self,
+        arms: &[&MatchArm],
+        path: &str,
+        skip_tag: bool,
+        condition: &TranspileCondition,
+        exit: &str,
+    ) -> String {
+        let mut out = String::new();
+        for arm in arms {
+            let mut tests = Vec::new();
+            let mut bindings = Vec::new();
+            match &arm.pattern {
+                Pattern::Variant { fields, .. } if skip_tag => {
+                    self.variant_fields_tests(fields, path, &mut tests, &mut bindings)
+                }
+                pattern => self.pattern_tests(pattern, path, &mut tests, &mut bindings),
+            }
+            let always = tests.is_empty() && arm.guard.is_none();
+            //every arm that may not match gets its own block, so its variables don't clash
+            if !tests.is_empty() {
+                out.push_str(&self.indent(format!("if({}){{\n", tests.join(" && "))));
+                self.increase_identation_level();
+            } else if !always {
+                out.push_str(&self.indent("{\n"));
+                self.increase_identation_level();
+            }
+            for (name, value) in bindings {
+                if name != value {
+                    out.push_str(&self.indent(format!("const {name} = {value};\n")));
+                }
+            }
+            if let Some(guard) = &arm.guard {
+                let guard = self.generate_expression_content(guard);
+                let hoisted = self.take_hoisted();
+                out.push_str(&self.indent(format!("{hoisted}if({guard}){{\n")));
+                self.increase_identation_level();
+                out.push_str(&self.generate_arm_body(&arm.body, condition, exit));
+                self.decrease_identation_level();
+                out.push_str(&self.indent("}\n"));
+            } else {
+                out.push_str(&self.generate_arm_body(&arm.body, condition, exit));
+            }
+            if always {
+                break;
+            }
+            self.decrease_identation_level();
+            out.push_str(&self.indent("}\n"));
+        }
+        out
+    }
+    ///Generates a match as statements. Matches on enums are a switch over the tag of the value,
+    ///where each case tests the rest of the patterns of the arms that can match that variant.
+    ///Other matches are a labeled block testing every arm in order
+    pub(crate) fn generate_match(&mut self, ast: &Expr, condition: &TranspileCondition) -> String {
+        let ExprKind::Match {
+            expr,
+            arms,
+            variants,
+        } = &ast.kind
+        else {
+            panic!("This is a bug. Expected to receive a match");
+        };
+        let mut out = String::new();
+        let path = match &expr.kind {
+            ExprKind::Var { name, .. }
+                if !arms
+                    .iter()
+                    .any(|arm| Self::binds_nested(&arm.pattern, name)) =>
+            {
+                name.clone()
+            }
+            _ => {
+                let name = self.temporary("match");
+                let content = self.generate_expression_content(expr);
+                out.push_str(&self.take_hoisted());
+                out.push_str(&format!("const {name} = {content};\n"));
+                out.push_str(&self.indent(""));
+                name
+            }
+        };
+        let exit = match condition {
+            TranspileCondition::Return => String::new(),
+            _ => "break;".to_string(),
+        };
+        let Some(variants) = *variants else {
+            let label = self.temporary("match");
+            let exit = if exit.is_empty() {
+                exit
+            } else {
+                format!("break {label};")
+            };
+            out.push_str(&format!("{label}: {{\n"));
+            self.increase_identation_level();
+            let arms = arms.iter().collect::<Vec<_>>();
+            out.push_str(&self.generate_arms(&arms, &path, false, condition, &exit));
+            self.decrease_identation_level();
+            out.push_str(&self.indent("}"));
+            return out;
+        };
+
+        out.push_str(&format!("switch({path}.$){{\n"));
+        self.increase_identation_level();
+        let mut needs_default = false;
+        for tag in 0..variants {
+            let is_variant =
+                |arm: &MatchArm| matches!(arm.pattern, Pattern::Variant { tag: t, .. } if t == tag);
+            if !arms.iter().any(is_variant) {
+                needs_default = true;
+                continue;
+            }
+            let case_arms = arms
+                .iter()
+                .filter(|arm| is_variant(arm) || !matches!(arm.pattern, Pattern::Variant { .. }))
+                .collect::<Vec<_>>();
+            out.push_str(&self.indent(format!("case {tag}: {{\n")));
+            self.increase_identation_level();
+            out.push_str(&self.generate_arms(&case_arms, &path, true, condition, &exit));
+            self.decrease_identation_level();
+            out.push_str(&self.indent("}\n"));
+        }
+        if needs_default {
+            let default_arms = arms
+                .iter()
+                .filter(|arm| !matches!(arm.pattern, Pattern::Variant { .. }))
+                .collect::<Vec<_>>();
+            out.push_str(&self.indent("default: {\n"));
+            self.increase_identation_level();
+            out.push_str(&self.generate_arms(&default_arms, &path, true, condition, &exit));
+            self.decrease_identation_level();
+            out.push_str(&self.indent("}\n"));
+        }
+        self.decrease_identation_level();
+        out.push_str(&self.indent("}"));
+        out
+    }
+    ///Generates a match whose value is used inside another expression, such as an argument. The
+    ///match is hoisted before the statement holding it, giving its value to a temporary that
+    ///takes its place in the expression
+    pub(crate) fn generate_match_value(&mut self, expr: &Expr) -> String {
+        let name = self.temporary("match");
+        let content = self.generate_match(expr, &TranspileCondition::Assign(name.clone()));
+        self.hoist(format!("let {name};"));
+        self.hoist(content);
+        name
+    }
+}
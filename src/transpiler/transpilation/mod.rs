@@ -1,7 +1,14 @@
 mod basics;
 mod conditions;
+mod enums;
 mod function;
 mod loops;
+mod matches;
+pub mod modules;
+pub(crate) mod names;
+mod results;
+mod strings;
+#[allow(clippy::module_inception)]
 mod transpilation;
 mod vars;
 
@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use crate::transpiler::{hir::Import, transpiler::BeatriceTranspiler};
+
+///The path of the JS module generated for the source module at path, such as ./geometry.js for
+///./geometry or ./geometry.bt
+pub fn js_module_path(path: &str) -> String {
+    match Path::new(path).extension() {
+        Some(ext) if ext == "bt" => format!("{}js", &path[..path.len() - 2]),
+        _ => format!("{path}.js"),
+    }
+}
+
+impl BeatriceTranspiler {
+    ///Generates an ES import of the names that exist at runtime. Importing only types generates
+    ///nothing
+    pub(crate) fn generate_import_content(&mut self, path: &str, names: &[Import]) -> String {
+        if names.is_empty() {
+            return String::new();
+        }
+        let names = names
+            .iter()
+            .map(|import| match &import.emitted {
+                emitted if *emitted == import.name => emitted.clone(),
+                emitted => format!("{} as {emitted}", import.name),
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "import {{ {} }} from \"{}\";",
+            names.join(", "),
+            js_module_path(path)
+        )
+    }
+}
@@ -0,0 +1,88 @@
+//Names are emitted as written, except the ones js reserves or gives a meaning of its own, which
+//end in $ so they can't clash with any Beatrice name. Variables shadowing another one get a
+//number after the $ instead, as js doesn't allow declaring a name twice in the same block and
+//a block can't use an outer variable with the name of one declared later in it.
+//The names made up by the transpiler have shapes these can't take: the temporaries start with a
+//$, and the variables a macro introduces end in $g and the number of its expansion.
+
+///The words js doesn't allow as names, or that the generated code relies on meaning what js
+///makes them mean, such as arguments or Math
+const RESERVED: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+    "arguments",
+    "eval",
+    "undefined",
+    "NaN",
+    "Infinity",
+    "BigInt",
+    "JSON",
+    "Math",
+    "Number",
+    "Object",
+    "Symbol",
+];
+
+///The name a Beatrice name is emitted with when it doesn't shadow anything
+pub(crate) fn escape(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{name}$")
+    } else {
+        name.to_string()
+    }
+}
+
+///The name the nth variable shadowing another one with the given name is emitted with
+pub(crate) fn rename(name: &str, n: usize) -> String {
+    format!("{}${n}", escape(name))
+}
+
+///The name of the nth temporary, or label, made up by the transpiler. kind tells what it holds,
+///such as the value of a match
+pub(crate) fn temporary(kind: &str, n: usize) -> String {
+    format!("${kind}{n}")
+}
@@ -0,0 +1,32 @@
+use crate::transpiler::{
+    hir::{Expr, ExprKind},
+    transpiler::BeatriceTranspiler,
+};
+
+impl BeatriceTranspiler {
+    ///Generates a result as the object { ok, value } or { ok, error }
+    pub(crate) fn generate_result_content(&mut self, expr: &Expr) -> String {
+        match &expr.kind {
+            ExprKind::Ok(value) => format!(
+                "{{ok:true,value:{}}}",
+                self.generate_expression_content(value)
+            ),
+            ExprKind::Err(error) => {
+                format!(
+                    "{{ok:false,error:{}}}",
+                    self.generate_expression_content(error)
+                )
+            }
+            _ => panic!("This is a bug. Expected to receive a result"),
+        }
+    }
+    ///Generates expr? as the value of the result, hoisting before the current statement the
+    ///return of its error
+    pub(crate) fn generate_try_content(&mut self, expr: &Expr) -> String {
+        let content = self.generate_expression_content(expr);
+        let name = self.temporary("try");
+        self.hoist(format!("const {name} = {content};"));
+        self.hoist(format!("if(!{name}.ok) return {name};"));
+        format!("{name}.value")
+    }
+}
@@ -0,0 +1,77 @@
+use crate::transpiler::{
+    hir::{Display, StringPart},
+    transpiler::BeatriceTranspiler,
+};
+
+impl BeatriceTranspiler {
+    ///Generates an interpolated string as a template literal, formatting the values that need it
+    ///with their helper
+    pub(crate) fn generate_interpolated_content(&mut self, parts: &[StringPart]) -> String {
+        let mut out = String::from("`");
+        for part in parts {
+            match part {
+                StringPart::Literal(text) => {
+                    out.push_str(&text.replace('`', "\\`").replace("${", "\\${"))
+                }
+                StringPart::Expr { expr, formatter } => {
+                    let content = self.generate_expression_content(expr);
+                    match formatter {
+                        Some(helper) => out.push_str(&format!("${{{helper}({content})}}")),
+                        None => out.push_str(&format!("${{{content}}}")),
+                    }
+                }
+            }
+        }
+        out.push('`');
+        out
+    }
+
+    ///Generates the $debug and $display helpers of a struct with the @Display attribute. prefix
+    ///is written before each one, as `export `
+    pub(crate) fn generate_display_helpers(&mut self, display: &Display, prefix: &str) -> String {
+        let Display {
+            name,
+            fields,
+            format,
+        } = display;
+        //the helpers take the fields as a parameter destructuring them. Fields with a reserved
+        //name are destructured into a variable with another one
+        let param = fields
+            .iter()
+            .map(|field| match &field.emitted {
+                emitted if *emitted == field.name => emitted.clone(),
+                emitted => format!("{}: {emitted}", field.name),
+            })
+            .collect::<Vec<_>>();
+        let param = format!("{{{}}}", param.join(", "));
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let var = &field.emitted;
+                match &field.formatter {
+                    Some(helper) => format!("{}: ${{{helper}({var})}}", field.name),
+                    None => format!("{}: ${{{var}}}", field.name),
+                }
+            })
+            .collect::<Vec<_>>();
+        let body = if fields.is_empty() {
+            format!("`{name}`")
+        } else {
+            format!("`{name} {{ {} }}`", fields.join(", "))
+        };
+        let display_body = match format {
+            Some(format) => self.generate_expression_content(format),
+            None => format!("{name}$debug({param})"),
+        };
+        let mut out = String::new();
+        for (helper, body) in [("debug", body), ("display", display_body)] {
+            out.push_str(&format!("{prefix}function {name}${helper}({param}){{\n"));
+            self.increase_identation_level();
+            out.push_str(&self.indent(format!("return {body};\n")));
+            self.decrease_identation_level();
+            out.push_str(&self.indent("}\n"));
+            out.push_str(&self.indent(""));
+        }
+        out.trim_end().to_string()
+    }
+}
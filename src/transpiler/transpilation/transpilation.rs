@@ -1,51 +1,59 @@
-use std::{collections::VecDeque, io::Write};
+use std::io::Write;
 
-use crate::{parser::AST, transpiler::transpiler::BeatriceTranspiler};
+use crate::transpiler::{
+    hir::{Expr, ExprKind, Function},
+    transpiler::BeatriceTranspiler,
+};
 
 impl BeatriceTranspiler {
-    pub(crate) fn generate_expression_content(&mut self, ast: &AST) -> String {
-        match ast {
-            AST::Function { .. } => self.generate_function_content(ast),
-            AST::Identifier(s) => s.clone(),
-            AST::Float(f) => f.to_string(),
-            AST::Int(i) => i.to_string(),
-            AST::VarDecl { .. } => self.generate_var_decl_content(ast),
-            AST::BinExpr(..) => self.generate_binexpr_content(ast),
-            AST::Return(r) => match &**r {
-                AST::Loop(body) => {
-                    format!("{};", self.generate_loop_content(body))
-                }
-                AST::If { .. } => {
+    pub(crate) fn generate_expression_content(&mut self, expr: &Expr) -> String {
+        match &expr.kind {
+            ExprKind::Function(f) => self.generate_function_content(f),
+            ExprKind::Var { name, .. } => name.clone(),
+            ExprKind::Float(_) | ExprKind::Int(_) => self.generate_number_content(expr),
+            ExprKind::Let { .. } => self.generate_var_decl_content(expr),
+            ExprKind::Binary { .. } => self.generate_binexpr_content(expr),
+            ExprKind::Return(None) => "return;".to_string(),
+            ExprKind::Return(Some(r)) => match &r.kind {
+                ExprKind::Loop { .. } => format!("{};", self.generate_expression_content(r)),
+                ExprKind::If { .. } => {
                     self.generate_if_expr_assign(r, &super::TranspileCondition::Return)
                 }
-                r => format!("return {};", self.generate_expression_content(r)),
+                ExprKind::Match { .. } => {
+                    self.generate_match(r, &super::TranspileCondition::Return)
+                }
+                _ => format!("return {};", self.generate_expression_content(r)),
             },
-            AST::FunctionCall { name, args } => self.generate_fcall_content(name, args),
-            AST::Struct { .. } => "".to_string(),
-            AST::StructExpr { name, fields } => {
+            ExprKind::Call { .. } => self.generate_fcall_content(expr),
+            ExprKind::Display(display) => self.generate_display_helpers(display, ""),
+            ExprKind::TypeDecl => String::new(),
+            ExprKind::Str(s) => format!("\"{s}\""),
+            ExprKind::Bool(b) => b.to_string(),
+            ExprKind::Interpolated(parts) => self.generate_interpolated_content(parts),
+            ExprKind::StructLit { name, fields } => {
                 let mut out = format!("/**{name}*/ {{");
-                for field in fields {
-                    match &field.value {
-                        AST::Identifier(fval) if field.key == *fval => {
-                            out.push_str(&field.key);
-                            out.push(',');
-                        }
-                        _ => {
-                            let content = self.generate_expression_content(&field.value);
-                            out.push_str(&format!("{}:{content},", field.key));
-                        }
+                for (key, value) in fields {
+                    let content = self.generate_expression_content(value);
+                    if content == *key {
+                        out.push_str(&format!("{content},"));
+                    } else {
+                        out.push_str(&format!("{key}:{content},"));
                     }
                 }
                 out.split_off(out.len() - 1).truncate(0);
                 out.push('}');
                 out
             }
-            AST::If { .. } => self.generate_if_expr(ast),
-            AST::Block(asts) => {
+            ExprKind::If {
+                elseblock: Some(_), ..
+            } => self.generate_if_value(expr),
+            ExprKind::If { .. } => self.generate_if_expr(expr),
+            ExprKind::Block(exprs) => {
                 let mut out = String::from("{\n");
                 self.increase_identation_level();
-                for ast in asts {
-                    let content = self.generate_expression_content(ast);
+                for expr in exprs {
+                    let content = self.generate_statement_content(expr);
+                    let content = format!("{}{content}", self.take_hoisted());
                     out.push_str(&self.indent(content));
                     out.push('\n');
                 }
@@ -54,22 +62,83 @@ impl BeatriceTranspiler {
                 out.push('}');
                 out
             }
-            AST::Loop(body) => self.generate_loop_content(body),
+            ExprKind::Loop { body, breaks } => self.generate_loop_content(body, *breaks),
+            ExprKind::Break => format!("break {};", self.loop_label()),
+            ExprKind::Panic(message) => format!(
+                "throw new Error({});",
+                self.generate_expression_content(message)
+            ),
+            ExprKind::Variant { .. } => self.generate_variant_content(expr),
+            ExprKind::Match { .. } => self.generate_match_value(expr),
+            ExprKind::Tuple(values) => {
+                let values = values
+                    .iter()
+                    .map(|value| self.generate_expression_content(value))
+                    .collect::<Vec<_>>();
+                format!("[{}]", values.join(","))
+            }
+            ExprKind::TupleIndex {
+                expr,
+                index,
+                optional,
+            } => {
+                let access = if *optional { "?." } else { "" };
+                format!(
+                    "{}{access}[{index}]",
+                    self.generate_expression_content(expr)
+                )
+            }
+            ExprKind::FieldAccess {
+                expr,
+                field,
+                optional,
+            } => {
+                let access = if *optional { "?." } else { "." };
+                format!("{}{access}{field}", self.generate_expression_content(expr))
+            }
+            //an option is the value it holds, or undefined when there's none
+            ExprKind::Some(value) => self.generate_expression_content(value),
+            ExprKind::None => "undefined".to_string(),
+            ExprKind::Ok(_) | ExprKind::Err(_) => self.generate_result_content(expr),
+            ExprKind::Try(value) => self.generate_try_content(value),
+            ExprKind::Cast { .. } => self.generate_cast_content(expr),
+            ExprKind::Import { path, names } => self.generate_import_content(path, names),
+            ExprKind::Export(decl) => match &decl.kind {
+                ExprKind::Display(display) => self.generate_display_helpers(display, "export "),
+                kind => {
+                    let content = self.generate_expression_content(decl);
+                    match kind {
+                        //structs and enums don't exist at runtime, so there is nothing to export
+                        _ if content.is_empty() => content,
+                        //a function with an escaped name is still exported with the one it was
+                        //given
+                        ExprKind::Function(f) if f.emitted != f.name => format!(
+                            "{content}{}",
+                            self.indent(format!("export {{ {} as {} }};", f.emitted, f.name))
+                        ),
+                        _ => format!("export {content}"),
+                    }
+                }
+            },
+        }
+    }
+    ///Generates an expression written as a statement, whose value isn't used. Only matches and
+    ///ifs are generated differently inside other expressions, where they give their value
+    ///through a temporary
+    pub(crate) fn generate_statement_content(&mut self, expr: &Expr) -> String {
+        match &expr.kind {
+            ExprKind::If { .. } => self.generate_if_expr(expr),
+            ExprKind::Match { .. } => self.generate_match(expr, &super::TranspileCondition::None),
+            _ => self.generate_expression_content(expr),
         }
     }
-    fn generate_function_content(&mut self, ast: &AST) -> String {
+    fn generate_function_content(&mut self, f: &Function) -> String {
         self.increase_identation_level();
-        let AST::Function {
-            name, params, body, ..
-        } = ast
-        else {
-            panic!("This is a bug. Expected to receive a function");
-        };
-        let mut content = format!("function {name}(");
+        let mut content = format!("function {}(", f.emitted);
         {
             let mut param_amount = 0;
-            for param in params {
-                content.push_str(&param.key);
+            for (idx, param) in f.params.iter().enumerate() {
+                content.push_str(&self.generate_param_target(idx, param));
                 content.push(',');
                 param_amount += 1;
             }
@@ -78,26 +147,28 @@ impl BeatriceTranspiler {
             }
         }
         content.push_str("){\n");
-        let body = self.generate_transpilation_content(body.body());
+        let defaults = self.generate_defaults_content(&f.params);
+        content.push_str(&defaults);
+        let body = self.generate_transpilation_content(&f.body);
         content.push_str(&body);
         self.decrease_identation_level();
         content.push_str(&self.indent("}\n"));
         content
     }
-    fn generate_transpilation_content(&mut self, ast: &VecDeque<AST>) -> String {
+    pub(crate) fn generate_transpilation_content(&mut self, program: &[Expr]) -> String {
         let mut content = String::new();
-        for ast in ast {
-            let exprcontent = self.generate_expression_content(ast);
+        for expr in program {
+            let exprcontent = self.generate_statement_content(expr);
+            let exprcontent = format!("{}{exprcontent}", self.take_hoisted());
             content.push_str(&self.indent(exprcontent));
             content.push('\n');
         }
         content
     }
-    pub fn transpile(&mut self, ast: &VecDeque<AST>) -> Result<usize, ()> {
-        let content = self.generate_transpilation_content(ast);
-        let mut f = std::fs::File::create(self.outdir()).unwrap();
+    pub fn transpile(&mut self, program: &[Expr]) -> std::io::Result<usize> {
+        let content = self.generate_transpilation_content(program);
+        let mut f = std::fs::File::create(self.outdir())?;
         println!("Writing into {:?}:\n\n{}", self.outdir(), content);
-        let bytes = f.write(content.as_bytes()).unwrap();
-        Ok(bytes)
+        f.write(content.as_bytes())
     }
 }
@@ -1,57 +1,155 @@
-use crate::{parser::AST, transpiler::transpiler::BeatriceTranspiler};
+use crate::transpiler::{
+    hir::{Expr, ExprKind, Pattern, VariantPattern},
+    transpiler::BeatriceTranspiler,
+};
 
 impl BeatriceTranspiler {
-    pub(crate) fn generate_var_decl_content(&mut self, ast: &AST) -> String {
-        let AST::VarDecl {
-            varname,
+    ///Generates the JS destructuring target of an irrefutable pattern, such as {x, y: py} for
+    ///Point { x, y: py }. Wildcards inside arrays are left as holes and struct fields matched by
+    ///a wildcard are omitted
+    pub(crate) fn generate_pattern_target(&self, pattern: &Pattern) -> String {
+        let fields_target = |fields: &[(String, Pattern)]| {
+            let fields = fields
+                .iter()
+                .filter(|(_, pattern)| !matches!(pattern, Pattern::Wildcard))
+                .map(|(key, pattern)| match pattern {
+                    Pattern::Binding { name, .. } if name == key => key.clone(),
+                    pattern => format!("{key}: {}", self.generate_pattern_target(pattern)),
+                })
+                .collect::<Vec<_>>();
+            format!("{{{}}}", fields.join(", "))
+        };
+        match pattern {
+            Pattern::Wildcard => String::new(),
+            Pattern::Binding { name, .. } => name.clone(),
+            Pattern::Struct(fields)
+            | Pattern::Variant {
+                fields: VariantPattern::Struct(fields),
+                ..
+            } => fields_target(fields),
+            Pattern::Variant {
+                fields: VariantPattern::Tuple(patterns),
+                ..
+            } => {
+                let fields = patterns
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, pattern)| !matches!(pattern, Pattern::Wildcard))
+                    .map(|(idx, pattern)| {
+                        format!("{idx}: {}", self.generate_pattern_target(pattern))
+                    })
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", fields.join(", "))
+            }
+            Pattern::Variant {
+                fields: VariantPattern::Unit,
+                ..
+            } => "{}".to_string(),
+            Pattern::Tuple(patterns) | Pattern::Array(patterns) => {
+                let items = patterns
+                    .iter()
+                    .map(|pattern| self.generate_pattern_target(pattern))
+                    .collect::<Vec<_>>();
+                format!("[{}]", items.join(", "))
+            }
+            Pattern::Bool(_)
+            | Pattern::Int(_)
+            | Pattern::Float(_)
+            | Pattern::Range { .. }
+            | Pattern::Some(_)
+            | Pattern::None
+            | Pattern::Ok(_)
+            | Pattern::Err(_) => {
+                panic!("This is a bug. Refutable patterns can't be destructured")
+            }
+        }
+    }
+    pub(crate) fn generate_var_decl_content(&mut self, expr: &Expr) -> String {
+        let ExprKind::Let {
+            pattern,
             mutable,
             body,
-        } = ast
+        } = &expr.kind
         else {
             unreachable!();
         };
-        if let AST::StructExpr { .. } = **body {
-            let content = self.generate_expression_content(body);
-            if !*mutable {
-                format!("const {varname} = Object.seal({content})")
+        let decl = if *mutable { "let" } else { "const" };
+        //the value of blocks is assigned to a single variable, so the ones assigned to a
+        //destructuring pattern go through a temporary one
+        let assign_block = |this: &mut Self, varname: String| {
+            let mut out = format!("let {varname};\n");
+            let condition = super::TranspileCondition::Assign(varname.clone());
+            if let ExprKind::Match { .. } = body.kind {
+                let content = this.generate_match(body, &condition);
+                out.push_str(&this.indent(content));
             } else {
-                format!("const {varname} = {content}")
+                out.push_str(&this.generate_if_expr_assign(body, &condition));
             }
-        } else if let AST::If {
+            match pattern {
+                Pattern::Binding { .. } | Pattern::Wildcard => {}
+                pattern => {
+                    let target = this.generate_pattern_target(pattern);
+                    out.push('\n');
+                    out.push_str(&this.indent(format!("{decl} {target} = {varname};")));
+                }
+            }
+            out
+        };
+        let block_var = |this: &mut Self| match pattern {
+            Pattern::Binding { name, .. } => name.clone(),
+            _ => this.temporary("let"),
+        };
+        if let ExprKind::StructLit { .. } = body.kind {
+            let content = self.generate_expression_content(body);
+            match pattern {
+                Pattern::Wildcard => content,
+                Pattern::Binding { name, .. } if !*mutable => {
+                    format!("const {name} = Object.seal({content})")
+                }
+                pattern => format!(
+                    "const {} = {content}",
+                    self.generate_pattern_target(pattern)
+                ),
+            }
+        } else if let ExprKind::If {
             elseblock,
             block,
-            expr,
-        } = &**body
+            cond,
+        } = &body.kind
         {
-            if let Some(ast) = elseblock {
-                if !(block.is_blockexpr() || ast.is_blockexpr()) {
-                    let cond_content = self.generate_expression_content(expr);
+            if let Some(elseblock) = elseblock {
+                if !(block.is_block() || elseblock.is_block()) {
+                    let cond_content = self.generate_expression_content(cond);
                     let ifcontent = self.generate_expression_content(block);
-                    let elsecontent = self.generate_expression_content(ast);
-                    format!(
-                        "{} {varname} = {} ? {} : {}",
-                        if *mutable { "let" } else { "const" },
-                        cond_content,
-                        ifcontent,
-                        elsecontent
-                    )
+                    let elsecontent = self.generate_expression_content(elseblock);
+                    let content = format!("{} ? {} : {}", cond_content, ifcontent, elsecontent);
+                    match pattern {
+                        Pattern::Wildcard => content,
+                        pattern => {
+                            format!(
+                                "{decl} {} = {content}",
+                                self.generate_pattern_target(pattern)
+                            )
+                        }
+                    }
                 } else {
-                    let mut out = format!("let {varname};\n");
-                    out.push_str(&self.generate_if_expr_assign(
-                        body,
-                        &super::TranspileCondition::Assign(varname.clone()),
-                    ));
-                    out
+                    let varname = block_var(self);
+                    assign_block(self, varname)
                 }
             } else {
                 unreachable!();
             }
+        } else if let ExprKind::Match { .. } = body.kind {
+            let varname = block_var(self);
+            assign_block(self, varname)
         } else {
             let content = self.generate_expression_content(body);
-            if *mutable {
-                format!("let {varname} = {content};")
-            } else {
-                format!("const {varname} = {content};")
+            match pattern {
+                Pattern::Wildcard => format!("{content};"),
+                pattern => format!(
+                    "{decl} {} = {content};",
+                    self.generate_pattern_target(pattern)
+                ),
             }
         }
     }
@@ -1,16 +1,75 @@
 use std::{
-    collections::VecDeque,
+    cell::Cell,
+    collections::{HashMap, VecDeque},
     fmt::Display,
     path::{Path, PathBuf},
+    rc::Rc,
 };
 
-use super::{TypeError, scope::Scope};
-use crate::parser::AST;
+use super::{
+    BeatriceType, Diagnostic, TypeError,
+    checkings::arguments::{ArgSource, Callee},
+    hir::Expr,
+    resolver::{Resolution, Resolver, SymbolId},
+    scope::Scope,
+    transpilation::names,
+};
+use crate::parser::{AST, Program, Span};
+
+///A node of the program being checked, by its address. The program is borrowed from the start of
+///its checking until it's lowered, so its nodes don't move and no two of them share an id. The
+///tables keyed by them are emptied before checking another program
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct NodeId(usize);
+
+impl NodeId {
+    pub(crate) fn of(ast: &AST) -> Self {
+        Self(ast as *const AST as usize)
+    }
+}
 
 pub struct BeatriceTranspiler {
     scopes: VecDeque<Scope>,
     target: PathBuf,
     indent_level: usize,
+    ///Amount of matches that needed a name for their value or label
+    match_count: usize,
+    ///The exports of the modules this one can import, by the path its imports use for them
+    modules: HashMap<String, Rc<Scope>>,
+    ///Statements that must run before the one being generated, such as the checks of its `?`s
+    hoisted: Vec<String>,
+    ///The helpers formatting the interpolated expressions that need one
+    formatters: HashMap<NodeId, String>,
+    ///The operations, casts and literals whose js number must be brought back to the values of
+    ///their numeric type, with that type. Such as the divisions of ints, which are truncated, or
+    ///the sums of u8s, which wrap
+    numeric: HashMap<NodeId, BeatriceType>,
+    ///Where each call takes the arguments of its parameters from
+    call_args: HashMap<NodeId, Vec<ArgSource>>,
+    ///Whether int, i64 and u64 are emitted as js bigints, so they are exact past 2^53
+    bigint: bool,
+    ///The casts between bigints and js numbers, with the types they convert from and to
+    bigint_casts: HashMap<NodeId, (BeatriceType, BeatriceType)>,
+    ///The symbol of every name of the program being checked
+    resolution: Resolution,
+    ///The generics and parameters of the global functions, by their symbol
+    callees: HashMap<SymbolId, Callee>,
+    ///The types of the variables checked so far, by their symbol
+    symbol_types: HashMap<SymbolId, BeatriceType>,
+    ///The types of the expressions checked so far
+    types: HashMap<NodeId, BeatriceType>,
+    ///The errors found so far in the program being checked
+    errors: Vec<Diagnostic>,
+    ///Where the first expression the checker left without a type was found while lowering
+    untyped: Cell<Option<Span>>,
+    ///Where the statement being checked starts
+    span: Span,
+    ///Whether each loop being checked is left by a break, the innermost last
+    loops: Vec<bool>,
+    ///The labels of the loops being generated that are left by a break, the innermost last
+    loop_labels: Vec<String>,
+    ///The id of the next struct declared
+    next_struct_id: usize,
 }
 
 ///The transpiler of Beatrice source code.
@@ -23,6 +82,24 @@ impl BeatriceTranspiler {
             scopes: VecDeque::from(vec![Scope::new()]),
             target: target.into(),
             indent_level: 0,
+            match_count: 0,
+            modules: HashMap::new(),
+            hoisted: Vec::new(),
+            formatters: HashMap::new(),
+            numeric: HashMap::new(),
+            call_args: HashMap::new(),
+            bigint: false,
+            bigint_casts: HashMap::new(),
+            resolution: Resolution::default(),
+            callees: HashMap::new(),
+            symbol_types: HashMap::new(),
+            types: HashMap::new(),
+            errors: Vec::new(),
+            untyped: Cell::new(None),
+            span: Span::default(),
+            loops: Vec::new(),
+            loop_labels: Vec::new(),
+            next_struct_id: 0,
         }
     }
 
@@ -39,6 +116,165 @@ impl BeatriceTranspiler {
     pub fn decrease_identation_level(&mut self) {
         self.indent_level -= 4;
     }
+    ///Returns a name never returned before for a temporary or label of the given kind, such as
+    ///the values of matches and `?`s and the labels of loops
+    pub(crate) fn temporary(&mut self, kind: &str) -> String {
+        self.match_count += 1;
+        names::temporary(kind, self.match_count - 1)
+    }
+    pub(crate) fn define_formatter(&mut self, expr: &AST, helper: String) {
+        self.formatters.insert(NodeId::of(expr), helper);
+    }
+    ///The helper formatting an interpolated expression, if it needs one
+    pub(crate) fn formatter_of(&self, expr: &AST) -> Option<&String> {
+        self.formatters.get(&NodeId::of(expr))
+    }
+    pub(crate) fn define_numeric(&mut self, expr: &AST, kind: BeatriceType) {
+        self.numeric.insert(NodeId::of(expr), kind);
+    }
+    ///The numeric type an expression must be emitted to keep the semantics of, if the js number
+    ///it gives could be another value
+    pub(crate) fn numeric_of(&self, expr: &AST) -> Option<&BeatriceType> {
+        self.numeric.get(&NodeId::of(expr))
+    }
+    ///Makes int, i64 and u64 be emitted as js bigints, with the 64 bit semantics they have in the
+    ///checker. Conversions between them and the other numeric types must be written with `as`
+    pub fn set_bigint(&mut self, bigint: bool) {
+        self.bigint = bigint;
+    }
+    pub(crate) fn bigint_mode(&self) -> bool {
+        self.bigint
+    }
+    ///Makes the structs declared from now on take their ids from id. The modules of a program
+    ///are checked by different transpilers, each starting where the one before it ended, so
+    ///their structs never have the same id
+    pub fn set_next_struct_id(&mut self, id: usize) {
+        self.next_struct_id = id;
+    }
+    pub fn next_struct_id(&self) -> usize {
+        self.next_struct_id
+    }
+    ///An id never given to another struct
+    pub(crate) fn new_struct_id(&mut self) -> usize {
+        self.next_struct_id += 1;
+        self.next_struct_id - 1
+    }
+    ///Whether values of kind are emitted as js bigints
+    pub(crate) fn is_bigint(&self, kind: &BeatriceType) -> bool {
+        self.bigint && matches!(kind, BeatriceType::Int | BeatriceType::U64)
+    }
+    pub(crate) fn define_bigint_cast(&mut self, cast: &AST, from: BeatriceType, to: BeatriceType) {
+        self.bigint_casts.insert(NodeId::of(cast), (from, to));
+    }
+    ///The types a cast between a bigint and a js number converts from and to
+    pub(crate) fn bigint_cast_of(&self, cast: &AST) -> Option<&(BeatriceType, BeatriceType)> {
+        self.bigint_casts.get(&NodeId::of(cast))
+    }
+    pub(crate) fn define_call_args(&mut self, call: &AST, sources: Vec<ArgSource>) {
+        self.call_args.insert(NodeId::of(call), sources);
+    }
+    ///Where a call takes the arguments of its parameters from, if it was checked
+    pub(crate) fn call_args_of(&self, call: &AST) -> Option<&Vec<ArgSource>> {
+        self.call_args.get(&NodeId::of(call))
+    }
+    pub(crate) fn resolution(&self) -> &Resolution {
+        &self.resolution
+    }
+    pub(crate) fn set_callees(&mut self, callees: HashMap<SymbolId, Callee>) {
+        self.callees = callees;
+    }
+    ///The declared function a call calls, if it calls one and not a variable holding a function
+    pub(crate) fn callee(&self, call: &AST) -> Option<&Callee> {
+        self.resolution()
+            .use_of(call)
+            .and_then(|id| self.callees.get(&id))
+    }
+    ///Sets the type of the variable of a symbol, returning the one it had
+    pub(crate) fn define_symbol_type(
+        &mut self,
+        id: SymbolId,
+        kind: BeatriceType,
+    ) -> Option<BeatriceType> {
+        self.symbol_types.insert(id, kind)
+    }
+    ///The type of the variable of a symbol, if it was checked
+    pub(crate) fn symbol_type(&self, id: SymbolId) -> Option<&BeatriceType> {
+        self.symbol_types.get(&id)
+    }
+    pub(crate) fn define_type(&mut self, expr: &AST, kind: BeatriceType) {
+        self.types.insert(NodeId::of(expr), kind);
+    }
+    ///The type an expression was given while checking it, if it was checked
+    pub(crate) fn type_of(&self, expr: &AST) -> Option<&BeatriceType> {
+        self.types.get(&NodeId::of(expr))
+    }
+    ///Reports an error of the statement being checked. The errors that know where they are keep
+    ///their own span
+    pub(crate) fn report(&mut self, error: TypeError) {
+        let span = match &error {
+            TypeError::ArityMismatch { span, .. } => *span,
+            _ => self.span,
+        };
+        self.errors.push(Diagnostic { span, error });
+    }
+    pub(crate) fn span(&self) -> Span {
+        self.span
+    }
+    ///Sets where the statement being checked starts, returning where the previous one did
+    pub(crate) fn set_span(&mut self, span: Span) -> Span {
+        std::mem::replace(&mut self.span, span)
+    }
+    ///Starts checking the body of a loop
+    pub(crate) fn enter_loop(&mut self) {
+        self.loops.push(false);
+    }
+    ///Stops checking the body of the innermost loop, returning whether a break leaves it
+    pub(crate) fn exit_loop(&mut self) -> bool {
+        self.loops
+            .pop()
+            .expect("This is a bug. Exited a loop that wasn't entered")
+    }
+    ///Marks the innermost loop as left by a break. False when there's no loop to leave
+    pub(crate) fn break_loop(&mut self) -> bool {
+        self.loops.last_mut().map(|breaks| *breaks = true).is_some()
+    }
+    ///Starts generating a loop left by a break, returning the label the break leaves it through
+    pub(crate) fn enter_loop_label(&mut self) -> String {
+        let label = self.temporary("loop");
+        self.loop_labels.push(label.clone());
+        label
+    }
+    pub(crate) fn exit_loop_label(&mut self) {
+        self.loop_labels.pop();
+    }
+    ///The label of the innermost loop being generated
+    pub(crate) fn loop_label(&self) -> &str {
+        self.loop_labels
+            .last()
+            .expect("This is a bug. A break is only checked inside a loop")
+    }
+    ///Adds a statement to run before the one being generated
+    pub(crate) fn hoist(&mut self, statement: String) {
+        self.hoisted.push(statement);
+    }
+    ///Runs f, giving apart the statements hoisted while it runs. The ones hoisted before are kept
+    pub(crate) fn with_own_hoisted<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> (T, String) {
+        let outer = std::mem::take(&mut self.hoisted);
+        let out = f(self);
+        let own = self.take_hoisted();
+        self.hoisted = outer;
+        (out, own)
+    }
+    ///Takes the statements hoisted while generating the current one, to be written before it
+    pub(crate) fn take_hoisted(&mut self) -> String {
+        let mut out = String::new();
+        for statement in std::mem::take(&mut self.hoisted) {
+            out.push_str(&statement);
+            out.push('\n');
+            out.push_str(&self.indent(""));
+        }
+        out
+    }
     pub fn outdir(&self) -> &Path {
         &self.target
     }
@@ -69,11 +305,118 @@ impl BeatriceTranspiler {
         self.scopes.back_mut().unwrap()
     }
 
-    pub fn start_transpilation(&mut self, ast: &VecDeque<AST>) -> Result<(), TypeError> {
+    ///Makes the exports of a module available to the imports that name it with path
+    pub fn define_module<T: Into<String>>(&mut self, path: T, exports: Rc<Scope>) {
+        self.modules.insert(path.into(), exports);
+    }
+
+    ///Defines in the global scope the names imported from the module at path
+    pub(crate) fn import_names(&mut self, path: &str, names: &[String]) -> Result<(), TypeError> {
+        let module = self
+            .modules
+            .get(path)
+            .ok_or(TypeError::UnresolvedModule(path.to_string()))?;
+        let global = self.scopes.front_mut().unwrap();
+        for name in names {
+            if !global.import(module, name) {
+                return Err(TypeError::NotExported {
+                    module: path.to_string(),
+                    name: name.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    ///Collects the definitions a checked program exports, so other modules can import them
+    pub fn exports(&self, ast: &VecDeque<AST>) -> Scope {
+        let mut exports = Scope::new();
+        let global = self.scopes.front().unwrap();
         for ast in ast {
-            self.generate_metadata(ast)?;
+            let AST::Export(decl) = ast else {
+                continue;
+            };
+            let (AST::Function { name, .. } | AST::Struct { name, .. } | AST::Enum { name, .. }) =
+                &**decl
+            else {
+                panic!("This is a bug. Only declarations can be exported");
+            };
+            exports.import(global, name);
+        }
+        exports
+    }
+
+    ///Checks every declaration of a program. Every global is declared before any body is checked,
+    ///so it can be used above the line declaring it, and every name is resolved before the types
+    ///of the variables are checked. Every error is collected, sorted by where it was found
+    fn check_program(&mut self, program: &Program) -> Result<(), Vec<Diagnostic>> {
+        self.forget_nodes();
+        let duplicates = self.declare_globals(program);
+        let (resolution, errors) = Resolver::new(self.current_scope().functions()).resolve(program);
+        self.resolution = resolution;
+        self.define_callees();
+        self.errors.extend(errors);
+        //the resolver reported the duplicated functions, which would be checked against the
+        //signature of the first one
+        for (idx, (ast, span)) in program.statements().enumerate() {
+            if !duplicates.contains(&idx) {
+                self.check_statement(ast, Some(span));
+            }
+        }
+        let mut errors = std::mem::take(&mut self.errors);
+        if errors.is_empty() {
+            return Ok(());
         }
-        self.transpile(ast).unwrap();
+        errors.sort_by_key(|diagnostic| (diagnostic.span.line, diagnostic.span.column));
+        Err(errors)
+    }
+
+    pub fn start_transpilation(&mut self, program: &Program) -> Result<(), Vec<Diagnostic>> {
+        let program = self.check(program)?;
+        self.transpile(&program).unwrap();
         Ok(())
     }
+
+    ///Records that the statement being lowered has an expression the checker left without a type
+    pub(crate) fn record_untyped(&self) {
+        if self.untyped.get().is_none() {
+            self.untyped.set(Some(self.span));
+        }
+    }
+
+    ///Empties the tables of what was found about the nodes and symbols of the program checked
+    ///before, so none of them is taken for one of the next program
+    fn forget_nodes(&mut self) {
+        self.formatters.clear();
+        self.numeric.clear();
+        self.call_args.clear();
+        self.bigint_casts.clear();
+        self.symbol_types.clear();
+        self.types.clear();
+    }
+
+    ///Checks the program and lowers it into the HIR, which js is generated from. An expression
+    ///the checker left without a type is reported instead of being lowered with a wrong one
+    pub fn check(&mut self, program: &Program) -> Result<Vec<Expr>, Vec<Diagnostic>> {
+        self.check_program(program)?;
+        let lowered = self.lower_program(program);
+        if let Some(span) = self.untyped.take() {
+            return Err(vec![Diagnostic {
+                span,
+                error: TypeError::UntypedExpression,
+            }]);
+        }
+        Ok(lowered)
+    }
+
+    ///Generates the javascript of a program lowered by this transpiler
+    pub fn emit(&mut self, program: &[Expr]) -> String {
+        self.generate_transpilation_content(program)
+    }
+
+    ///Checks the program and returns the javascript generated for it without writing it anywhere
+    pub fn generate(&mut self, program: &Program) -> Result<String, Vec<Diagnostic>> {
+        let program = self.check(program)?;
+        Ok(self.emit(&program))
+    }
 }
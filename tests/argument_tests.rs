@@ -0,0 +1,152 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, AstErrorKind, Parser},
+    transpiler::{BeatriceType, TypeError},
+};
+use common::transpile;
+
+const F: &str = "function f(a: int, b: float = 2.5, c: int? = a + 1): float { b }
+";
+
+#[test]
+fn test_parse_defaults_and_named_args() {
+    let program = Parser::from_content(&format!("{F}function g(): float {{ f(1, c: 3) }}"))
+        .gen_ast()
+        .unwrap();
+    let AST::Function { params, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    assert!(params[0].default.is_none());
+    assert!(matches!(params[1].default, Some(AST::Float(_))));
+    assert!(matches!(params[2].default, Some(AST::BinExpr(..))));
+    let AST::Function { body, .. } = &program.body()[1] else {
+        panic!("Expected a function");
+    };
+    let AST::Return(Some(ret)) = &body.body()[0] else {
+        panic!("Expected the returned expression");
+    };
+    let AST::FunctionCall { args, named, .. } = &**ret else {
+        panic!("Expected a call, got {ret:?}");
+    };
+    assert_eq!(args.len(), 1);
+    assert_eq!(named.len(), 1);
+    assert_eq!(named[0].key, "c");
+    assert!(matches!(named[0].value, AST::Int(3)));
+
+    let errors = Parser::from_content("function g(): int { f(a: 1, 2) }")
+        .gen_ast()
+        .unwrap_err();
+    assert!(matches!(errors[0].kind, AstErrorKind::PositionalAfterNamed));
+}
+
+#[test]
+fn test_defaults_emission() {
+    let out = transpile(&format!(
+        "{F}function pair((x, y): (int, int) = (1, 2)): int {{ x + y }}
+        function g(): float {{
+            let m = f(1);
+            let n = f(1, c: None);
+            let o = f(c: 3, a: 2, b: 1.5);
+            f(pair(), 0.5)
+        }}"
+    ))
+    .unwrap();
+    //a None given to an optional parameter is not the default value
+    assert!(out.contains(
+        "    if(arguments.length <= 1 || b === Symbol.for(\"beatrice.default\")) b = 2.5;
+    if(arguments.length <= 2 || c === Symbol.for(\"beatrice.default\")) c = a + 1;"
+    ));
+    assert!(out.contains(
+        "function pair(_$0){
+    if(arguments.length <= 0 || _$0 === Symbol.for(\"beatrice.default\")) _$0 = [1,2];
+    let [x, y] = _$0;"
+    ));
+    assert!(out.contains("const m = f(1);"));
+    assert!(out.contains("const n = f(1,Symbol.for(\"beatrice.default\"),undefined);"));
+    assert!(out.contains("const o = f(2,1.5,3);"));
+    assert!(out.contains("return f(pair(),0.5);"));
+}
+
+#[test]
+fn test_named_args_checks() {
+    assert!(matches!(
+        transpile(&format!("{F}function g(): float {{ f(b: 1.5) }}")),
+        Err(TypeError::MissingArgument { function, param }) if function == "f" && param == "a"
+    ));
+    assert!(matches!(
+        transpile(&format!("{F}function g(): float {{ f(1, a: 2) }}")),
+        Err(TypeError::DuplicateArgument { argument, .. }) if argument == "a"
+    ));
+    assert!(matches!(
+        transpile(&format!("{F}function g(): float {{ f(1, b: 1.5, b: 2.5) }}")),
+        Err(TypeError::DuplicateArgument { argument, .. }) if argument == "b"
+    ));
+    assert!(matches!(
+        transpile(&format!("{F}function g(): float {{ f(1, d: 2) }}")),
+        Err(TypeError::UnknownArgument { argument, .. }) if argument == "d"
+    ));
+    assert!(matches!(
+        transpile(&format!("{F}function g(): float {{ f(1, c: 1.5) }}")),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Option(_),
+            received: BeatriceType::Float,
+        })
+    ));
+}
+
+#[test]
+fn test_default_values_checks() {
+    assert!(matches!(
+        transpile("function f(a: int = 1.5): int { a }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Float,
+        })
+    ));
+    //a default can only use the parameters before it
+    assert!(matches!(
+        transpile("function f(a: int = b, b: int = 1): int { a }"),
+        Err(TypeError::NotRecognizedVar(name)) if name == "b"
+    ));
+    //the default of a float parameter can be an int literal
+    assert!(transpile("function f(a: float = 1): float { a }").is_ok());
+    //generic functions infer their generics only from the arguments given
+    let out = transpile(
+        "function id<T>(x: T, times: int = 1): T { x }
+        function g(): int { id(times: 2, x: 5) + id(3) }",
+    )
+    .unwrap();
+    assert!(out.contains("return id(5,2) + id(3);"));
+}
+
+#[test]
+fn test_named_args_evaluation_order() {
+    //the arguments are evaluated in the order they are written, and passed in the order of the
+    //parameters
+    let out = transpile(
+        "function one(): int = 1;
+        function two(): int = 2;
+        function sub(a: int, b: int, c: int = 0): int = a - b - c;
+        function g(x: int): int {
+            let m = sub(b: one(), a: two());
+            let n = sub(c: x, b: one(), a: 3);
+            sub(one(), c: two(), b: x)
+        }",
+    )
+    .unwrap();
+    assert!(
+        out.contains("    let $arg0;\n    let $arg1;\n    const m = ($arg0 = one(), $arg1 = two(), sub($arg1,$arg0));"),
+        "{out}"
+    );
+    //the arguments without side effects are passed as they are
+    assert!(
+        out.contains("    let $arg2;\n    const n = ($arg2 = one(), sub(3,$arg2,x));"),
+        "{out}"
+    );
+    //the positional one too, so it's still evaluated first
+    assert!(
+        out.contains("    return ($arg3 = one(), $arg4 = two(), sub($arg3,x,$arg4));"),
+        "{out}"
+    );
+}
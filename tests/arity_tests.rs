@@ -0,0 +1,119 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, Parser, Span},
+    transpiler::TypeError,
+};
+use common::transpile;
+
+const F: &str = "function f(a: int, b: int): int { a }
+";
+
+#[test]
+fn test_call_span() {
+    let program = Parser::from_content("function g(): int {\n    f(1)\n}")
+        .gen_ast()
+        .unwrap();
+    let AST::Function { body, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    let AST::Return(Some(ret)) = &body.body()[0] else {
+        panic!("Expected the returned expression");
+    };
+    assert!(matches!(
+        &**ret,
+        AST::FunctionCall {
+            span: Span { line: 1, .. },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_direct_call_arity() {
+    for (call, expected, received) in [("f(1)", 2, 1), ("f()", 2, 0), ("f(1, 2, 3)", 2, 3)] {
+        let result = transpile(&format!("{F}function g(): int {{ {call} }}"));
+        assert!(
+            matches!(
+                result,
+                Err(TypeError::ArityMismatch { expected: e, received: r, .. })
+                    if e == expected && r == received
+            ),
+            "{call}: {result:?}"
+        );
+    }
+    //calls nested in other expressions are checked too
+    assert!(matches!(
+        transpile(&format!("{F}function g(): int {{ let x = 1 + f(1); x }}")),
+        Err(TypeError::ArityMismatch {
+            expected: 2,
+            received: 1,
+            ..
+        })
+    ));
+    assert!(matches!(
+        transpile(&format!(
+            "{F}function id<T>(x: T): T {{ x }} function g(): int {{ id(1, 2) }}"
+        )),
+        Err(TypeError::ArityMismatch {
+            expected: 1,
+            received: 2,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_defaults_arity() {
+    let defaults = "function k(a: int = 1, b: int, c: int = 3): int { a }\n";
+    //b has no default, so it must be reached
+    assert!(matches!(
+        transpile(&format!("{defaults}function g(): int {{ k(1) }}")),
+        Err(TypeError::ArityMismatch {
+            expected: 2,
+            received: 1,
+            ..
+        })
+    ));
+    assert!(matches!(
+        transpile(&format!("{defaults}function g(): int {{ k(1, 2, 3, 4) }}")),
+        Err(TypeError::ArityMismatch {
+            expected: 3,
+            received: 4,
+            ..
+        })
+    ));
+    assert!(
+        transpile(&format!(
+            "{defaults}function g(): int {{ k(1, 2) + k(b: 2) }}"
+        ))
+        .is_ok()
+    );
+    //with named arguments the missing parameter can be named
+    assert!(matches!(
+        transpile(&format!("{defaults}function g(): int {{ k(c: 2) }}")),
+        Err(TypeError::MissingArgument { param, .. }) if param == "b"
+    ));
+}
+
+#[test]
+fn test_function_value_arity() {
+    let apply = "function double(x: int): int { x * 2 }
+    function apply(g: (int): int, x: int): int { g(x) }
+    ";
+    let out = transpile(&format!("{apply}function h(): int {{ apply(double, 2) }}")).unwrap();
+    assert!(out.contains("return g(x);"));
+    assert!(out.contains("return apply(double,2);"));
+    assert!(matches!(
+        transpile("function h(g: (int): int): int { g(1, 2) }"),
+        Err(TypeError::ArityMismatch {
+            expected: 1,
+            received: 2,
+            span: Span { line: 0, .. },
+        })
+    ));
+    assert!(matches!(
+        transpile("function h(g: (int): int): int { g(x: 1) }"),
+        Err(TypeError::UnknownArgument { argument, .. }) if argument == "x"
+    ));
+}
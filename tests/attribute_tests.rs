@@ -0,0 +1,114 @@
+use beatrice::{
+    parser::{AST, AstErrorKind, AttributeTarget, Parser},
+    transpiler::transpiler::BeatriceTranspiler,
+};
+
+#[test]
+fn test_parse_attributes() {
+    let program = Parser::from_content(
+        "@Memorized
+        struct Color {
+            @Deprecated(1) r: int;
+            g: int;
+        }
+        @Inline @Deprecated(2, 3)
+        function f(x: int): int {
+            @Inline g(x);
+            x
+        }
+        @Deprecated
+        enum E { A { @Deprecated v: int }, B }",
+    )
+    .gen_ast()
+    .unwrap();
+    let AST::Struct {
+        attributes, fields, ..
+    } = &program.body()[0]
+    else {
+        panic!("Expected a struct");
+    };
+    assert_eq!(attributes[0].name, "Memorized");
+    assert!(attributes[0].args.is_empty());
+    assert!(fields[0].attributes[0].is("Deprecated"));
+    assert!(matches!(fields[0].attributes[0].args[0], AST::Int(1)));
+    assert!(fields[1].attributes.is_empty());
+
+    let AST::Function {
+        attributes, body, ..
+    } = &program.body()[1]
+    else {
+        panic!("Expected a function");
+    };
+    let names = attributes
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Inline", "Deprecated"]);
+    assert_eq!(attributes[1].args.len(), 2);
+    let AST::Attributed { attributes, ast } = &body.body()[0] else {
+        panic!("Expected an attributed statement");
+    };
+    assert!(attributes[0].is("Inline"));
+    assert!(matches!(**ast, AST::FunctionCall { .. }));
+
+    let AST::Enum { attributes, .. } = &program.body()[2] else {
+        panic!("Expected an enum");
+    };
+    assert!(attributes[0].is("Deprecated"));
+}
+
+#[test]
+fn test_attribute_diagnostics() {
+    let errs = Parser::from_content("@Fast function f(): int = 1;")
+        .gen_ast()
+        .unwrap_err();
+    assert!(matches!(&errs[0].kind, AstErrorKind::UnknownAttribute(name) if name == "Fast"));
+    assert_eq!((errs[0].line, errs[0].column), (0, 0));
+
+    let errs = Parser::from_content(
+        "function f(): int {
+            @Memorized let x = 1;
+            x
+        }",
+    )
+    .gen_ast()
+    .unwrap_err();
+    assert!(matches!(
+        &errs[0].kind,
+        AstErrorKind::MisplacedAttribute {
+            name,
+            target: AttributeTarget::Statement,
+        } if name == "Memorized"
+    ));
+    assert_eq!(errs[0].line, 1);
+
+    let errs = Parser::from_content("struct S { @Test a: int; }")
+        .gen_ast()
+        .unwrap_err();
+    assert!(matches!(
+        &errs[0].kind,
+        AstErrorKind::MisplacedAttribute {
+            target: AttributeTarget::Field,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_attributes_are_transparent_to_transpilation() {
+    let program = Parser::from_content(
+        "@Inline
+        function id(x: int): int = x;
+        function f(): int {
+            @Inline let y = id(2);
+            y
+        }",
+    )
+    .gen_ast()
+    .unwrap();
+    let out = BeatriceTranspiler::new("unused.js")
+        .generate(&program)
+        .unwrap();
+    assert!(out.contains("function id(x){"), "{out}");
+    assert!(out.contains("const y = id(2);"), "{out}");
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use beatrice::{
+    parser::{AstErrorKind, Operator, Parser},
+    transpiler::{BeatriceType, Bound, TypeError, transpiler::BeatriceTranspiler},
+};
+use common::{generate_with, only_error};
+
+fn transpile(input: &str) -> Result<String, TypeError> {
+    let mut transpiler = BeatriceTranspiler::new("unused.js");
+    transpiler.set_bigint(true);
+    generate_with(transpiler, input).map_err(only_error)
+}
+
+#[test]
+fn test_bigint_literals() {
+    let out = transpile(
+        "function f(a: int, b: u8, c: u64?): int {
+            let big = 9007199254740993;
+            let small: u8 = 200;
+            let x: float = 1;
+            let d = b + 1;
+            let e: u64 = c ?? 5;
+            let ok = a > 1 && a != 3;
+            match a { 1 -> 10, 2..=5 -> 20, _ -> big }
+        }",
+    )
+    .unwrap();
+    //past 2^53, where js numbers aren't exact anymore
+    assert!(out.contains("const big = 9007199254740993n;"));
+    //literals given another type are numbers
+    assert!(out.contains("const small = 200;"));
+    assert!(out.contains("const x = 1;"));
+    assert!(out.contains("const d = b + 1 & 0xFF;"));
+    assert!(out.contains("const e = c ?? 5n;"));
+    assert!(out.contains("const ok = a > 1n && a != 3n;"));
+    //a bigint is equal to a number with its value only by ==
+    assert!(out.contains("if(a == 1){"));
+    assert!(out.contains("return 10n;"));
+    //numbers are kept as they are without the mode
+    let program = Parser::from_content("function f(): int = 5;")
+        .gen_ast()
+        .unwrap();
+    let out = BeatriceTranspiler::new("unused.js")
+        .generate(&program)
+        .unwrap();
+    assert!(out.contains("return 5;"));
+    //the literals of an u64 can be past the largest int
+    let out = transpile(
+        "function max(): u64 = 18446744073709551615;
+        function last(a: u64): bool = a == 0xFFFFFFFFFFFFFFFF;
+        function f(n: u64): int = match n { 0b11 -> 1, 9223372036854775808 -> 2, _ -> 3 };",
+    )
+    .unwrap();
+    assert!(out.contains("return 18446744073709551615n;"), "{out}");
+    assert!(out.contains("return a == 18446744073709551615n;"), "{out}");
+    assert!(out.contains("if(n == 3){"), "{out}");
+    assert!(matches!(
+        transpile("function f(): int = 9223372036854775808;"),
+        Err(TypeError::LiteralOutOfRange {
+            literal: 9223372036854775808,
+            kind: BeatriceType::Int,
+        })
+    ));
+    let errors = Parser::from_content("function f(): u64 = 18446744073709551616;")
+        .gen_ast()
+        .unwrap_err();
+    assert!(
+        matches!(&errors[0].kind, AstErrorKind::IntegerTooLarge(n) if n == "18446744073709551616")
+    );
+}
+
+#[test]
+fn test_bigint_arithmetic() {
+    let out = transpile(
+        "function add(a: int, b: int): int = a + b * 2;
+        function div(a: int, b: int): int = a / b;
+        function rem(a: int, b: int): int = a % b;
+        function shl(a: int, b: int): int = a << b;
+        function shr(a: int, b: int): int = a >> b;
+        function addu(a: u64, b: u64): u64 = a + b;
+        function mask(a: int, b: int): int = a & b | 1;",
+    )
+    .unwrap();
+    //ints wrap around 64 bits
+    assert!(out.contains("return BigInt.asIntN(64, a + BigInt.asIntN(64, b * 2n));"));
+    assert!(out.contains("return BigInt.asUintN(64, a + b);"));
+    //bigint divisions already truncate towards zero, and modulo has the sign of the dividend
+    assert!(out.contains("return BigInt.asIntN(64, a / b);"));
+    assert!(out.contains("return a % b;"));
+    //shifts take the amount modulo 64
+    assert!(out.contains("return BigInt.asIntN(64, a << (b & 63n));"));
+    assert!(out.contains("return a >> (b & 63n);"));
+    assert!(out.contains("return a & b | 1n;"));
+}
+
+#[test]
+fn test_bigint_casts() {
+    let out = transpile(
+        "function f(a: int, x: float, s: i8, b: u8, h: f32): float {
+            let c = a as float * 1.5;
+            let d = x as int;
+            let e = a as u8;
+            let g = s as u64;
+            let i = b as int;
+            let k = a as u64;
+            let l = a as f32;
+            let m = h as u64;
+            c
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("const c = Number(a) * 1.5;"));
+    assert!(out.contains("const d = BigInt.asIntN(64, BigInt(Math.trunc(x) % 2 ** 64 || 0));"));
+    //the bigint wraps before being a number, so no bit is lost
+    assert!(out.contains("const e = Number(BigInt.asUintN(8, a));"));
+    assert!(out.contains("const g = BigInt.asUintN(64, BigInt(s));"));
+    assert!(out.contains("const i = BigInt(b);"));
+    assert!(out.contains("const k = BigInt.asUintN(64, a);"));
+    assert!(out.contains("const l = Math.fround(Number(a));"));
+    assert!(out.contains("const m = BigInt.asUintN(64, BigInt(Math.trunc(h) % 2 ** 64 || 0));"));
+}
+
+#[test]
+fn test_bigint_mixing() {
+    assert!(matches!(
+        transpile("function f(a: int, x: float): float = a * x;"),
+        Err(TypeError::InvalidOperands {
+            operator: Operator::Star(false),
+            lhs: BeatriceType::Int,
+            rhs: BeatriceType::Float,
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(a: int): float { let x: float = a; x }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Float,
+            received: BeatriceType::Int,
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(a: u8): int = a;"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::U8,
+        })
+    ));
+    //generic functions work on js numbers, so they can't be given bigints
+    let scale = "function scale<T>(x: T): float where T extends Numeric = x as float * 1.5;\n";
+    assert!(matches!(
+        transpile(&format!("{scale}function f(a: int): float = scale(a);")),
+        Err(TypeError::UnsatisfiedBound {
+            bound: Bound::Numeric,
+            received: BeatriceType::Int,
+            ..
+        })
+    ));
+    assert!(transpile(&format!("{scale}function f(a: float): float = scale(a);")).is_ok());
+    //generics without bounds only move values around
+    assert!(transpile("function id<T>(x: T): T = x; function f(a: int): int = id(a);").is_ok());
+}
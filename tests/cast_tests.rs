@@ -0,0 +1,163 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, Operator, Parser, TypeAst},
+    transpiler::{BeatriceType, TypeError},
+};
+use common::transpile;
+
+#[test]
+fn test_parse_casts() {
+    let program =
+        Parser::from_content("function f(n: int): int { let a = n as float * 1.5 as int; a }")
+            .gen_ast()
+            .unwrap();
+    let AST::Function { body, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    let AST::VarDecl { body: value, .. } = &body.body()[0] else {
+        panic!("Expected a let");
+    };
+    //(n as float) * (1.5 as int)
+    let AST::BinExpr(lhs, rhs, Operator::Star(false)) = &**value else {
+        panic!("Expected a product, got {value:?}");
+    };
+    assert!(matches!(
+        &**lhs,
+        AST::Cast { expr, kindof: TypeAst::Primitive(_) } if matches!(**expr, AST::Identifier(_))
+    ));
+    assert!(matches!(&**rhs, AST::Cast { expr, .. } if matches!(**expr, AST::Float(_))));
+    //chained casts apply from the left
+    let program = Parser::from_content("function f(x: float): float = x as int as float;")
+        .gen_ast()
+        .unwrap();
+    let AST::Function { body, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    let AST::Return(Some(value)) = &body.body()[0] else {
+        panic!("Expected the returned expression");
+    };
+    assert!(matches!(
+        &**value,
+        AST::Cast { expr, .. } if matches!(**expr, AST::Cast { .. })
+    ));
+}
+
+#[test]
+fn test_casts_emission() {
+    let out = transpile(
+        "function f(n: int, y: float): float {
+            let a = n as float * 1.5;
+            let b = (y * 2.5) as int + 1;
+            let c = (n + 1) as float * y;
+            let d: int = y as int as int;
+            let e = n as int;
+            a
+        }",
+    )
+    .unwrap();
+    //ints are already js numbers, converting them does nothing
+    assert!(out.contains("const a = n * 1.5;"));
+    //floats drop their fractional part, towards zero, and wrap around 64 bits
+    assert!(out.contains(
+        "const b = Number(BigInt.asIntN(64, BigInt(Math.trunc(y * 2.5) % 2 ** 64 || 0))) + 1;"
+    ));
+    assert!(out.contains("const c = (n + 1) * y;"));
+    assert!(
+        out.contains("const d = Number(BigInt.asIntN(64, BigInt(Math.trunc(y) % 2 ** 64 || 0)));")
+    );
+    assert!(out.contains("const e = n;"));
+}
+
+#[test]
+fn test_casts_types() {
+    //a cast has the type it converts to, so ints and floats can be mixed through it
+    assert!(matches!(
+        transpile("function f(n: int): int = n as float;"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Float,
+        })
+    ));
+    let out = transpile(
+        "function scale<T>(x: T): float where T extends Numeric = x as float * 1.5;
+        function whole<T>(x: T): int where T extends Numeric = x as int;
+        function g(n: int): float = scale(n) + whole(2.5) as float;",
+    )
+    .unwrap();
+    assert!(out.contains("return x * 1.5;"));
+    //a generic numeric could be a float
+    assert!(
+        out.contains("return Number(BigInt.asIntN(64, BigInt(Math.trunc(x) % 2 ** 64 || 0)));")
+    );
+    let out = transpile("function f<T>(x: T): T where T extends Integer = 2.5 as T;").unwrap();
+    assert!(
+        out.contains("return Number(BigInt.asIntN(64, BigInt(Math.trunc(2.5) % 2 ** 64 || 0)));")
+    );
+}
+
+#[test]
+fn test_float_to_int_casts() {
+    //the remainder is exact, so a float past 2^63 wraps as an u64 does. It's NaN for NaN and the
+    //infinities, which become 0
+    let out = transpile(
+        "function f(zero: float): int {
+            let nan = (zero / zero) as int;
+            let inf = (1.0 / zero) as int;
+            let ninf = ((0.0 - 1.0) / zero) as int;
+            let big = 1000000000000000000000000000000.0 as int;
+            nan + inf + ninf + big
+        }",
+    )
+    .unwrap();
+    assert!(
+        out.contains(
+            "const nan = Number(BigInt.asIntN(64, BigInt(Math.trunc(zero / zero) % 2 ** 64 || 0)));"
+        ),
+        "{out}"
+    );
+    assert!(
+        out.contains(
+            "const inf = Number(BigInt.asIntN(64, BigInt(Math.trunc(1 / zero) % 2 ** 64 || 0)));"
+        ),
+        "{out}"
+    );
+    assert!(out.contains("const ninf = Number(BigInt.asIntN(64, BigInt(Math.trunc((0 - 1) / zero) % 2 ** 64 || 0)));"), "{out}");
+    assert!(
+        out.contains("const big = Number(BigInt.asIntN(64, BigInt(Math.trunc(1000000000000000000000000000000) % 2 ** 64 || 0)));"),
+        "{out}"
+    );
+}
+
+#[test]
+fn test_invalid_casts() {
+    assert!(matches!(
+        transpile("function f(b: bool): int = b as int;"),
+        Err(TypeError::InvalidCast {
+            from: BeatriceType::Bool,
+            to: BeatriceType::Int,
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(x: int): string = x as string;"),
+        Err(TypeError::InvalidCast {
+            from: BeatriceType::Int,
+            to: BeatriceType::String,
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(x: int?): float = x as float;"),
+        Err(TypeError::InvalidCast {
+            from: BeatriceType::Option(_),
+            ..
+        })
+    ));
+    //nothing tells if a generic only bound to be numeric is an int or a float
+    assert!(matches!(
+        transpile("function f<T>(x: float): T where T extends Numeric = x as T;"),
+        Err(TypeError::InvalidCast {
+            to: BeatriceType::TypeVar(_),
+            ..
+        })
+    ));
+}
@@ -0,0 +1,53 @@
+//The fixtures shared by the integration tests. Each test file is its own crate and only uses some
+//of them
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use beatrice::{
+    parser::Parser,
+    transpiler::{Diagnostic, TypeError, transpiler::BeatriceTranspiler},
+};
+
+///Checks a program with the transpiler and emits it, giving every diagnostic when it fails
+pub fn generate_with(
+    mut transpiler: BeatriceTranspiler,
+    input: &str,
+) -> Result<String, Vec<Diagnostic>> {
+    let program = Parser::from_content(input).gen_ast().unwrap();
+    transpiler.generate(&program)
+}
+
+pub fn generate(input: &str) -> Result<String, Vec<Diagnostic>> {
+    generate_with(BeatriceTranspiler::new("unused.js"), input)
+}
+
+///The diagnostics of a program, empty when it checks
+pub fn errors(input: &str) -> Vec<Diagnostic> {
+    generate(input).err().unwrap_or_default()
+}
+
+///The error of a program that fails with a single one. Programs with more errors are checked
+///against the whole list of their diagnostics instead
+pub fn only_error(errors: Vec<Diagnostic>) -> TypeError {
+    let [diagnostic] = <[Diagnostic; 1]>::try_from(errors)
+        .unwrap_or_else(|errors| panic!("Expected a single error, got {errors:?}"));
+    diagnostic.error
+}
+
+///Emits a program, or gives the only error it fails with
+pub fn transpile(input: &str) -> Result<String, TypeError> {
+    generate(input).map_err(only_error)
+}
+
+///Writes the files into a new directory named after the test, returning its path
+pub fn write_modules(test: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("beatrice_{test}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    for (name, content) in files {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    dir
+}
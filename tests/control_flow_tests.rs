@@ -0,0 +1,183 @@
+mod common;
+
+use beatrice::transpiler::{BeatriceType, Diagnostic, TypeError};
+use common::{errors, generate};
+
+#[test]
+fn test_missing_return() {
+    //the value is only returned when c is true
+    let found = errors(
+        "function f(c: bool): int {
+            if c {
+                return 1;
+            }
+        }",
+    );
+    assert_eq!(found.len(), 1, "{found:?}");
+    assert!(matches!(
+        &found[0].error,
+        TypeError::MissingReturn(BeatriceType::Int)
+    ));
+    assert_eq!(found[0].span.line, 0);
+    //neither does a body ending with a statement
+    let found = errors("function g(): int { let x = 1; x * 2; }");
+    assert!(matches!(
+        &found[..],
+        [Diagnostic {
+            error: TypeError::MissingReturn(_),
+            ..
+        }]
+    ));
+    //every path returns, through the tail of the body or a jump
+    assert!(
+        errors(
+            "function f(c: bool, d: bool): int {
+                if c {
+                    return 1;
+                } else {
+                    if d { return 2; } else { panic(\"neither\"); };
+                };
+            }
+            function g(c: bool): int {
+                if c { return 1; } else { return 2; };
+            }
+            function h(): int {
+                loop { }
+            }
+            function v(c: bool) {
+                if c { return; };
+            }"
+        )
+        .is_empty()
+    );
+}
+
+#[test]
+fn test_unreachable_code() {
+    let found = errors(
+        "function f(c: bool): int {
+            return 1;
+            let x = 2;
+            x
+        }
+        function g(o: int?): int {
+            loop {
+                match o {
+                    Some(v) -> {
+                        break;
+                        v;
+                    },
+                    None -> panic(\"none\"),
+                };
+            };
+            3
+        }
+        function h() {
+            loop { };
+            let y = 1;
+        }",
+    );
+    let spans = found
+        .iter()
+        .map(|e| (e.span.line, &e.error))
+        .collect::<Vec<_>>();
+    assert!(
+        matches!(
+            &spans[..],
+            [
+                (2, TypeError::UnreachableCode),
+                (10, TypeError::UnreachableCode),
+                (19, TypeError::UnreachableCode),
+            ]
+        ),
+        "{found:?}"
+    );
+}
+
+#[test]
+fn test_jumps_have_the_never_type() {
+    //a jump is accepted as a value of any type
+    let out = generate(
+        "function f(o: int?, c: bool): int {
+            let x: string = if c { \"yes\" } else { return 0; };
+            let y = match o {
+                Some(v) -> v,
+                None -> panic(\"no value: {x}\"),
+            };
+            y
+        }
+        function forever(): string {
+            loop { }
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("return 0;"), "{out}");
+    assert!(!out.contains("x = return"), "{out}");
+    assert!(out.contains("throw new Error(`no value: ${x}`);"), "{out}");
+    //the jumps are still checked
+    let found = errors(
+        "function f(c: bool): int {
+            if c { return \"one\"; };
+            panic(2);
+        }
+        function g() { break; }
+        function h(): int { loop { break; } }",
+    );
+    assert!(
+        matches!(
+            &found[..],
+            [
+                Diagnostic {
+                    error: TypeError::UnexpectedType {
+                        expected: BeatriceType::Int,
+                        received: BeatriceType::String,
+                    },
+                    ..
+                },
+                Diagnostic {
+                    error: TypeError::UnexpectedType {
+                        expected: BeatriceType::String,
+                        received: BeatriceType::Int,
+                    },
+                    ..
+                },
+                Diagnostic {
+                    error: TypeError::BreakOutsideLoop,
+                    ..
+                },
+                Diagnostic {
+                    error: TypeError::UnexpectedType {
+                        expected: BeatriceType::Int,
+                        received: BeatriceType::Void,
+                    },
+                    ..
+                },
+            ]
+        ),
+        "{found:?}"
+    );
+}
+
+#[test]
+fn test_breaks_name_their_loop() {
+    //the break is in the switch of the match, which it would leave without the label
+    let out = generate(
+        "enum Step { Go, Stop }
+        function run(next: Step) {
+            loop {
+                match next {
+                    Step::Go -> {},
+                    Step::Stop -> break,
+                };
+                loop {
+                    if true { break; };
+                };
+            };
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("$loop0: for(;;){"), "{out}");
+    assert!(out.contains("break $loop0;"), "{out}");
+    assert!(out.contains("$loop1: for(;;){"), "{out}");
+    assert!(out.contains("break $loop1;"), "{out}");
+}
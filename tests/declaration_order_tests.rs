@@ -0,0 +1,81 @@
+mod common;
+
+use beatrice::transpiler::{BeatriceType, TypeError};
+use common::transpile;
+
+#[test]
+fn test_calls_before_declaration() {
+    let out = transpile(
+        "function f(): int = scale(2, by: 3) + id(4) + scale(1) + area(unit());
+        function scale(x: int, by: int = 2): int = x * by;
+        function id<T>(x: T): T = x;
+        function unit(): Square = Square { side: 1 };
+        function area(s: Square): int = s.side * s.side;
+        struct Square { side: int; }",
+    )
+    .unwrap();
+    //the parameters of a later function are known, so named arguments and defaults still work
+    assert!(out.contains("return scale(2,3) + id(4) + scale(1) + area(unit());"));
+}
+
+#[test]
+fn test_mutual_recursion() {
+    let out = transpile(
+        "function even(n: int): bool = if n == 0 { true } else { odd(n - 1) };
+        function odd(n: int): bool = if n == 0 { false } else { even(n - 1) };",
+    )
+    .unwrap();
+    assert!(out.contains(
+        "if(n == 0){
+        return true;
+    } else {
+        return odd(n - 1);
+    }"
+    ));
+    assert!(out.contains("return even(n - 1);"));
+    //a struct and a function can refer to each other too
+    let out = transpile(
+        "function depth(t: Tree): int = match t.child { Some(c) -> 1 + depth(c), None -> 1 };
+        struct Tree { child: Tree?; }",
+    );
+    assert!(out.is_ok());
+}
+
+#[test]
+fn test_signatures_checked_before_bodies() {
+    assert!(matches!(
+        transpile("function f(): int = g(2.5); function g(x: int): int = x;"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Float,
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(): float = g(1); function g(x: int): int = x;"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Float,
+            received: BeatriceType::Int,
+        })
+    ));
+    //a signature is checked even if the function is never called
+    assert!(matches!(
+        transpile("function f(): int = 1; function g(x: Missing): int = 1;"),
+        Err(TypeError::NotRecognizedType(name)) if name == "Missing"
+    ));
+    assert!(matches!(
+        transpile("function f(): int = nowhere(1);"),
+        Err(TypeError::NotRecognizedVar(name)) if name == "nowhere"
+    ));
+}
+
+#[test]
+fn test_exports_in_any_order() {
+    let out = transpile(
+        "export function origin(): Point = Point { x: zero(), y: zero() };
+        function zero(): int = 0;
+        export struct Point { x: int; y: int; }",
+    )
+    .unwrap();
+    assert!(out.contains("export function origin(){"));
+    assert!(out.contains("{x:zero(),y:zero()}"));
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, Parser, Pattern},
+    transpiler::{BeatriceType, TypeError},
+};
+use common::transpile;
+
+const POINT: &str = "struct Point {
+    x: int;
+    y: int;
+}
+";
+
+#[test]
+fn test_parse_destructuring() {
+    let program = Parser::from_content(
+        "function f(Pair { a, .. }: Pair<int>, _: int): int {
+            let Point { x, y: py, .. } = p;
+            let (a, (b,), _) = t;
+            let [first, _, third] = arr;
+            a
+        }",
+    )
+    .gen_ast()
+    .unwrap();
+    let AST::Function { params, body, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    assert!(matches!(params[0].pattern, Pattern::Struct { .. }));
+    assert!(matches!(params[1].pattern, Pattern::Wildcard));
+    let body = body.body();
+
+    let AST::VarDecl { pattern, .. } = &body[0] else {
+        panic!("Expected a variable declaration");
+    };
+    let Pattern::Struct { name, fields, rest } = pattern else {
+        panic!("Expected a struct pattern");
+    };
+    assert_eq!(name, "Point");
+    assert!(*rest);
+    assert!(matches!(&fields[1].pattern, Pattern::Binding(b) if b == "py"));
+    assert_eq!(pattern.bindings(), vec!["x", "py"]);
+
+    let AST::VarDecl { pattern, .. } = &body[1] else {
+        panic!("Expected a variable declaration");
+    };
+    assert_eq!(pattern.to_string(), "(a, (b,), _)");
+    let AST::VarDecl { pattern, .. } = &body[2] else {
+        panic!("Expected a variable declaration");
+    };
+    assert!(matches!(pattern, Pattern::Array(items) if items.len() == 3));
+}
+
+#[test]
+fn test_let_destructuring_emission() {
+    let out = transpile(&format!(
+        "{POINT}function f(): int {{
+            let Point {{ x: a, y: _ }} = Point {{ x: 1, y: 2 }};
+            let mut Point {{ y, .. }} = Point {{ x: a, y: 3 }};
+            let _ = Point {{ x: 0, y: 0 }};
+            a + y
+        }}"
+    ))
+    .unwrap();
+    assert!(out.contains("const {x: a} = /**Point*/ {x:1,y:2}"), "{out}");
+    assert!(out.contains("const {y} = /**Point*/ {x:a,y:3}"), "{out}");
+    assert!(out.contains("/**Point*/ {x:0,y:0}\n"), "{out}");
+}
+
+#[test]
+fn test_parameter_destructuring() {
+    let out = transpile(
+        "struct Pair<T> {
+            a: T;
+            b: T;
+        }
+        enum Wrap { W(int, float) }
+        function sum(Pair { a, b: bb }: Pair<int>, _: int): int = a + bb;
+        function first(Wrap::W(n, _): Wrap): int = n;",
+    )
+    .unwrap();
+    assert!(out.contains("function sum({a, b: bb},_$1){"), "{out}");
+    assert!(out.contains("function first({0: n}){"), "{out}");
+}
+
+#[test]
+fn test_destructuring_block_values() {
+    let out = transpile(&format!(
+        "{POINT}function f(n: int): int {{
+            let Point {{ x, y }} = match n {{
+                0 -> Point {{ x: 0, y: 1 }},
+                _ -> Point {{ x: n, y: n }},
+            }};
+            x + y
+        }}"
+    ))
+    .unwrap();
+    assert!(out.contains("let $let0;"), "{out}");
+    assert!(out.contains("$let0 = /**Point*/ {x:0,y:1};"), "{out}");
+    assert!(out.contains("const {x, y} = $let0;"), "{out}");
+}
+
+#[test]
+fn test_destructuring_errors() {
+    assert!(matches!(
+        transpile(&format!(
+            "{POINT}function f(): int {{ let Point {{ x, z }} = Point {{ x: 1, y: 2 }}; x }}"
+        )),
+        Err(TypeError::InvalidFieldName { field, .. }) if field == "z"
+    ));
+    assert!(matches!(
+        transpile(&format!(
+            "{POINT}function f(): int {{ let Point {{ x }} = Point {{ x: 1, y: 2 }}; x }}"
+        )),
+        Err(TypeError::NotCorrectFields { fields, .. }) if fields == ["y"]
+    ));
+    assert!(matches!(
+        transpile(&format!(
+            "{POINT}function f(): float {{ let Point {{ x: Shape::Empty, .. }} = Point {{ x: 1, y: 2 }}; 1.0 }}"
+        )),
+        Err(TypeError::InvalidPattern {
+            expected: BeatriceType::Int,
+            ..
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(): int { let (a, b) = 1; a }"),
+        Err(TypeError::InvalidPattern { .. })
+    ));
+}
+
+#[test]
+fn test_refutable_patterns() {
+    let result = transpile(
+        "enum Shape { Circle(float), Empty }
+        function f(s: Shape): float { let Shape::Circle(r) = s; r }",
+    );
+    assert!(
+        matches!(&result, Err(TypeError::RefutablePattern { missing, .. }) if missing == "Shape::Empty"),
+        "{result:?}"
+    );
+    assert!(matches!(
+        transpile(&format!(
+            "{POINT}function f(): int {{ let Point {{ x: 0, .. }} = Point {{ x: 1, y: 2 }}; 1 }}"
+        )),
+        Err(TypeError::RefutablePattern { .. })
+    ));
+    assert!(matches!(
+        transpile("function f(0: int): int = 1;"),
+        Err(TypeError::RefutablePattern { .. })
+    ));
+}
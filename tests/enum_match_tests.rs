@@ -0,0 +1,267 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, AstErrorKind, Parser, Pattern, VariantPattern},
+    transpiler::{BeatriceType, TypeError},
+};
+use common::transpile;
+
+const SHAPE: &str = "enum Shape {
+    Circle(float),
+    Rect { w: float, h: float },
+    Empty,
+}
+";
+
+#[test]
+fn test_parse_patterns() {
+    let program = Parser::from_content(
+        "function f(s: Shape): int = match s {
+            Shape::Rect { w: 1.0..=2.5, .. } -> 1,
+            Shape::Circle(r) if r > 0.0 && r < 1.0 -> 2,
+            -3..5 -> 3,
+            _ -> 4,
+        };",
+    )
+    .gen_ast()
+    .unwrap();
+    let AST::Function { body, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    let AST::Return(Some(ret)) = &body.body()[0] else {
+        panic!("Expected a return");
+    };
+    let AST::Match { arms, .. } = &**ret else {
+        panic!("Expected a match");
+    };
+    assert_eq!(arms.len(), 4);
+    let Pattern::Variant {
+        fields: VariantPattern::Struct { fields, rest },
+        ..
+    } = &arms[0].pattern
+    else {
+        panic!("Expected a struct-like variant pattern");
+    };
+    assert!(*rest);
+    assert!(matches!(
+        fields[0].pattern,
+        Pattern::Range {
+            inclusive: true,
+            ..
+        }
+    ));
+    assert!(matches!(arms[1].guard, Some(AST::BinExpr(..))));
+    assert_eq!(arms[2].pattern.to_string(), "-3..5");
+    assert!(matches!(arms[3].pattern, Pattern::Wildcard));
+}
+
+#[test]
+fn test_enum_emission() {
+    let out = transpile(&format!(
+        "{SHAPE}function area(s: Shape): float = match s {{
+            Shape::Circle(r) -> r * r,
+            Shape::Rect {{ w, h }} -> w * h,
+            Shape::Empty -> 0.0,
+        }};
+        function square(w: float): Shape = Shape::Rect {{ w, h: w }};"
+    ))
+    .unwrap();
+    assert!(out.contains("switch(s.$){"), "{out}");
+    assert!(out.contains("case 0: {"), "{out}");
+    assert!(out.contains("const r = s[0];"), "{out}");
+    assert!(out.contains("const w = s.w;"), "{out}");
+    assert!(
+        out.contains("return /**Shape::Rect*/ {$:1,w,h:w};"),
+        "{out}"
+    );
+}
+
+#[test]
+fn test_guards_and_literal_patterns() {
+    let out = transpile(
+        "function sign(n: int): int {
+            let s = match n {
+                0 -> 0,
+                1..=9 -> 1,
+                x if x < 0 -> 0 - 1,
+                _ -> 2,
+            };
+            s
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("let s;"), "{out}");
+    assert!(out.contains("if(n >= 1 && n <= 9){"), "{out}");
+    assert!(out.contains("if(x < 0){"), "{out}");
+    assert!(out.contains("break $match0;"), "{out}");
+}
+
+#[test]
+fn test_exhaustiveness() {
+    let result = transpile(&format!(
+        "{SHAPE}function f(s: Shape): int = match s {{
+            Shape::Circle(_) -> 1,
+            Shape::Empty -> 3,
+        }};"
+    ));
+    assert!(
+        matches!(&result, Err(TypeError::NonExhaustiveMatch { missing }) if missing == "Shape::Rect { w: _, h: _ }"),
+        "{result:?}"
+    );
+    //a guarded arm may not match, so it doesn't cover its variant
+    assert!(matches!(
+        transpile(&format!(
+            "{SHAPE}function f(s: Shape): int = match s {{
+                Shape::Circle(r) if r > 0.0 -> 1,
+                Shape::Rect {{ .. }} -> 2,
+                Shape::Empty -> 3,
+            }};"
+        )),
+        Err(TypeError::NonExhaustiveMatch { missing }) if missing == "Shape::Circle(_)"
+    ));
+    assert!(matches!(
+        transpile("function f(n: int): int = match n { 0 -> 1, 1..10 -> 2 };"),
+        Err(TypeError::NonExhaustiveMatch { missing }) if missing == "_"
+    ));
+}
+
+#[test]
+fn test_bool_patterns() {
+    let out = transpile(
+        "function f(b: bool): int = match b { true -> 1, false -> 2 };
+        function g(p: (bool, int)): int = match p { (true, n) -> n, (false, _) -> 0 };",
+    )
+    .unwrap();
+    assert!(out.contains("if(b === true){"), "{out}");
+    assert!(out.contains("if(b === false){"), "{out}");
+    assert!(out.contains("if(p[0] === true){"), "{out}");
+    assert!(!out.contains("true$"), "{out}");
+    assert!(matches!(
+        transpile("function f(b: bool): int = match b { true -> 1 };"),
+        Err(TypeError::NonExhaustiveMatch { missing }) if missing == "false"
+    ));
+    assert!(matches!(
+        transpile("function f(b: bool): int = match b { (false) -> 1, true if b -> 2 };"),
+        Err(TypeError::NonExhaustiveMatch { missing }) if missing == "true"
+    ));
+    //true and false are values, not names to bind
+    assert!(matches!(
+        transpile("function f(): int { let true = 5; 1 }"),
+        Err(TypeError::InvalidPattern { pattern, .. }) if pattern == "true"
+    ));
+    assert!(matches!(
+        transpile("function f(b: bool): int { let true = b; 1 }"),
+        Err(TypeError::RefutablePattern { missing, .. }) if missing == "false"
+    ));
+    let errors = Parser::from_content(
+        "struct P { a: bool }
+        function f(p: P): int = match p { P { true } -> 1, _ -> 2 };",
+    )
+    .gen_ast()
+    .unwrap_err();
+    assert!(matches!(errors[0].kind, AstErrorKind::UnexpectedToken(_)));
+}
+
+#[test]
+fn test_nested_generic_variants() {
+    let maybe = "enum Maybe<T> { Some(T), None }\n";
+    let result = transpile(&format!(
+        "{maybe}{SHAPE}function f(m: Maybe<Shape>): int = match m {{
+            Maybe::Some(Shape::Circle(_)) -> 1,
+            Maybe::Some(Shape::Empty) -> 2,
+            Maybe::None -> 3,
+        }};"
+    ));
+    assert!(
+        matches!(&result, Err(TypeError::NonExhaustiveMatch { missing }) if missing == "Maybe::Some(Shape::Rect { w: _, h: _ })"),
+        "{result:?}"
+    );
+    let out = transpile(&format!(
+        "{maybe}function f(m: Maybe<int>): int = match m {{
+            Maybe::Some(Maybe::None) -> 1,
+            _ -> 0,
+        }};"
+    ));
+    assert!(matches!(out, Err(TypeError::InvalidPattern { .. })));
+
+    assert!(
+        transpile(&format!(
+            "{maybe}function f(): Maybe<int> = Maybe::Some(4);"
+        ))
+        .is_ok()
+    );
+    //the returned None gets its type from the return type, the one of a bare let has none
+    assert!(transpile(&format!("{maybe}function f(): Maybe<int> = Maybe::None;")).is_ok());
+    assert!(matches!(
+        transpile(&format!("{maybe}function f() {{ let m = Maybe::None; }}")),
+        Err(TypeError::CannotInferGeneric { param, .. }) if param == "T"
+    ));
+    assert!(matches!(
+        transpile(&format!(
+            "{maybe}function f(): Maybe<int> = Maybe<float>::None;"
+        )),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Enum { .. },
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_arms_must_agree() {
+    let result = transpile(&format!(
+        "{SHAPE}function f(s: Shape): int = match s {{
+            Shape::Circle(_) -> 1,
+            Shape::Rect {{ w, h }} -> w,
+            _ -> 0,
+        }};"
+    ));
+    let Err(TypeError::MatchArmWrong {
+        arm,
+        expected,
+        received,
+    }) = result
+    else {
+        panic!("Expected the arms to disagree, got {result:?}");
+    };
+    assert_eq!(arm, 1);
+    assert_eq!(*expected, BeatriceType::Int);
+    assert_eq!(*received, BeatriceType::Float);
+    assert!(matches!(
+        transpile(&format!(
+            "{SHAPE}function f(s: Shape): Shape = Shape::Circle(1.0, 2.0);"
+        )),
+        Err(TypeError::WrongVariantFields {
+            expected: 1,
+            received: 2,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_match_inside_an_expression() {
+    //the match is hoisted before the statement holding it, and gives its value to a temporary
+    let out = transpile(&format!(
+        "{SHAPE}function scale(x: float): float = x * 2.0;
+        function area(s: Shape): float = scale(match s {{
+            Shape::Circle(r) -> r * r,
+            Shape::Rect {{ w, h }} -> w * h,
+            Shape::Empty -> 0.0,
+        }});
+        function size(n: int): Result<string, string> = Ok(match n {{
+            0 -> \"empty\",
+            _ -> \"full\",
+        }});"
+    ))
+    .unwrap();
+    assert!(out.contains("let $match0;\n    switch(s.$){"), "{out}");
+    assert!(out.contains("$match0 = r * r;"), "{out}");
+    assert!(out.contains("return scale($match0);"), "{out}");
+    assert!(out.contains("let $match1;\n    $match2: {"), "{out}");
+    assert!(
+        out.contains("$match1 = \"empty\";\n            break $match2;"),
+        "{out}"
+    );
+    assert!(out.contains("return {ok:true,value:$match1};"), "{out}");
+}
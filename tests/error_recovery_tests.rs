@@ -0,0 +1,104 @@
+mod common;
+
+use beatrice::transpiler::{BeatriceType, Diagnostic, TypeError};
+use common::errors;
+
+fn lines(found: &[Diagnostic]) -> Vec<usize> {
+    found.iter().map(|e| e.span.line).collect()
+}
+
+#[test]
+fn test_every_independent_error_is_reported() {
+    let found = errors(
+        "function f(): int {
+            let x = 1 + true;
+            let y: string = 5;
+            2
+        }
+        function g(): float = 1.5 + nowhere;
+        function h(): bool = f() as bool;",
+    );
+    assert_eq!(lines(&found), [1, 2, 5, 6]);
+    assert!(matches!(
+        &found[0].error,
+        TypeError::InvalidOperands {
+            rhs: BeatriceType::Bool,
+            ..
+        }
+    ));
+    assert!(matches!(
+        &found[1].error,
+        TypeError::UnexpectedType {
+            expected: BeatriceType::String,
+            received: BeatriceType::Int,
+        }
+    ));
+    assert!(matches!(&found[2].error, TypeError::NotRecognizedVar(name) if name == "nowhere"));
+    assert!(matches!(&found[3].error, TypeError::InvalidCast { .. }));
+    //a program without errors still generates
+    assert!(errors("function f(): int = 1;").is_empty());
+}
+
+#[test]
+fn test_errors_dont_cascade() {
+    //x failed to check, so every use of it is accepted
+    let found = errors(
+        "struct Point { x: int; y: int; }
+        function f(p: Point?): int {
+            let x = nowhere;
+            let a = x + 1;
+            let b: Point = x;
+            let c = x.y ?? x.0;
+            let (d, e) = x;
+            let s = \"{x} {d:?}\";
+            match x { Some(v) -> v, None -> e }
+        }",
+    );
+    assert_eq!(found.len(), 1, "{found:?}");
+    assert!(matches!(&found[0].error, TypeError::NotRecognizedVar(name) if name == "nowhere"));
+
+    //neither does the value of a block holding an error
+    let found = errors("function f(): int { let x = { let a = 1 + true; a * 2 }; x? }");
+    assert_eq!(found.len(), 1, "{found:?}");
+}
+
+#[test]
+fn test_failed_declarations_are_still_declared() {
+    let found = errors(
+        "import { load } from \"./missing\";
+        struct Point { x: Coord; y: int; }
+        enum Shape { Dot(Point), Line(Vector) }
+        function f(p: Point, s: Shape): int = match s {
+            Shape::Dot(q) -> q.x + p.y,
+            Shape::Line(v) -> load(v.length),
+        };
+        function g(size: Size): int = size * 2 + f(Point { x: 1, y: 2 }, Shape::Line(size));",
+    );
+    assert_eq!(lines(&found), [0, 1, 2, 7], "{found:?}");
+    assert!(matches!(&found[0].error, TypeError::UnresolvedModule(path) if path == "./missing"));
+    assert!(matches!(&found[1].error, TypeError::NotRecognizedType(name) if name == "Coord"));
+    assert!(matches!(&found[2].error, TypeError::NotRecognizedType(name) if name == "Vector"));
+    assert!(matches!(&found[3].error, TypeError::NotRecognizedType(name) if name == "Size"));
+}
+
+#[test]
+fn test_errors_are_sorted_by_position() {
+    //the declarations are checked first, and the names resolved before the types are checked,
+    //but the errors come in the order they are written
+    let found = errors(
+        "function f(): int = g() + missing;
+        function g(): int = 1 + 2.5;
+        struct Wrong { field: Nope; }
+        function g(): int = 2;",
+    );
+    assert_eq!(lines(&found), [0, 1, 2, 3], "{found:?}");
+    assert!(matches!(&found[0].error, TypeError::NotRecognizedVar(name) if name == "missing"));
+    assert!(matches!(&found[1].error, TypeError::InvalidOperands { .. }));
+    assert!(matches!(&found[2].error, TypeError::NotRecognizedType(name) if name == "Nope"));
+    assert!(matches!(&found[3].error, TypeError::DuplicateDefinition(name) if name == "g"));
+    assert!(
+        found
+            .windows(2)
+            .all(|w| (w[0].span.line, w[0].span.column) <= (w[1].span.line, w[1].span.column))
+    );
+}
@@ -0,0 +1,230 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, Parser, TypeAst},
+    transpiler::{BeatriceType, Bound, TypeError},
+};
+use common::transpile;
+
+const PAIR: &str = "struct Pair<T> where T extends Numeric {
+    a: T;
+    b: T;
+}
+";
+
+#[test]
+fn test_parse_generic_declarations() {
+    let program = Parser::from_content(
+        "struct Pair<T, U> where T extends Numeric + Integer, U extends Numeric {
+            a: T;
+            b: U;
+        }
+        function id<T>(x: T): T = x;",
+    )
+    .gen_ast()
+    .unwrap();
+    let AST::Struct { generics, .. } = &program.body()[0] else {
+        panic!("Expected a struct");
+    };
+    assert_eq!(generics.len(), 2);
+    assert_eq!(generics[0].bounds, ["Numeric", "Integer"]);
+    assert_eq!(generics[1].bounds, ["Numeric"]);
+    let AST::Function { generics, .. } = &program.body()[1] else {
+        panic!("Expected a function");
+    };
+    assert_eq!(generics[0].name, "T");
+    assert!(generics[0].bounds.is_empty());
+}
+
+#[test]
+fn test_parse_nested_generic_type() {
+    let mut parser = Parser::from_content("Pair<Pair<int>>");
+    let TypeAst::Generic { name, args } = parser.get_type().unwrap() else {
+        panic!("Expected a generic type");
+    };
+    assert_eq!(name, "Pair");
+    assert!(matches!(&args[0], TypeAst::Generic { args, .. } if args.len() == 1));
+}
+
+#[test]
+fn test_inferred_and_explicit_calls() {
+    let id = "function id<T>(x: T): T = x;\n";
+    assert!(transpile(&format!("{id}function main(): float = id(2.5);")).is_ok());
+    assert!(matches!(
+        transpile(&format!("{id}function main(): int = id(2.5);")),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Float
+        })
+    ));
+    assert!(matches!(
+        transpile(&format!("{id}function main(): int = id<int>(2.5);")),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Float
+        })
+    ));
+    let out = transpile(&format!("{id}function main(): int = id<int>(5);")).unwrap();
+    assert!(out.contains("return id(5);"), "{out}");
+}
+
+#[test]
+fn test_unsatisfied_bound() {
+    let add = "function add<T>(a: T, b: T): T where T extends Integer = a + b;\n";
+    assert!(transpile(&format!("{add}function main(): int = add(1, 2);")).is_ok());
+    let result = transpile(&format!("{add}function main(): float = add(1.0, 2.0);"));
+    let Err(TypeError::UnsatisfiedBound {
+        param,
+        bound,
+        received,
+    }) = result
+    else {
+        panic!("Expected an unsatisfied bound, got {result:?}");
+    };
+    assert_eq!(param, "T");
+    assert_eq!(bound, Bound::Integer);
+    assert_eq!(received, BeatriceType::Float);
+    //the body is emitted once without wrapping its operations, so 200 + 100 would be an u8 300
+    assert!(matches!(
+        transpile(&format!(
+            "{add}function main(a: u8, b: u8): u8 = add(a, b);"
+        )),
+        Err(TypeError::UnsatisfiedBound {
+            bound: Bound::Integer,
+            received: BeatriceType::U8,
+            ..
+        })
+    ));
+    let scale = "function scale<T>(x: T): T where T extends Floating = x * x;\n";
+    assert!(matches!(
+        transpile(&format!("{scale}function main(a: f32): f32 = scale(a);")),
+        Err(TypeError::UnsatisfiedBound {
+            received: BeatriceType::F32,
+            ..
+        })
+    ));
+
+    //generic parameters satisfy the bounds implied by their own
+    assert!(
+        transpile(&format!(
+            "{add}function twice<N>(n: N): N where N extends Integer = add(n, n);"
+        ))
+        .is_ok()
+    );
+    assert!(matches!(
+        transpile(&format!("{add}function twice<N>(n: N): N = add(n, n);")),
+        Err(TypeError::UnsatisfiedBound { .. })
+    ));
+    assert!(matches!(
+        transpile("function f<T>(a: T): T where T extends Sortable = a;"),
+        Err(TypeError::NotRecognizedBound(b)) if b == "Sortable"
+    ));
+}
+
+#[test]
+fn test_generic_structs() {
+    assert!(
+        transpile(&format!(
+            "{PAIR}function make(): Pair<int> = Pair {{ a: 1, b: 2 }};"
+        ))
+        .is_ok()
+    );
+    //the return type is the expected type of the literal, a Pair inferred alone is a Pair<int>
+    assert!(
+        transpile(&format!(
+            "{PAIR}function make(): Pair<float> = Pair {{ a: 1, b: 2 }};"
+        ))
+        .is_ok()
+    );
+    assert!(matches!(
+        transpile(&format!(
+            "{PAIR}function make(): Pair<float> {{
+                let p = Pair {{ a: 1, b: 2 }};
+                p
+            }}"
+        )),
+        Err(TypeError::UnexpectedType { .. })
+    ));
+    assert!(matches!(
+        transpile(&format!(
+            "{PAIR}function make(): Pair<float> {{
+                let p = Pair<float> {{ a: 1, b: 2 }};
+                p
+            }}"
+        )),
+        Err(TypeError::InvalidFieldValue { .. })
+    ));
+    //a generic parameter given to Pair must carry the bounds Pair asks for
+    assert!(matches!(
+        transpile(&format!("{PAIR}function first<T>(p: Pair<T>): int = 0;")),
+        Err(TypeError::UnsatisfiedBound { .. })
+    ));
+    assert!(
+        transpile(&format!(
+            "{PAIR}function first<T>(p: Pair<T>): int where T extends Numeric = 0;\nfunction main(): int = first(Pair {{ a: 1.5, b: 2.5 }});"
+        ))
+        .is_ok()
+    );
+    assert!(matches!(
+        transpile(&format!("{PAIR}function make(p: Pair): int = 0;")),
+        Err(TypeError::WrongGenericsAmount {
+            expected: 1,
+            received: 0,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_generics_that_cannot_be_inferred() {
+    let zero = "function zero<T>(): int = 0;\n";
+    assert!(matches!(
+        transpile(&format!("{zero}function main(): int = zero();")),
+        Err(TypeError::CannotInferGeneric { param, .. }) if param == "T"
+    ));
+    assert!(transpile(&format!("{zero}function main(): int = zero<float>();")).is_ok());
+    assert!(matches!(
+        transpile(&format!("{zero}function main(): int = zero<float, int>();")),
+        Err(TypeError::WrongGenericsAmount {
+            expected: 1,
+            received: 2,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_type_arguments_of_non_generic_targets() {
+    let wrong = |program: &str| {
+        matches!(
+            transpile(program),
+            Err(TypeError::WrongGenericsAmount {
+                expected: 0,
+                received: 2,
+                ..
+            })
+        )
+    };
+    assert!(wrong(
+        "function k(x: int): int = x; function f(): int = k<float, bool>(1);"
+    ));
+    assert!(wrong(
+        "struct S { a: int; } function f(): S = S<float, bool> { a: 1 };"
+    ));
+    assert!(wrong(
+        "enum E { A(int) } function f(): E = E<float, bool>::A(1);"
+    ));
+    //nor does a variable holding a function, even when it hides a generic function
+    assert!(wrong("function f(h: (int): int): int = h<float, bool>(1);"));
+    assert!(matches!(
+        transpile(
+            "function id<T>(x: T): T = x;
+            function f(id: (int): int): int = id<float>(1);"
+        ),
+        Err(TypeError::WrongGenericsAmount {
+            expected: 0,
+            received: 1,
+            ..
+        })
+    ));
+}
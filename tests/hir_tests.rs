@@ -0,0 +1,154 @@
+use beatrice::{
+    parser::Parser,
+    transpiler::{
+        BeatriceType,
+        hir::{Expr, ExprKind, Pattern},
+        transpiler::BeatriceTranspiler,
+    },
+};
+
+fn lower(input: &str) -> (BeatriceTranspiler, Vec<Expr>) {
+    let program = Parser::from_content(input).gen_ast().unwrap();
+    let mut transpiler = BeatriceTranspiler::new("unused.js");
+    let hir = transpiler.check(&program).unwrap();
+    (transpiler, hir)
+}
+
+///The expression returned by the function declared at idx
+fn returned(hir: &[Expr], idx: usize) -> &Expr {
+    let ExprKind::Function(f) = &hir[idx].kind else {
+        panic!("Expected a function, got {:?}", hir[idx]);
+    };
+    let ExprKind::Return(Some(ret)) = &f.body.last().unwrap().kind else {
+        panic!("Expected the returned expression");
+    };
+    ret
+}
+
+#[test]
+fn test_expressions_carry_their_type() {
+    let (_, hir) = lower("function f(x: u8): u8 = x + 1;");
+    let sum = returned(&hir, 0);
+    assert_eq!(sum.ty, BeatriceType::U8);
+    let ExprKind::Binary {
+        lhs, rhs, numeric, ..
+    } = &sum.kind
+    else {
+        panic!("Expected a sum, got {sum:?}");
+    };
+    assert_eq!(lhs.ty, BeatriceType::U8);
+    assert_eq!(rhs.ty, BeatriceType::U8);
+    assert_eq!(*numeric, Some(BeatriceType::U8));
+    //declarations have no value
+    assert_eq!(hir[0].ty, BeatriceType::Void);
+
+    let (_, hir) = lower("function f(): string? { let s = Some(\"a\"); s }");
+    assert_eq!(
+        returned(&hir, 0).ty,
+        BeatriceType::option(BeatriceType::String)
+    );
+}
+
+#[test]
+fn test_names_carry_their_symbol() {
+    let (_, hir) = lower("function f(x: int): int { let x = x + 1; x }");
+    let ExprKind::Function(f) = &hir[0].kind else {
+        panic!("Expected a function");
+    };
+    let Pattern::Binding { symbol: param, .. } = f.params[0].pattern else {
+        panic!("Expected a binding");
+    };
+    let ExprKind::Let {
+        pattern: Pattern::Binding {
+            symbol: shadow,
+            name,
+        },
+        body,
+        ..
+    } = &f.body[0].kind
+    else {
+        panic!("Expected a let, got {:?}", f.body[0]);
+    };
+    assert_ne!(param, *shadow);
+    assert_eq!(name, "x$1");
+    let ExprKind::Binary { lhs, .. } = &body.kind else {
+        panic!("Expected a sum");
+    };
+    assert!(matches!(lhs.kind, ExprKind::Var { symbol, .. } if symbol == param));
+    assert!(
+        matches!(&returned(&hir, 0).kind, ExprKind::Var { symbol, name } if symbol == shadow && name == "x$1")
+    );
+}
+
+#[test]
+fn test_call_arguments_in_parameter_order() {
+    let (_, hir) = lower(
+        "function g(a: int, b: int = 2, c: int = 3): int = a + b + c;
+        function f(): int = g(1, c: 5);",
+    );
+    let call = returned(&hir, 1);
+    assert_eq!(call.ty, BeatriceType::Int);
+    let ExprKind::Call { name, args, .. } = &call.kind else {
+        panic!("Expected a call, got {call:?}");
+    };
+    assert_eq!(name, "g");
+    assert!(matches!(
+        args.as_slice(),
+        [
+            Some(Expr {
+                kind: ExprKind::Int(1),
+                ..
+            }),
+            None,
+            Some(Expr {
+                kind: ExprKind::Int(5),
+                ..
+            }),
+        ]
+    ));
+}
+
+#[test]
+fn test_emits_from_the_hir() {
+    let (mut transpiler, mut hir) = lower("function f(): int = 1 + 2;");
+    assert!(transpiler.emit(&hir).contains("return 1 + 2;"));
+    //emitting only reads the hir, so changes made to it are emitted
+    let ExprKind::Function(f) = &mut hir[0].kind else {
+        panic!("Expected a function");
+    };
+    let ExprKind::Return(Some(ret)) = &mut f.body[0].kind else {
+        panic!("Expected the returned expression");
+    };
+    let ExprKind::Binary { rhs, .. } = &mut ret.kind else {
+        panic!("Expected a sum");
+    };
+    rhs.kind = ExprKind::Int(40);
+    assert!(transpiler.emit(&hir).contains("return 1 + 40;"));
+}
+
+#[test]
+fn test_if_arguments_are_typed() {
+    let (mut transpiler, hir) = lower(
+        "function g(x: int): int = x;
+        function f(c: bool, n: int): int = g(if c { 1 } else { let m = n * 2; m + 1 });",
+    );
+    let call = returned(&hir, 1);
+    let ExprKind::Call { args, .. } = &call.kind else {
+        panic!("Expected a call, got {call:?}");
+    };
+    let Some(Expr {
+        kind: ExprKind::If { cond, .. },
+        ty,
+        ..
+    }) = &args[0]
+    else {
+        panic!("Expected an if, got {:?}", args[0]);
+    };
+    assert_eq!(*ty, BeatriceType::Int);
+    assert_eq!(cond.ty, BeatriceType::Bool);
+    //the if can't be written inside the call, so it gives its value through a temporary
+    let out = transpiler.emit(&hir);
+    assert!(out.contains("let $if0;"), "{out}");
+    assert!(out.contains("$if0 = m + 1;"), "{out}");
+    assert!(out.contains("return g($if0);"), "{out}");
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use beatrice::modules::ModuleGraph;
+use common::{transpile, write_modules};
+
+#[test]
+fn test_reserved_words() {
+    let out = transpile(
+        "function f(class: int, new: int = 2): int {
+            let this = class + new;
+            let arguments = this * 2;
+            arguments
+        }
+        function g(): int = f(1, new: 3);",
+    )
+    .unwrap();
+    assert!(out.contains("function f(class$,new$){"));
+    //the defaults still read the arguments of the call
+    assert!(out.contains("if(arguments.length <= 1 || new$ === "));
+    assert!(out.contains("const this$ = class$ + new$;"));
+    assert!(out.contains("const arguments$ = this$ * 2;"));
+    assert!(out.contains("return f(1,3);"));
+}
+
+#[test]
+fn test_shadowing_in_one_block() {
+    let out = transpile(
+        "function f(a: int): int {
+            let a = a + 1;
+            let a = a * 2;
+            a
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("const a$1 = a + 1;"));
+    assert!(out.contains("const a$2 = a$1 * 2;"));
+    assert!(out.contains("return a$2;"));
+    //a variable declared after a call to the function with its name doesn't hide it
+    let out = transpile(
+        "function id(x: int): int = x;
+        function f(): int { let y = id(1); let id = 2; y + id }",
+    )
+    .unwrap();
+    assert!(out.contains("const y = id(1);"));
+    assert!(out.contains("const id$1 = 2;"));
+    //variables that don't shadow anything keep their names
+    let out = transpile("function f(p: (int, int)): int { let (a, b) = p; a + b }").unwrap();
+    assert!(out.contains("const [a, b] = p;"));
+}
+
+#[test]
+fn test_made_up_names_dont_clash() {
+    //the variables a macro introduces can't take the names of renamed variables
+    let out = transpile(
+        "macro decl {
+            ($x:expr) -> { let tmp = $x; }
+        }
+        function f(a: int): int {
+            let tmp = 1;
+            decl!(a)
+            let tmp = 2;
+            decl!(a)
+            tmp
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("const tmp$g0 = a;"), "{out}");
+    assert!(out.contains("const tmp$1 = 2;"), "{out}");
+    assert!(out.contains("const tmp$g1 = a;"), "{out}");
+    //nor the temporaries of the transpiler, which start with a $
+    let out = transpile(
+        "macro keep {
+            ($x:expr) -> { let try = $x; }
+        }
+        function p(a: int): Result<int, string> = Ok(a);
+        function g(a: int, arg: int): Result<int, string> {
+            keep!(a)
+            let arg = arg + 1;
+            let b = p(a)?;
+            Ok(b + arg)
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("const try$g0 = a;"), "{out}");
+    assert!(out.contains("const arg$1 = arg + 1;"), "{out}");
+    assert!(out.contains("const $try0 = p(a);"), "{out}");
+}
+
+#[test]
+fn test_reserved_field_names() {
+    let out = transpile(
+        "@Display(\"{class}\")
+        struct Item { class: int; size: int; }
+        function f(i: Item): string {
+            let Item { class, size } = i;
+            let copy = Item { class: class, size: size };
+            \"{copy}\"
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("function Item$display({class: class$, size}){"));
+    assert!(out.contains("return `${class$}`;"));
+    assert!(out.contains("`Item { class: ${class$}, size: ${size} }`"));
+    assert!(out.contains("const {class: class$, size} = i;"));
+    assert!(out.contains("{class:class$,size}"));
+}
+
+#[test]
+fn test_exported_names_are_stable() {
+    let dir = write_modules(
+        "stable_exports",
+        &[
+            (
+                "main.bt",
+                "import { new, scale } from \"./lib\";
+                function f(): int = scale(new(), 2);",
+            ),
+            (
+                "lib.bt",
+                "export function new(): int = 1;
+                export function scale(scale: int, by: int): int { let scale = scale * by; scale }",
+            ),
+        ],
+    );
+    let out = ModuleGraph::load(dir.join("main.bt"))
+        .unwrap()
+        .transpile()
+        .unwrap();
+    assert!(out[0].contains("function new$(){"), "{}", out[0]);
+    assert!(out[0].contains("export { new$ as new };"), "{}", out[0]);
+    assert!(
+        out[0].contains("export function scale(scale,by){"),
+        "{}",
+        out[0]
+    );
+    assert!(out[0].contains("const scale$1 = scale * by;"), "{}", out[0]);
+    assert!(
+        out[1].contains("import { new as new$, scale } from \"./lib.js\";"),
+        "{}",
+        out[1]
+    );
+    assert!(out[1].contains("return scale(new$(),2);"), "{}", out[1]);
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, Parser, Pattern, TypeAst},
+    transpiler::{BeatriceType, TypeError},
+};
+use common::transpile;
+
+const MAYBE: &str = "enum Maybe<T> {
+    Some(T),
+    None,
+}
+struct Pair<T> {
+    a: T;
+    b: T;
+}
+";
+
+#[test]
+fn test_parse_annotations() {
+    let program = Parser::from_content(
+        "function f(): int {
+            let a: int = 5;
+            let mut b: (float, int?) = (1, None);
+            let c = 2;
+            a
+        }",
+    )
+    .gen_ast()
+    .unwrap();
+    let AST::Function { body, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    let body = body.body();
+    assert!(matches!(
+        &body[0],
+        AST::VarDecl {
+            pattern: Pattern::Binding(name),
+            mutable: false,
+            kindof: Some(TypeAst::Primitive(_)),
+            ..
+        } if name == "a"
+    ));
+    assert!(matches!(
+        &body[1],
+        AST::VarDecl {
+            mutable: true,
+            kindof: Some(TypeAst::Tuple(types)),
+            ..
+        } if matches!(types[1], TypeAst::Option(_))
+    ));
+    assert!(matches!(&body[2], AST::VarDecl { kindof: None, .. }));
+}
+
+#[test]
+fn test_annotation_drives_literals() {
+    let out = transpile(
+        "function f(x: int?): float {
+            let a: float = 1;
+            let (b, c): (float, int?) = (2, 3);
+            let d: float? = 4;
+            let e: Result<float, int> = Ok(5);
+            let g: float = if x != None { 6 } else { 7.5 };
+            a
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("const a = 1;"));
+    assert!(out.contains("const [b, c] = [2,3];"));
+    //the bindings get the annotated types, so a float can be returned
+    assert!(transpile("function f(): float { let a: float = 1; a }").is_ok());
+    assert!(matches!(
+        transpile("function f(): float { let a = 1; a }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Float,
+            received: BeatriceType::Int,
+        })
+    ));
+    //only literals become floats, an int variable is still an int
+    assert!(matches!(
+        transpile("function f(x: int): float { let a: float = x; a }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Float,
+            received: BeatriceType::Int,
+        })
+    ));
+}
+
+#[test]
+fn test_expected_type_outside_lets() {
+    //the return type is expected of the returned value
+    assert!(transpile("function f(): float { return 5; }").is_ok());
+    //the types of a tuple are expected of its elements, the one of an option of its value
+    assert!(transpile("function f(): (float, float) = (1, 2);").is_ok());
+    assert!(transpile("function f(): float? = Some(3);").is_ok());
+    assert!(transpile("function f(): Result<float, string> = Ok(3);").is_ok());
+    //the value of the option is expected of the right side of a ??, which can be another option
+    assert!(transpile("function f(x: float?): float = x ?? 5;").is_ok());
+    assert!(transpile("function f(x: float?, y: float?): float? = x ?? y;").is_ok());
+    //explicit type arguments are expected of the arguments they type
+    let out = transpile(
+        "function id<T>(x: T): T = x;
+        function f(): float = id<float>(5);",
+    )
+    .unwrap();
+    assert!(out.contains("return id(5);"), "{out}");
+    assert!(matches!(
+        transpile("function f(x: float?): float = x ?? \"5\";"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Float,
+            received: BeatriceType::String,
+        })
+    ));
+}
+
+#[test]
+fn test_annotation_infers_generics() {
+    let out = transpile(&format!(
+        "{MAYBE}function f(): int {{
+            let m: Maybe<int> = Maybe::None;
+            let p: Pair<int?> = Pair {{ a: None, b: None }};
+            let q: Pair<float>? = Pair {{ a: 1, b: 2.5 }};
+            1
+        }}"
+    ))
+    .unwrap();
+    assert!(out.contains("const m = /**Maybe::None*/ {$:1};"));
+    assert!(out.contains("const p = Object.seal(/**Pair*/ {a:undefined,b:undefined})"));
+    //without the annotation nothing tells what T is
+    assert!(matches!(
+        transpile(&format!(
+            "{MAYBE}function f(): int {{ let m = Maybe::None; 1 }}"
+        )),
+        Err(TypeError::CannotInferGeneric { param, .. }) if param == "T"
+    ));
+}
+
+#[test]
+fn test_annotation_mismatch() {
+    assert!(matches!(
+        transpile("function f(): int { let a: int = 1.5; a }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Float,
+        })
+    ));
+    assert!(matches!(
+        transpile(&format!(
+            "{MAYBE}function f(): int {{ let m: Maybe<int> = Maybe::Some(1.5); 1 }}"
+        )),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Float,
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(x: int?): int { let a: int = x; a }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Option(_),
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(): int { let (a, b): (int, int) = (1, 2, 3); a }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Tuple(_),
+            received: BeatriceType::Tuple(_),
+        })
+    ));
+}
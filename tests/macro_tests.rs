@@ -0,0 +1,154 @@
+mod common;
+
+use beatrice::parser::{AST, AstErrorKind, Parser};
+
+fn transpile(input: &str) -> String {
+    common::generate(input).unwrap()
+}
+
+#[test]
+fn test_expression_macro_keeps_precedence() {
+    let out = transpile(
+        "macro square {
+            ($x:expr) -> ($x * $x);
+        }
+        function f(a: int): int = square!(a + 1) * 2;",
+    );
+    assert!(out.contains("return (a + 1) * (a + 1) * 2;"), "{out}");
+}
+
+#[test]
+fn test_repetition() {
+    let out = transpile(
+        "macro sum {
+            () -> (0);
+            ($($x:expr),+) -> (0 $(+ $x)*);
+        }
+        function f(a: int, b: int): int = sum!(a, b, 3) + sum!();",
+    );
+    assert!(out.contains("return 0 + a + b + 3 + 0;"), "{out}");
+    //a '+' followed by the operator is the separator
+    let out = transpile(
+        "macro names {
+            ($($x:ident)+*) -> (0 $(+ $x)*);
+        }
+        function f(a: int, b: int): int = names!(a + b);",
+    );
+    assert!(out.contains("return 0 + a + b;"), "{out}");
+}
+
+#[test]
+fn test_item_macro_with_ident_and_type_fragments() {
+    let program = Parser::from_content(
+        "macro constant {
+            ($name:ident, $t:type, $value:expr) -> { function $name(): $t = $value; }
+        }
+        constant!(five, int, 5)
+        constant!(half, float, 0.5)",
+    )
+    .gen_ast()
+    .unwrap();
+    let names = program
+        .body()
+        .iter()
+        .map(|ast| match ast {
+            AST::Function { name, .. } => name.as_str(),
+            _ => panic!("Expected a function, got {ast:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["five", "half"]);
+}
+
+#[test]
+fn test_hygiene() {
+    let out = transpile(
+        "macro double {
+            ($x:expr) -> { let tmp = $x; tmp + tmp }
+        }
+        function f(tmp: int): int {
+            double!(tmp * 2)
+        }",
+    );
+    assert!(out.contains("const tmp$g0 = tmp * 2;"), "{out}");
+    assert!(out.contains("return tmp$g0 + tmp$g0;"), "{out}");
+}
+
+#[test]
+fn test_hygiene_keeps_field_names() {
+    //only the bindings and their uses are renamed, not the fields and named arguments sharing
+    //their name
+    let out = transpile(
+        "struct Pair { tmp: int; other: int; }
+        function scale(value: int, tmp: int = 1): int = value * tmp;
+        macro pair {
+            ($x:expr) -> {
+                let tmp = $x;
+                let Pair { tmp: first, other } = Pair { tmp: tmp, other: tmp };
+                let copy = Pair { tmp, other };
+                scale(copy.tmp, tmp: tmp) + first
+            }
+        }
+        function f(tmp: int): int {
+            pair!(tmp)
+        }",
+    );
+    assert!(out.contains("const tmp$g0 = tmp;"), "{out}");
+    assert!(
+        out.contains(
+            "const {tmp: first$g0, other: other$g0} = /**Pair*/ {tmp:tmp$g0,other:tmp$g0}"
+        ),
+        "{out}"
+    );
+    //the shorthands of the renamed bindings are written out
+    assert!(
+        out.contains("const copy$g0 = Object.seal(/**Pair*/ {tmp:tmp$g0,other:other$g0})"),
+        "{out}"
+    );
+    assert!(
+        out.contains("return scale(copy$g0.tmp,tmp$g0) + first$g0;"),
+        "{out}"
+    );
+}
+
+#[test]
+fn test_errors_carry_expansion_trace() {
+    let errors = Parser::from_content(
+        "macro inner {
+            ($x:ident) -> ($x);
+        }
+        macro outer {
+            ($x:expr) -> (inner!($x));
+        }
+        function f(): int = outer!(1 + 2);",
+    )
+    .gen_ast()
+    .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0].kind,
+        AstErrorKind::NoMatchingMacroRule(_)
+    ));
+    let trace = errors[0]
+        .expansion_trace()
+        .iter()
+        .map(|e| e.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(trace, ["inner", "outer"]);
+
+    let errors = Parser::from_content("function f(): int = nothing!(1);")
+        .gen_ast()
+        .unwrap_err();
+    assert!(matches!(&errors[0].kind, AstErrorKind::UnknownMacro(name) if name == "nothing"));
+}
+
+#[test]
+fn test_recursive_macro() {
+    let out = transpile(
+        "macro count {
+            () -> (0);
+            ($head:tt $($rest:tt)*) -> (1 + count!($($rest)*));
+        }
+        function f(): int = count!(a b c);",
+    );
+    assert!(out.contains("return 1 + (1 + (1 + 0));"), "{out}");
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use beatrice::{
+    modules::{ModuleError, ModuleGraph},
+    parser::{AST, Parser},
+    transpiler::{Diagnostic, TypeError, transpiler::BeatriceTranspiler},
+};
+use common::write_modules;
+
+#[test]
+fn test_parse_import_and_export() {
+    let program = Parser::from_content(
+        "import { area, Rect } from \"./geometry\";
+        export function f(): int = 1;
+        export @Inline function g(): int = 2;",
+    )
+    .gen_ast()
+    .unwrap();
+    let AST::Import { names, path } = &program.body()[0] else {
+        panic!("Expected an import");
+    };
+    assert_eq!(names, &["area", "Rect"]);
+    assert_eq!(path, "./geometry");
+    assert!(matches!(&program.body()[1], AST::Export(f) if matches!(**f, AST::Function { .. })));
+    let AST::Export(g) = &program.body()[2] else {
+        panic!("Expected an export");
+    };
+    assert!(matches!(&**g, AST::Function { attributes, .. } if attributes[0].is("Inline")));
+}
+
+#[test]
+fn test_modules_become_es_modules() {
+    let dir = write_modules(
+        "es_modules",
+        &[
+            (
+                "main.bt",
+                "import { double, Pair } from \"./lib/math\";
+                function f(): int {
+                    let Pair { a, .. } = Pair { a: double(2), b: 1 };
+                    a
+                }",
+            ),
+            (
+                "lib/math.bt",
+                "import { one } from \"../one.bt\";
+                export struct Pair {
+                    a: int;
+                    b: int;
+                }
+                export function double(x: int): int = x * 2 + one() - 1;
+                function hidden(): int = 0;",
+            ),
+            ("one.bt", "export function one(): int = 1;"),
+        ],
+    );
+    let graph = ModuleGraph::load(dir.join("main.bt")).unwrap();
+    let names = graph
+        .modules()
+        .iter()
+        .map(|m| m.path.file_name().unwrap().to_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["one.bt", "math.bt", "main.bt"]);
+
+    let out = graph.transpile().unwrap();
+    assert!(out[0].contains("export function one(){"), "{}", out[0]);
+    assert!(
+        out[1].contains("import { one } from \"../one.js\";"),
+        "{}",
+        out[1]
+    );
+    assert!(out[1].contains("export function double(x){"), "{}", out[1]);
+    assert!(out[1].contains("\nfunction hidden(){"), "{}", out[1]);
+    //Pair is only a type, so it isn't imported at runtime
+    assert!(
+        out[2].contains("import { double } from \"./lib/math.js\";"),
+        "{}",
+        out[2]
+    );
+}
+
+#[test]
+fn test_module_scopes_are_separate() {
+    let dir = write_modules(
+        "separate_scopes",
+        &[
+            (
+                "main.bt",
+                "import { f } from \"./a\";
+                function g(): int = hidden();",
+            ),
+            (
+                "a.bt",
+                "export function f(): int = hidden();
+                function hidden(): int = 0;",
+            ),
+        ],
+    );
+    let result = ModuleGraph::load(dir.join("main.bt")).unwrap().transpile();
+    assert!(matches!(
+        result,
+        Err(ModuleError::Type { errors, .. })
+            if matches!(&errors[0].error, TypeError::NotRecognizedVar(name) if name == "hidden")
+    ));
+
+    let dir = write_modules(
+        "not_exported",
+        &[
+            ("main.bt", "import { hidden } from \"./a\";"),
+            ("a.bt", "function hidden(): int = 0;"),
+        ],
+    );
+    let result = ModuleGraph::load(dir.join("main.bt")).unwrap().transpile();
+    assert!(matches!(
+        result,
+        Err(ModuleError::Type { errors, .. })
+            if matches!(&errors[0].error, TypeError::NotExported { name, .. } if name == "hidden")
+    ));
+
+    let program = Parser::from_content("import { f } from \"./a\";")
+        .gen_ast()
+        .unwrap();
+    let result = BeatriceTranspiler::new("unused.js").generate(&program);
+    assert!(matches!(
+        result.as_ref().map_err(Vec::as_slice),
+        Err([Diagnostic { error: TypeError::UnresolvedModule(path), .. }]) if path == "./a"
+    ));
+}
+
+#[test]
+fn test_struct_ids_across_modules() {
+    //the Point held by Wrap isn't the one of main, although both are the first struct of their
+    //module
+    let dir = write_modules(
+        "struct_ids",
+        &[
+            (
+                "main.bt",
+                "import { Wrap } from \"./a\";
+                struct Point { x: int; }
+                function f(w: Wrap): Point = w.p;",
+            ),
+            (
+                "a.bt",
+                "struct Point { x: int; }
+                export struct Wrap { p: Point; }",
+            ),
+        ],
+    );
+    let result = ModuleGraph::load(dir.join("main.bt")).unwrap().transpile();
+    assert!(matches!(
+        result,
+        Err(ModuleError::Type { errors, .. })
+            if matches!(errors[0].error, TypeError::UnexpectedType { .. })
+    ));
+}
+
+#[test]
+fn test_import_cycles_and_missing_modules() {
+    let dir = write_modules(
+        "cycles",
+        &[
+            ("main.bt", "import { b } from \"./b\";"),
+            (
+                "b.bt",
+                "import { c } from \"./c\";\nexport function b(): int = 1;",
+            ),
+            (
+                "c.bt",
+                "import { b } from \"./b\";\nexport function c(): int = 1;",
+            ),
+        ],
+    );
+    let Err(ModuleError::Cycle(cycle)) = ModuleGraph::load(dir.join("main.bt")) else {
+        panic!("Expected an import cycle");
+    };
+    let names = cycle
+        .iter()
+        .map(|p| p.file_name().unwrap().to_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["b.bt", "c.bt", "b.bt"]);
+
+    let dir = write_modules("missing", &[("main.bt", "import { b } from \"./b\";")]);
+    assert!(matches!(
+        ModuleGraph::load(dir.join("main.bt")),
+        Err(ModuleError::Io { path, .. }) if path.ends_with("b.bt")
+    ));
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, Operator, Parser},
+    transpiler::{BeatriceType, TypeError},
+};
+use common::transpile;
+
+fn invalid(lhs: &str, op: &str, rhs: &str) -> Result<String, TypeError> {
+    transpile(&format!(
+        "function f(x: int, y: float, b: bool): int {{ let v = {lhs} {op} {rhs}; 1 }}"
+    ))
+}
+
+#[test]
+fn test_parse_power_and_bools() {
+    let program = Parser::from_content("function f(): bool { let p = 2 * 3 ** 2 ** 2; true }")
+        .gen_ast()
+        .unwrap();
+    let AST::Function { body, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    let body = body.body();
+    let AST::VarDecl { body: power, .. } = &body[0] else {
+        panic!("Expected a let");
+    };
+    //2 * (3 ** (2 ** 2))
+    let AST::BinExpr(_, rhs, Operator::Star(false)) = &**power else {
+        panic!("Expected a product, got {power:?}");
+    };
+    let AST::BinExpr(_, exponent, Operator::Pow(false)) = &**rhs else {
+        panic!("Expected a power, got {rhs:?}");
+    };
+    assert!(matches!(
+        **exponent,
+        AST::BinExpr(_, _, Operator::Pow(false))
+    ));
+    assert!(matches!(&body[1], AST::Return(Some(b)) if matches!(**b, AST::Bool(true))));
+}
+
+#[test]
+fn test_operator_types() {
+    let out = transpile(
+        "function between(x: int, lo: int, hi: int): bool = x >= lo && x <= hi;
+        function f(x: int, y: float, s: string, o: int?): bool {
+            let a: int = x % 3 + (x << 2) ^ 1;
+            let b: float = y * 2.5 - y / 0.5;
+            let c: string = s + \"!\";
+            let d: bool = s < c || o != None && x == 1;
+            between(a, 1, 9) && d
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("return x >= lo && x <= hi;"));
+    assert!(out.contains("const d = s < c || o != undefined && x == 1;"));
+    //bounded generics can use the operators of their bound
+    assert!(
+        transpile("function add<T>(a: T, b: T): T where T extends Integer = a + b & b;").is_ok()
+    );
+    assert!(matches!(
+        transpile("function add<T>(a: T, b: T): T where T extends Numeric = a & b;"),
+        Err(TypeError::InvalidOperands {
+            operator: Operator::BitAnd(false),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_invalid_operands() {
+    let cases = [
+        ("1", "+", "2.5", Operator::Add(false)),
+        ("x", "%", "y", Operator::Mod(false)),
+        ("y", "**", "2", Operator::Pow(false)),
+        ("b", "&&", "1", Operator::And(false)),
+        ("x", "&", "2.5", Operator::BitAnd(false)),
+        ("y", "<<", "1", Operator::BitLeft(false)),
+        ("1", "<", "b", Operator::Lt(false)),
+        ("x", "==", "y", Operator::Eq(true)),
+        ("b", "-", "b", Operator::Sub(false)),
+    ];
+    for (lhs, op, rhs, operator) in cases {
+        let result = invalid(lhs, op, rhs);
+        assert!(
+            matches!(&result, Err(TypeError::InvalidOperands { operator: o, .. }) if *o == operator),
+            "{lhs} {op} {rhs}: {result:?}"
+        );
+    }
+    assert!(matches!(
+        invalid("1", "+", "2.5"),
+        Err(TypeError::InvalidOperands {
+            lhs: BeatriceType::Int,
+            rhs: BeatriceType::Float,
+            ..
+        })
+    ));
+    //the result of a comparison is a bool, not the type of its operands
+    assert!(matches!(
+        transpile("function f(x: int): int = x > 1;"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Bool,
+        })
+    ));
+}
+
+#[test]
+fn test_integer_division_and_power() {
+    let out = transpile(
+        "function f(a: int, b: int, x: float): float {
+            let q = a / b + a ** 2;
+            let r = 2 * (a / b);
+            x / 2.5 + x ** 0.5
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("const q = Math.trunc(a / b) + Math.trunc(a ** 2);"));
+    assert!(out.contains("const r = 2 * Math.trunc(a / b);"));
+    assert!(out.contains("return x / 2.5 + x ** 0.5;"));
+    //a generic number is divided as an integer or as a float only if its bound says which
+    let out = transpile(
+        "function div<T>(a: T, b: T): T where T extends Integer = a / b;
+        function root<T>(a: T, b: T): T where T extends Floating = a ** b;",
+    )
+    .unwrap();
+    assert!(out.contains("return Math.trunc(a / b);"), "{out}");
+    assert!(out.contains("return a ** b;"), "{out}");
+    for op in ["/", "**"] {
+        let result = transpile(&format!(
+            "function div<T>(a: T, b: T): T where T extends Numeric = a {op} b;"
+        ));
+        assert!(
+            matches!(result, Err(TypeError::InvalidOperands { .. })),
+            "{op}: {result:?}"
+        );
+    }
+}
+
+#[test]
+fn test_js_precedence() {
+    //the bitwise operators bind tighter than the comparisons in beatrice, and looser in js. The
+    //ones of i32s are the ones of js
+    let out = transpile(
+        "function f(a: i32, b: i32, o: bool?, c: bool): i32 {
+            let e = a == b & 1;
+            let x = a ^ b | 1;
+            let y = a & 3 | 4 ^ 1;
+            let z = a < b == c;
+            let n = o ?? c || c;
+            x + y
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("const e = a == (b & 1);"), "{out}");
+    assert!(out.contains("const x = a ^ (b | 1);"), "{out}");
+    assert!(out.contains("const y = (a & 3 | 4) ^ 1;"), "{out}");
+    //equality binds looser than the other comparisons in js, as loose as them in beatrice
+    assert!(out.contains("const z = a < b == c;"), "{out}");
+    //js rejects a ?? next to a || without parenthesis
+    assert!(out.contains("const n = (o ?? c) || c;"), "{out}");
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, Operator, Parser, TypeAst},
+    transpiler::{BeatriceType, TypeError},
+};
+use common::transpile;
+
+const PAIR: &str = "struct Pair<T> {
+    a: T;
+    b: T;
+}
+";
+
+#[test]
+fn test_parse_options() {
+    let option = Parser::from_content("(int, float)?").get_type().unwrap();
+    assert!(matches!(option, TypeAst::Option(inner) if matches!(*inner, TypeAst::Tuple(_))));
+    //int?? tokenizes its end as the ?? operator
+    let nested = Parser::from_content("int??").get_type().unwrap();
+    assert!(matches!(nested, TypeAst::Option(inner) if matches!(*inner, TypeAst::Option(_))));
+
+    let program = Parser::from_content(
+        "function f(p: Pair<int>?): int {
+            let none = None;
+            p?.a ?? Some(1).0
+        }",
+    )
+    .gen_ast()
+    .unwrap();
+    let AST::Function { body, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    let body = body.body();
+    assert!(matches!(&body[0], AST::VarDecl { body, .. } if matches!(**body, AST::None)));
+    let AST::Return(Some(ret)) = &body[1] else {
+        panic!("Expected the returned expression");
+    };
+    let AST::BinExpr(lhs, rhs, Operator::Coalesce) = &**ret else {
+        panic!("Expected a ??, got {ret:?}");
+    };
+    assert!(matches!(
+        &**lhs,
+        AST::FieldAccess { field, optional: true, .. } if field == "a"
+    ));
+    assert!(matches!(
+        &**rhs,
+        AST::TupleIndex { expr, optional: false, .. } if matches!(**expr, AST::Some(_))
+    ));
+}
+
+#[test]
+fn test_option_emission() {
+    let out = transpile(&format!(
+        "{PAIR}function first(p: Pair<int>?, d: int): int {{
+            p?.a ?? d
+        }}
+        function second(t: (int, int)?): int? {{
+            t?.1
+        }}
+        function call(): int {{
+            first(None, 1) + first(Pair {{ a: 2, b: 3 }}, 4)
+        }}"
+    ))
+    .unwrap();
+    assert!(out.contains("return p?.a ?? d;"));
+    assert!(out.contains("return t?.[1];"));
+    assert!(out.contains("first(undefined,1)"));
+}
+
+#[test]
+fn test_option_refused_as_value() {
+    assert!(matches!(
+        transpile("function f(x: int?): int { x }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Option(_),
+        })
+    ));
+    assert!(matches!(
+        transpile("function g(x: int): int { x } function f(x: int?): int { g(x) }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            ..
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(x: int): int { x ?? 1 }"),
+        Err(TypeError::ExpectedOption(BeatriceType::Int))
+    ));
+    //a value can be given where an option is expected
+    assert!(
+        transpile("function f(x: int): int? { x } function g(): int? { f(1) ?? None }").is_ok()
+    );
+}
+
+#[test]
+fn test_option_narrowing_and_match() {
+    let out = transpile(
+        "function f(x: int?): int {
+            let y = if x != None { x + 1 } else { 0 };
+            y
+        }
+        function g(x: int?): int = match x {
+            Some(n) if n > 0 -> n,
+            Some(_) -> 0,
+            None -> 1,
+        };",
+    )
+    .unwrap();
+    assert!(out.contains("x != undefined"));
+    assert!(out.contains("if(x !== undefined){"));
+    assert!(out.contains("const n = x;"));
+    assert!(out.contains("x === undefined"));
+
+    assert!(transpile("function f(x: int?): int { if x == None { 0 } else { x } }").is_ok());
+    assert!(matches!(
+        transpile("function f(x: int?): int { if x == None { x } else { 0 } }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Option(_),
+        })
+    ));
+    //the pattern binds the name again, so its variable is renamed instead of copying the value
+    let out =
+        transpile("function f(x: int?): int = match x { Some(x) -> x, None -> 0, };").unwrap();
+    assert!(out.contains("const x$1 = x;"));
+    assert!(out.contains("return x$1;"));
+    assert!(matches!(
+        transpile("function f(x: int?): int = match x { Some(n) -> n, };"),
+        Err(TypeError::NonExhaustiveMatch { missing }) if missing == "None"
+    ));
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use beatrice::transpiler::{BeatriceType, TypeError};
+use common::transpile;
+
+#[test]
+fn test_shadowing() {
+    //each let is a new variable, with a type of its own
+    assert!(transpile("function f(): float { let x = 1; let x = 2.5; x }").is_ok());
+    assert!(matches!(
+        transpile("function f(): int { let x = 1; let x = 2.5; x }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Float,
+        })
+    ));
+    //the value of a let still sees the variable it shadows
+    assert!(transpile("function f(x: int): float { let x = x as float; x * 0.5 }").is_ok());
+    //a parameter holding a function hides the global function with its name
+    assert!(
+        transpile(
+            "function id<T>(x: T): T = x;
+            function f(id: (int): int, x: int): int = id(x);"
+        )
+        .is_ok()
+    );
+    //and neither its generics nor its parameters are taken by the calls of the parameter
+    assert!(matches!(
+        transpile(
+            "function id<T>(x: T): T = x;
+            function f(id: (int): int): int = id<float>(1);"
+        ),
+        Err(TypeError::WrongGenericsAmount { expected: 0, .. })
+    ));
+    assert!(matches!(
+        transpile(
+            "function add(a: int, b: int = 1): int = a + b;
+            function f(add: (int, int): int): int = add(1);"
+        ),
+        Err(TypeError::ArityMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_block_scopes() {
+    assert!(matches!(
+        transpile("function f(): int { let y = { let inner = 1; inner }; inner }"),
+        Err(TypeError::NotRecognizedVar(name)) if name == "inner"
+    ));
+    assert!(matches!(
+        transpile("function f(o: int?): int = match o { Some(v) -> v, None -> v };"),
+        Err(TypeError::NotRecognizedVar(name)) if name == "v"
+    ));
+    //a variable narrowed by an if keeps its type once shadowed inside it
+    assert!(
+        transpile("function f(x: int?): float = if x != None { let x = 0.5; x } else { 1.0 };")
+            .is_ok()
+    );
+    //the variables of a function aren't visible to the others
+    assert!(matches!(
+        transpile("function f(a: int): int = a; function g(): int = a;"),
+        Err(TypeError::NotRecognizedVar(name)) if name == "a"
+    ));
+}
+
+#[test]
+fn test_use_before_definition() {
+    assert!(matches!(
+        transpile("function f(): int { let y = z; let z = 1; y }"),
+        Err(TypeError::UseBeforeDefinition(name)) if name == "z"
+    ));
+    assert!(matches!(
+        transpile("function f(): int { let x = x + 1; x }"),
+        Err(TypeError::UseBeforeDefinition(name)) if name == "x"
+    ));
+    //a variable defined further down a block doesn't hide the one defined before it
+    assert!(transpile("function f(z: int): int { let y = z; let z = 1; y + z }").is_ok());
+}
+
+#[test]
+fn test_duplicate_definitions() {
+    let duplicate = |input: &str| match transpile(input) {
+        Err(TypeError::DuplicateDefinition(name)) => name,
+        other => panic!("Expected a duplicate definition, got {other:?}"),
+    };
+    assert_eq!(duplicate("function f(a: int, a: int): int = a;"), "a");
+    assert_eq!(
+        duplicate("function f(): int { let (a, a) = (1, 2); a }"),
+        "a"
+    );
+    assert_eq!(
+        duplicate("function f(): int = 1; function f(): int = 2;"),
+        "f"
+    );
+    assert_eq!(duplicate("struct P { x: int; } enum P { A, B }"), "P");
+    //the first function keeps the name, and the body of the other isn't checked against it
+    assert_eq!(
+        duplicate(
+            "function f(): int = 1;
+            function f(): float = 1.0;
+            function g(): int = f() + 1;"
+        ),
+        "f"
+    );
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, Parser, Pattern},
+    transpiler::{BeatriceType, TypeError},
+};
+use common::transpile;
+
+const PARSE: &str = "function parse(x: int): Result<int, float> = match x {
+    0 -> Err(1.5),
+    n -> Ok(n),
+};
+";
+
+#[test]
+fn test_parse_results() {
+    let program = Parser::from_content(
+        "function f(r: Result<int, float>): int = match r {
+            Ok(n) -> n,
+            Err(_) -> (g(r)?).0,
+        };",
+    )
+    .gen_ast()
+    .unwrap();
+    let AST::Function { body, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    let AST::Return(Some(ret)) = &body.body()[0] else {
+        panic!("Expected the returned expression");
+    };
+    let AST::Match { arms, .. } = &**ret else {
+        panic!("Expected a match");
+    };
+    assert!(matches!(&arms[0].pattern, Pattern::Ok(n) if matches!(**n, Pattern::Binding(_))));
+    assert_eq!(arms[1].pattern.to_string(), "Err(_)");
+    let AST::TupleIndex { expr, .. } = &arms[1].body else {
+        panic!("Expected a tuple index, got {:?}", arms[1].body);
+    };
+    assert!(matches!(&**expr, AST::Try(call) if matches!(**call, AST::FunctionCall { .. })));
+}
+
+#[test]
+fn test_try_emission() {
+    let out = transpile(&format!(
+        "{PARSE}function sum(x: int, y: int): Result<int, float> {{
+            let a = parse(x)?;
+            Ok(a + parse(y)?)
+        }}"
+    ))
+    .unwrap();
+    assert!(out.contains("return {ok:false,error:1.5};"));
+    assert!(out.contains(
+        "    const $try1 = parse(x);
+    if(!$try1.ok) return $try1;
+    const a = $try1.value;
+    const $try2 = parse(y);
+    if(!$try2.ok) return $try2;
+    return {ok:true,value:a + $try2.value};"
+    ));
+}
+
+#[test]
+fn test_try_keeps_operators_lazy() {
+    //parse is only called, and its error returned, when the left side doesn't decide the value
+    let out = transpile(&format!(
+        "{PARSE}function positive(x: int): Result<bool, float> = Ok(x > 0 && parse(x)? > 1);
+        function or_parse(o: int?, x: int): Result<int, float> = Ok(o ?? parse(x)?);"
+    ))
+    .unwrap();
+    assert!(
+        out.contains(
+            "    let $lazy2 = x > 0;
+    if($lazy2){
+        const $try1 = parse(x);
+        if(!$try1.ok) return $try1;
+        $lazy2 = $try1.value > 1;
+    }
+    return {ok:true,value:$lazy2};"
+        ),
+        "{out}"
+    );
+    assert!(
+        out.contains(
+            "    let $lazy4 = o;
+    if($lazy4 === undefined){
+        const $try3 = parse(x);
+        if(!$try3.ok) return $try3;
+        $lazy4 = $try3.value;
+    }
+    return {ok:true,value:$lazy4};"
+        ),
+        "{out}"
+    );
+}
+
+#[test]
+fn test_try_needs_compatible_result() {
+    assert!(matches!(
+        transpile(&format!("{PARSE}function f(): int {{ parse(1)? }}")),
+        Err(TypeError::IncompatibleTry {
+            return_type: BeatriceType::Int,
+            error: BeatriceType::Float,
+        })
+    ));
+    assert!(matches!(
+        transpile(&format!(
+            "{PARSE}function f(): Result<int, int> {{ Ok(parse(1)?) }}"
+        )),
+        Err(TypeError::IncompatibleTry {
+            return_type: BeatriceType::Result { .. },
+            error: BeatriceType::Float,
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(x: int): Result<int, int> { Ok(x?) }"),
+        Err(TypeError::ExpectedResult(BeatriceType::Int))
+    ));
+    //the value type of the propagated result doesn't matter, only its error
+    assert!(
+        transpile(&format!(
+            "{PARSE}function f(): Result<(int, int), float> {{ Ok((parse(1)?, 2)) }}"
+        ))
+        .is_ok()
+    );
+}
+
+#[test]
+fn test_result_match() {
+    let out = transpile(&format!(
+        "{PARSE}function f(x: int): int = match parse(x) {{
+            Ok(n) -> n,
+            Err(_) -> 0,
+        }};"
+    ))
+    .unwrap();
+    assert!(out.contains("const $match1 = parse(x);"));
+    assert!(out.contains("if($match1.ok){"));
+    assert!(out.contains("const n = $match1.value;"));
+    assert!(out.contains("if(!$match1.ok){"));
+    assert!(matches!(
+        transpile(&format!(
+            "{PARSE}function f(x: int): int = match parse(x) {{ Ok(n) -> n, }};"
+        )),
+        Err(TypeError::NonExhaustiveMatch { missing }) if missing == "Err(_)"
+    ));
+}
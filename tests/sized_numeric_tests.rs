@@ -0,0 +1,197 @@
+mod common;
+
+use beatrice::{
+    parser::Operator,
+    transpiler::{BeatriceType, TypeError},
+};
+use common::transpile;
+
+#[test]
+fn test_sized_types() {
+    let out = transpile(
+        "struct Pixel { r: u8; g: u8; b: u8; }
+        function gray(level: u8): u8 { let p = Pixel { r: level, g: 0, b: 255 }; level }
+        function f(a: i64, b: f64, c: u16?): int {
+            let p = gray(255);
+            let d: u16 = c ?? 1000;
+            let x: i8 = 127;
+            let y: u64 = 9007199254740991;
+            a
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("const p = gray(255);"));
+    assert!(matches!(
+        transpile("struct Pixel { r: u8; } function f(): int { let p = Pixel { r: 256 }; 1 }"),
+        Err(TypeError::InvalidFieldValue { .. })
+    ));
+    //i64 and f64 are int and float
+    assert!(transpile("function f(a: i64, b: f64): int { let c: float = b; a }").is_ok());
+    assert!(matches!(
+        transpile("function f(a: u8): u16 = a;"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::U16,
+            received: BeatriceType::U8,
+        })
+    ));
+    //literals must fit in the type they are given to
+    assert!(matches!(
+        transpile("function f(): u8 = 256;"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::U8,
+            received: BeatriceType::Int,
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(a: u8): u8 = a + 300;"),
+        Err(TypeError::InvalidOperands {
+            operator: Operator::Add(false),
+            ..
+        })
+    ));
+    assert!(matches!(
+        transpile("function f(a: i32): i32 { let b: i32 = 2147483648; a }"),
+        Err(TypeError::UnexpectedType { .. })
+    ));
+}
+
+#[test]
+fn test_wrapping_arithmetic() {
+    let out = transpile(
+        "function add8(a: u8, b: u8): u8 = a + b;
+        function addi8(a: i8, b: i8): i8 = a + b * 2;
+        function shl16(a: i16): i16 = a << 3;
+        function sum32(a: i32, b: i32): i32 = a * b + 1;
+        function mul32(a: u32, b: u32): u32 = a * b;
+        function shr32(a: u32, b: u32): u32 = a >> b;
+        function or32(a: u32, b: u32): u32 = a | b;
+        function div(a: i32, b: i32): bool = a / b < 10 && a % b == 1;
+        function sub64(a: u64, b: u64): u64 = a - b;
+        function shl64(a: u64, b: u64): u64 = a << b;
+        function rem64(a: u64, b: u64): u64 = a % b;",
+    )
+    .unwrap();
+    assert!(out.contains("return a + b & 0xFF;"));
+    //every operation wraps, not only the last one
+    assert!(out.contains("return a + (b * 2 << 24 >> 24) << 24 >> 24;"));
+    assert!(out.contains("return (a << 3) << 16 >> 16;"));
+    //the product of two i32 can be past 2^53, where js numbers lose precision
+    assert!(out.contains("return Math.imul(a, b) + 1 | 0;"));
+    assert!(out.contains("return Math.imul(a, b) >>> 0;"));
+    assert!(out.contains("return a >>> b;"));
+    assert!(out.contains("return (a | b) >>> 0;"));
+    //divisions truncate as every integer division does, modulo is always in range
+    assert!(out.contains("return (a / b | 0) < 10 && a % b == 1;"));
+    //an u64 is computed on bigints, so it can't go negative
+    assert!(out.contains("return Number(BigInt.asUintN(64, BigInt(a) - BigInt(b)));"));
+    assert!(out.contains("return Number(BigInt.asUintN(64, BigInt(a) << (BigInt(b) & 63n)));"));
+    assert!(out.contains("return a % b;"));
+}
+
+#[test]
+fn test_sized_powers() {
+    //3 ** 40 is past 2^53, so wrapping its js number would give 689956864 instead of 689956897
+    let out = transpile(
+        "function pow32(a: i32, b: i32): i32 = a ** b;
+        function pow8(a: u8, b: u8): u8 = a ** b;
+        function powi8(a: i8, b: i8): i8 = a ** b + 1;",
+    )
+    .unwrap();
+    assert!(out.contains("return Number(BigInt.asIntN(32, BigInt(a) ** BigInt(b)));"));
+    assert!(out.contains("return Number(BigInt.asUintN(8, BigInt(a) ** BigInt(b)));"));
+    assert!(
+        out.contains("return Number(BigInt.asIntN(8, BigInt(a) ** BigInt(b))) + 1 << 24 >> 24;")
+    );
+}
+
+#[test]
+fn test_int_bitwise_operators() {
+    //js bitwise operators only keep 32 bits, so 1 << 33 would be 2 and 2^40 + 3 & 2^40 + 1 would
+    //be 1
+    let out = transpile(
+        "function shl(a: int, b: int): int = a << b;
+        function shr(a: int, b: int): int = a >> b;
+        function and(a: int, b: int): int = a & b;
+        function mixed(a: int, b: int): int = a ^ b | 1;",
+    )
+    .unwrap();
+    assert!(out.contains("return Number(BigInt.asIntN(64, BigInt(a) << (BigInt(b) & 63n)));"));
+    assert!(out.contains("return Number(BigInt.asIntN(64, BigInt(a) >> (BigInt(b) & 63n)));"));
+    assert!(out.contains("return Number(BigInt.asIntN(64, BigInt(a) & BigInt(b)));"));
+    assert!(out.contains(
+        "return Number(BigInt.asIntN(64, BigInt(a) ^ BigInt(Number(BigInt.asIntN(64, BigInt(b) | BigInt(1))))));"
+    ));
+    //the operators of the sized integers are still the ones of js, which keep them in range
+    let out = transpile("function f(a: i32, b: i32): i32 = a & b;").unwrap();
+    assert!(out.contains("return a & b;"));
+}
+
+#[test]
+fn test_single_precision() {
+    let out = transpile(
+        "function f(a: f32, b: f32): f32 {
+            let c: f32 = 0.1;
+            let d: f32 = 0.5;
+            let e: f32 = 16777217;
+            a * 1.1 + b / c
+        }",
+    )
+    .unwrap();
+    //the literals f32 can't hold are rounded, 0.5 and most ints can be held as they are
+    assert!(out.contains("const c = Math.fround(0.1);"));
+    assert!(out.contains("const d = 0.5;"));
+    assert!(out.contains("const e = Math.fround(16777217);"));
+    assert!(
+        out.contains("return Math.fround(Math.fround(a * Math.fround(1.1)) + Math.fround(b / c));")
+    );
+    assert!(matches!(
+        transpile("function f(a: f32, b: float): f32 = a + b;"),
+        Err(TypeError::InvalidOperands {
+            lhs: BeatriceType::F32,
+            rhs: BeatriceType::Float,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_sized_casts() {
+    let out = transpile(
+        "function f(x: float, n: int, s: i8, u: u16, h: f32): u8 {
+            let a = x as u8;
+            let b = n as u32;
+            let c = n as i16 * 2;
+            let d = s as i32 + u as i32;
+            let e = x as f32;
+            let g = h as float;
+            let i = u as f32;
+            let l = 300 as u8;
+            let m = s as u64;
+            let o = u as u64;
+            2 as u8
+        }",
+    )
+    .unwrap();
+    //to a sized integer, values wrap around its range
+    assert!(out.contains("const a = x & 0xFF;"));
+    assert!(out.contains("const b = n >>> 0;"));
+    assert!(out.contains("const c = (n << 16 >> 16) * 2 << 16 >> 16;"));
+    //unless every value of the original type is already in it
+    assert!(out.contains("const d = s + u | 0;"));
+    assert!(out.contains("const e = Math.fround(x);"));
+    assert!(out.contains("const g = h;"));
+    assert!(out.contains("const i = u;"));
+    assert!(out.contains("const l = 300 & 0xFF;"));
+    assert!(
+        out.contains("const m = Number(BigInt.asUintN(64, BigInt(Math.trunc(s) % 2 ** 64 || 0)));")
+    );
+    assert!(out.contains("const o = u;"));
+    assert!(out.contains("return 2;"));
+    //generic integers are emitted as ints
+    let out = transpile(
+        "function half<T>(x: T, two: T): T where T extends Integer = x / two;
+        function f(a: int): int = half(a, 2);",
+    )
+    .unwrap();
+    assert!(out.contains("return Math.trunc(x / two);"));
+}
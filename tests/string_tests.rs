@@ -0,0 +1,137 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, AstErrorKind, Parser, StringPart},
+    transpiler::{BeatriceType, TypeError},
+};
+use common::transpile;
+
+const POINT: &str = "@Display(\"({x}, {y})\")
+struct Point {
+    x: int;
+    y: float;
+}
+";
+
+#[test]
+fn test_parse_interpolation() {
+    let program = Parser::from_content(
+        "function f(x: int): string {
+            let plain = \"no values\";
+            \"x = {x + 1}, p = {Point { x: 1 }:?} {{x}}\"
+        }",
+    )
+    .gen_ast()
+    .unwrap();
+    let AST::Function { body, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    let body = body.body();
+    assert!(
+        matches!(&body[0], AST::VarDecl { body, .. } if matches!(&**body, AST::Str(s) if s == "no values"))
+    );
+    let AST::Return(Some(ret)) = &body[1] else {
+        panic!("Expected the returned expression");
+    };
+    let AST::Interpolated(parts) = &**ret else {
+        panic!("Expected an interpolated string, got {ret:?}");
+    };
+    assert_eq!(parts.len(), 5);
+    assert!(matches!(
+        &parts[1],
+        StringPart::Expr { expr, debug: false } if matches!(**expr, AST::BinExpr(..))
+    ));
+    assert!(matches!(
+        &parts[3],
+        StringPart::Expr { expr, debug: true } if matches!(**expr, AST::StructExpr { .. })
+    ));
+    assert!(matches!(&parts[4], StringPart::Literal(s) if s == " {x}"));
+
+    for invalid in ["\"{1 +}\"", "\"a } b\"", "\"{x\""] {
+        let errors = Parser::from_content(&format!("function f(x: int): string {{ {invalid} }}"))
+            .gen_ast()
+            .unwrap_err();
+        assert!(matches!(
+            errors[0].kind,
+            AstErrorKind::InvalidInterpolation(_)
+        ));
+    }
+}
+
+#[test]
+fn test_interpolation_emission() {
+    let out = transpile(
+        "function f(x: int, s: string): string {
+            \"x = {x}, s = {s:?}, `{x * 2}` costs ${{s}}\"
+        }",
+    )
+    .unwrap();
+    assert!(
+        out.contains("return `x = ${x}, s = ${JSON.stringify(s)}, \\`${x * 2}\\` costs \\${s}`;")
+    );
+    assert!(matches!(
+        transpile("function f(t: (int, int)): string { \"{t}\" }"),
+        Err(TypeError::NotDisplayable {
+            kind: BeatriceType::Tuple(_),
+            debug: false,
+        })
+    ));
+}
+
+#[test]
+fn test_display_helpers() {
+    let out = transpile(&format!(
+        "{POINT}@Display
+        struct Label {{
+            text: string;
+            size: int;
+        }}
+        function f(): string {{
+            let p = Point {{ x: 1, y: 2.5 }};
+            let l = Label {{ text: \"a\", size: 2 }};
+            \"{{p}} {{p:?}} {{l}}\"
+        }}"
+    ))
+    .unwrap();
+    assert!(out.contains(
+        "function Point$debug({x, y}){
+    return `Point { x: ${x}, y: ${y} }`;
+}
+function Point$display({x, y}){
+    return `(${x}, ${y})`;
+}"
+    ));
+    assert!(out.contains("return `Label { text: ${JSON.stringify(text)}, size: ${size} }`;"));
+    assert!(out.contains("return Label$debug({text, size});"));
+    assert!(out.contains("return `${Point$display(p)} ${Point$debug(p)} ${Label$display(l)}`;"));
+}
+
+#[test]
+fn test_display_protocol_checks() {
+    //structs are only displayable when they opt in
+    assert!(matches!(
+        transpile(
+            "struct P { x: int; }
+            function f(): string { let p = P { x: 1 }; \"{p:?}\" }"
+        ),
+        Err(TypeError::NotDisplayable {
+            kind: BeatriceType::Struct { .. },
+            debug: true,
+        })
+    ));
+    assert!(matches!(
+        transpile("@Display struct P { x: (int, int); }"),
+        Err(TypeError::NotDisplayable { debug: true, .. })
+    ));
+    assert!(matches!(
+        transpile("@Display(5) struct P { x: int; }"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::String,
+            received: BeatriceType::Int,
+        })
+    ));
+    assert!(matches!(
+        transpile("@Display(\"{z}\") struct P { x: int; }"),
+        Err(TypeError::NotRecognizedVar(name)) if name == "z"
+    ));
+}
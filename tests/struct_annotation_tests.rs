@@ -0,0 +1,164 @@
+mod common;
+
+use beatrice::transpiler::{BeatriceType, TypeError};
+use common::{errors, transpile};
+
+fn struct_name(kind: &BeatriceType) -> &str {
+    let BeatriceType::Struct { name, .. } = kind else {
+        panic!("Expected a struct type, got {kind:?}");
+    };
+    name
+}
+
+const SHAPES: &str = "struct Rect { origin: Point; size: Size; }
+struct Point { x: float; y: float; }
+struct Size { w: float; h: float; }
+";
+
+#[test]
+fn test_struct_signatures() {
+    let out = transpile(&format!(
+        "{SHAPES}function area(r: Rect): float = r.size.w * r.size.h;
+        function origin(r: Rect): Point = r.origin;
+        function square(side: float): Rect = Rect {{
+            origin: Point {{ x: 0.0, y: 0.0 }},
+            size: Size {{ w: side, h: side }}
+        }};
+        function f(): float = area(square(2.0)) + origin(square(1.0)).x;"
+    ))
+    .unwrap();
+    assert!(out.contains("return r.size.w * r.size.h;"));
+    assert!(out.contains("return area(square(2)) + origin(square(1)).x;"));
+    let err = transpile(&format!(
+        "{SHAPES}function area(r: Rect): float = r.size.w;
+        function f(): float = area(Size {{ w: 1.0, h: 1.0 }});"
+    ))
+    .unwrap_err();
+    let TypeError::UnexpectedType { expected, received } = &err else {
+        panic!("Expected an unexpected type, got {err:?}");
+    };
+    assert_eq!(
+        (struct_name(expected), struct_name(received)),
+        ("Rect", "Size")
+    );
+    assert!(matches!(
+        transpile("function f(r: Rectangle): int = 1;"),
+        Err(TypeError::NotRecognizedType(name)) if name == "Rectangle"
+    ));
+}
+
+#[test]
+fn test_nested_struct_fields() {
+    let out = transpile(&format!(
+        "{SHAPES}function f(x: float): float {{
+            let r = Rect {{ origin: Point {{ x: x, y: 0.0 }}, size: Size {{ w: 2.0, h: 3.0 }} }};
+            let Rect {{ origin: Point {{ x, .. }}, .. }} = r;
+            x + r.origin.y
+        }}"
+    ))
+    .unwrap();
+    assert!(out.contains("{origin:/**Point*/ {x,y:0},size:/**Size*/ {w:2,h:3}}"));
+    //the x of the pattern shadows the parameter, so it's renamed
+    assert!(out.contains("const {origin: {x: x$1}} = r;"));
+    let err = transpile(&format!(
+        "{SHAPES}function f(): Rect = Rect {{
+            origin: Size {{ w: 1.0, h: 1.0 }},
+            size: Size {{ w: 1.0, h: 1.0 }}
+        }};"
+    ))
+    .unwrap_err();
+    let TypeError::InvalidFieldValue {
+        target,
+        field,
+        expected,
+        received,
+    } = &err
+    else {
+        panic!("Expected an invalid field value, got {err:?}");
+    };
+    assert_eq!((target.as_str(), field.as_str()), ("Rect", "origin"));
+    assert_eq!(
+        (struct_name(expected), struct_name(received)),
+        ("Point", "Size")
+    );
+    assert!(matches!(
+        transpile(&format!(
+            "{SHAPES}function f(): Rect = Rect {{ origin: Point {{ x: 1.0 }}, size: Size {{ w: 1.0, h: 1.0 }} }};"
+        )),
+        Err(TypeError::NotCorrectFields { target, fields }) if target == "Point" && fields == ["y"]
+    ));
+    assert!(matches!(
+        transpile(&format!("{SHAPES}function f(r: Rect): float = r.size.x;")),
+        Err(TypeError::InvalidFieldAccess { field, .. }) if field == "x"
+    ));
+}
+
+#[test]
+fn test_declaration_order() {
+    //structs can be used above the line declaring them, by functions, enums and other structs
+    let out = transpile(
+        "function make(): Line = Line { a: Point { x: 1, y: 2 }, b: Point { x: 3, y: 4 }, style: Style::Solid };
+        enum Shape { Dot(Point), Segment(Line) }
+        struct Line { a: Point; b: Point; style: Style; }
+        struct Point { x: int; y: int; }
+        enum Style { Solid, Dashed }",
+    );
+    assert!(out.is_ok());
+    //cycles are found whichever struct is declared first, and reported once, for the first struct
+    //in them
+    let found = errors(
+        "struct A { b: B; } struct B { x: int; a: A; }
+        struct C { c: C; d: D; } struct D { c: C; }",
+    )
+    .into_iter()
+    .map(|diagnostic| match diagnostic.error {
+        TypeError::InfinitelySized { target, fields } => (target, fields),
+        error => panic!("Expected an infinitely sized struct, got {error:?}"),
+    })
+    .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            ("A".to_string(), vec!["b".to_string(), "a".to_string()]),
+            ("C".to_string(), vec!["c".to_string()]),
+            ("D".to_string(), vec!["c".to_string(), "d".to_string()]),
+        ]
+    );
+    assert!(transpile("struct A { b: B; } struct B { a: A?; }").is_ok());
+}
+
+#[test]
+fn test_generic_struct_annotations() {
+    let out = transpile(
+        "function first(p: Pair<Point>): Point = p.a;
+        struct Pair<T> { a: T; b: T; }
+        struct Point { x: int; y: int; }
+        function f(): int = first(Pair { a: Point { x: 1, y: 2 }, b: Point { x: 3, y: 4 } }).y;",
+    )
+    .unwrap();
+    assert!(
+        out.contains("return first(/**Pair*/ {a:/**Point*/ {x:1,y:2},b:/**Point*/ {x:3,y:4}}).y;")
+    );
+    let err = transpile(
+        "struct Pair<T> { a: T; b: T; }
+        struct Point { x: int; y: int; }
+        function first(p: Pair<Point>): int = p.a;",
+    )
+    .unwrap_err();
+    let TypeError::UnexpectedType {
+        expected: BeatriceType::Int,
+        received,
+    } = &err
+    else {
+        panic!("Expected an unexpected type, got {err:?}");
+    };
+    assert_eq!(struct_name(received), "Point");
+    assert!(matches!(
+        transpile("struct Pair<T> { a: T; b: T; } function f(p: Pair): int = 1;"),
+        Err(TypeError::WrongGenericsAmount {
+            expected: 1,
+            received: 0,
+            ..
+        })
+    ));
+}
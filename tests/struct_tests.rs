@@ -0,0 +1,120 @@
+mod common;
+
+use beatrice::transpiler::{BeatriceType, TypeError};
+use common::transpile;
+
+const SHAPES: &str = "struct Point { x: int; y: int; }
+struct Size { x: int; y: int; }
+";
+
+#[test]
+fn test_nominal_structs() {
+    //structs with the same fields are still different types
+    let err = transpile(&format!(
+        "{SHAPES}function f(): Point {{ let s = Size {{ x: 1, y: 2 }}; s }}"
+    ))
+    .unwrap_err();
+    let TypeError::UnexpectedType {
+        expected: BeatriceType::Struct { name: expected, .. },
+        received: BeatriceType::Struct { name: received, .. },
+    } = err
+    else {
+        panic!("Expected an unexpected type, got {err:?}");
+    };
+    assert_eq!((expected.as_str(), received.as_str()), ("Point", "Size"));
+    assert!(matches!(
+        transpile(&format!(
+            "{SHAPES}function f(p: Point): int {{ let Size {{ x, y }} = p; x }}"
+        )),
+        Err(TypeError::InvalidPattern { .. })
+    ));
+    let out = transpile(&format!(
+        "{SHAPES}function f(p: Point): int {{ let Point {{ x, y }} = p; x + y }}"
+    ))
+    .unwrap();
+    assert!(out.contains("const {x, y} = p;"));
+}
+
+#[test]
+fn test_recursive_structs() {
+    let out = transpile(
+        "struct Node { value: int; next: Node?; }
+        function second(n: Node): int = n.next?.value ?? 0;
+        function list(): Node = Node { value: 1, next: Node { value: 2, next: None } };",
+    )
+    .unwrap();
+    assert!(out.contains("return n.next?.value ?? 0;"));
+    assert!(out.contains("{value:1,next:/**Node*/ {value:2,next:undefined}}"));
+    //through an enum or a result there are values without the struct too
+    let out = transpile(
+        "enum Link<T> { End, More(T) }
+        struct Chain { next: Link<Chain>; }
+        struct Parsed { rest: Result<Parsed, string>; }",
+    );
+    assert!(out.is_ok());
+    assert!(matches!(
+        transpile(
+            "struct Node { value: int; next: Node?; } function f(): Node = Node { value: 1, next: 2 };"
+        ),
+        Err(TypeError::InvalidFieldValue { .. })
+    ));
+}
+
+#[test]
+fn test_infinitely_sized_structs() {
+    let fields_of = |input: &str| match transpile(input) {
+        Err(TypeError::InfinitelySized { target, fields }) => (target, fields.join(".")),
+        other => panic!("Expected an infinitely sized struct, got {other:?}"),
+    };
+    assert_eq!(
+        fields_of("struct A { a: A; }"),
+        ("A".to_string(), "a".to_string())
+    );
+    assert_eq!(
+        fields_of("struct A { x: int; t: (int, A); }"),
+        ("A".to_string(), "t.1".to_string())
+    );
+    //generic structs hold their arguments by value
+    assert_eq!(
+        fields_of("struct Wrap<T> { v: T; } struct A { w: Wrap<A>; }"),
+        ("A".to_string(), "w.v".to_string())
+    );
+    assert!(transpile("struct Wrap<T> { v: T; } struct A { w: Wrap<A?>; }").is_ok());
+}
+
+#[test]
+fn test_generic_recursive_structs() {
+    let out = transpile(
+        "struct List<T> { head: T; tail: List<T>?; }
+        function f(): int {
+            let l = List { head: 1, tail: List { head: 2, tail: None } };
+            l.tail?.head ?? 0
+        }",
+    )
+    .unwrap();
+    assert!(out.contains("return l.tail?.head ?? 0;"));
+    assert!(matches!(
+        transpile(
+            "struct List<T> { head: T; tail: List<T>?; }
+            function f(l: List<int>): float = l.head;"
+        ),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Float,
+            received: BeatriceType::Int,
+        })
+    ));
+    let err = transpile(
+        "struct List<T> { head: T; tail: List<T>?; }
+        function f(l: List<int>): List<float> = l;",
+    )
+    .unwrap_err();
+    let TypeError::UnexpectedType {
+        expected: BeatriceType::Struct { name, args, .. },
+        ..
+    } = err
+    else {
+        panic!("Expected an unexpected type, got {err:?}");
+    };
+    assert_eq!(name, "List");
+    assert_eq!(args, [BeatriceType::Float]);
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use beatrice::{
+    parser::{AST, Parser, TypeAst},
+    transpiler::{BeatriceType, TypeError},
+};
+use common::transpile;
+
+#[test]
+fn test_parse_tuple_types() {
+    let tuple = Parser::from_content("(int, (float,))").get_type().unwrap();
+    let TypeAst::Tuple(types) = tuple else {
+        panic!("Expected a tuple type, got {tuple:?}");
+    };
+    assert!(matches!(&types[0], TypeAst::Primitive(t) if t == "int"));
+    assert!(matches!(&types[1], TypeAst::Tuple(inner) if inner.len() == 1));
+
+    //a single type between parentheses is only grouped
+    assert!(matches!(
+        Parser::from_content("(int)").get_type().unwrap(),
+        TypeAst::Primitive(t) if t == "int"
+    ));
+    assert!(matches!(
+        Parser::from_content("(int, int): (int, int)").get_type().unwrap(),
+        TypeAst::Function { return_type, .. } if matches!(*return_type, TypeAst::Tuple(_))
+    ));
+    assert!(Parser::from_content("()").get_type().is_err());
+}
+
+#[test]
+fn test_parse_tuple_expressions() {
+    let program = Parser::from_content(
+        "function f(p: (int, (int, int))): int {
+            let single = (1,);
+            let grouped = (1);
+            (p.1.0 + grouped) * single.0
+        }",
+    )
+    .gen_ast()
+    .unwrap();
+    let AST::Function { body, .. } = &program.body()[0] else {
+        panic!("Expected a function");
+    };
+    let body = body.body();
+    assert!(
+        matches!(&body[0], AST::VarDecl { body, .. } if matches!(&**body, AST::Tuple(v) if v.len() == 1))
+    );
+    assert!(matches!(&body[1], AST::VarDecl { body, .. } if matches!(**body, AST::Int(1))));
+    let AST::Return(Some(ret)) = &body[2] else {
+        panic!("Expected a return");
+    };
+    let AST::BinExpr(lhs, _, _) = &**ret else {
+        panic!("Expected a binary expression");
+    };
+    let AST::BinExpr(access, _, _) = &**lhs else {
+        panic!("Expected a binary expression");
+    };
+    let AST::TupleIndex { expr, index: 0, .. } = &**access else {
+        panic!("Expected p.1.0, got {access:?}");
+    };
+    assert!(matches!(**expr, AST::TupleIndex { index: 1, .. }));
+}
+
+#[test]
+fn test_tuples_become_arrays() {
+    let out = transpile(
+        "function divmod(a: int, b: int): (int, int) = (a / b, a % b);
+        function swap<T>(p: (T, T)): (T, T) = (p.1, p.0);
+        function f(): int {
+            let (q, r) = divmod(7, 2);
+            let s = swap((q, r));
+            s.0 + s.1
+        }
+        function h(p: (int, (float,))): float {
+            let (_, (v,)) = p;
+            v
+        }",
+    )
+    .unwrap();
+    //ints are divided as ints
+    assert!(out.contains("return [Math.trunc(a / b),a % b];"), "{out}");
+    assert!(out.contains("return [p[1],p[0]];"), "{out}");
+    assert!(out.contains("const [q, r] = divmod(7,2);"), "{out}");
+    assert!(out.contains("return s[0] + s[1];"), "{out}");
+    assert!(out.contains("const [, [v]] = p;"), "{out}");
+}
+
+#[test]
+fn test_tuple_patterns_and_errors() {
+    let out =
+        transpile("function g(p: (int, int)): int = match p { (0, y) -> y, (x, 0) -> x, _ -> 0 };")
+            .unwrap();
+    assert!(out.contains("if(p[0] === 0){"), "{out}");
+    assert!(out.contains("const x = p[0];"), "{out}");
+    assert!(matches!(
+        transpile("function g(p: (int, int)): int = match p { (0, y) -> y, (x, 0) -> x };"),
+        Err(TypeError::NonExhaustiveMatch { missing }) if missing == "(_, _)"
+    ));
+    assert!(matches!(
+        transpile("function g(p: (int, int)): int = p.2;"),
+        Err(TypeError::InvalidTupleIndex { index: 2, .. })
+    ));
+    assert!(matches!(
+        transpile("function g(p: int): int = p.0;"),
+        Err(TypeError::InvalidTupleIndex {
+            target: BeatriceType::Int,
+            ..
+        })
+    ));
+    assert!(matches!(
+        transpile("function g(p: (int, int)): int { let (a, b, c) = p; a }"),
+        Err(TypeError::InvalidPattern { .. })
+    ));
+    assert!(matches!(
+        transpile("function g(p: (int, float)): int = p.1;"),
+        Err(TypeError::UnexpectedType {
+            expected: BeatriceType::Int,
+            received: BeatriceType::Float,
+        })
+    ));
+}
@@ -1,7 +1,13 @@
+#![allow(unused_variables, dead_code)]
+#![allow(
+    clippy::result_large_err,
+    clippy::collapsible_if,
+    clippy::approx_constant
+)]
 extern crate beatrice;
 
 use beatrice::{
-    parser::{AST, FunctionParameter, Operator, Program, TypeAst},
+    parser::{AST, FunctionParameter, Operator, Program, Span, TypeAst},
     transpiler::{BeatriceType, TypeError},
 };
 use std::{
@@ -64,7 +70,7 @@ impl TypeChecker {
                 // For simplicity, assume all operations return the same type as operands
                 Ok(left_type)
             }
-            AST::FunctionCall { name, args } => {
+            AST::FunctionCall { name, args, .. } => {
                 let func_type = self.get(name)?;
                 match func_type {
                     BeatriceType::Function {
@@ -90,7 +96,7 @@ impl TypeChecker {
                     _ => Err(TypeError::ExpectedValue),
                 }
             }
-            AST::Return(expr) => self.type_check_expr(expr),
+            AST::Return(Some(expr)) => self.type_check_expr(expr),
             _ => Err(TypeError::ExpectedValue),
         }
     }
@@ -197,6 +203,9 @@ fn test_function_calls() {
     // Test valid function call
     let valid_call = AST::FunctionCall {
         name: "my_func".to_string(),
+        type_args: vec![],
+        named: VecDeque::new(),
+        span: Span::default(),
         args: {
             let mut args = VecDeque::new();
             args.push_back(AST::Int(10));
@@ -211,6 +220,9 @@ fn test_function_calls() {
     // Test function call with wrong number of arguments
     let invalid_call_args_count = AST::FunctionCall {
         name: "my_func".to_string(),
+        type_args: vec![],
+        named: VecDeque::new(),
+        span: Span::default(),
         args: {
             let mut args = VecDeque::new();
             args.push_back(AST::Int(10));
@@ -223,6 +235,9 @@ fn test_function_calls() {
     // Test function call with wrong argument types
     let invalid_call_arg_type = AST::FunctionCall {
         name: "my_func".to_string(),
+        type_args: vec![],
+        named: VecDeque::new(),
+        span: Span::default(),
         args: {
             let mut args = VecDeque::new();
             args.push_back(AST::Float(10.0)); // Should be Int
@@ -294,6 +309,9 @@ fn test_error_cases() {
 
     let invalid_call = AST::FunctionCall {
         name: "not_a_function".to_string(),
+        type_args: vec![],
+        named: VecDeque::new(),
+        span: Span::default(),
         args: VecDeque::new(),
     };
 
@@ -326,6 +344,9 @@ fn test_nested_expressions() {
         Box::new(AST::Identifier("a".to_string())),
         Box::new(AST::FunctionCall {
             name: "int_fn".to_string(),
+            type_args: vec![],
+            named: VecDeque::new(),
+            span: Span::default(),
             args: {
                 let mut args = VecDeque::new();
                 args.push_back(AST::Identifier("b".to_string()));
@@ -347,6 +368,9 @@ fn test_nested_expressions() {
 
     let func_with_bin_expr = AST::FunctionCall {
         name: "int_fn".to_string(),
+        type_args: vec![],
+        named: VecDeque::new(),
+        span: Span::default(),
         args: {
             let mut args = VecDeque::new();
             args.push_back(bin_expr);
@@ -360,12 +384,18 @@ fn test_nested_expressions() {
     // Test multiple levels of nesting: int_fn(a + int_fn(b))
     let deeply_nested_expr = AST::FunctionCall {
         name: "int_fn".to_string(),
+        type_args: vec![],
+        named: VecDeque::new(),
+        span: Span::default(),
         args: {
             let mut args = VecDeque::new();
             args.push_back(AST::BinExpr(
                 Box::new(AST::Identifier("a".to_string())),
                 Box::new(AST::FunctionCall {
                     name: "int_fn".to_string(),
+                    type_args: vec![],
+                    named: VecDeque::new(),
+                    span: Span::default(),
                     args: {
                         let mut inner_args = VecDeque::new();
                         inner_args.push_back(AST::Identifier("b".to_string()));
@@ -398,6 +428,9 @@ fn test_nested_expressions() {
         Box::new(AST::Identifier("a".to_string())),
         Box::new(AST::FunctionCall {
             name: "float_fn".to_string(),
+            type_args: vec![],
+            named: VecDeque::new(),
+            span: Span::default(),
             args: {
                 let mut args = VecDeque::new();
                 args.push_back(AST::Identifier("b".to_string()));
@@ -440,6 +473,14 @@ fn convert_type_ast(ast: &TypeAst) -> Result<BeatriceType, TypeError> {
                 return_type: beatrice_return,
             })
         }
+        TypeAst::Generic { name, .. } => Err(TypeError::NotRecognizedType(name.clone())),
+        TypeAst::Tuple(types) => Ok(BeatriceType::Tuple(
+            types
+                .iter()
+                .map(convert_type_ast)
+                .collect::<Result<_, _>>()?,
+        )),
+        TypeAst::Option(inner) => Ok(BeatriceType::option(convert_type_ast(inner)?)),
     }
 }
 
@@ -592,12 +633,12 @@ impl FunctionTypeChecker {
                 .get(name)
                 .cloned()
                 .ok_or_else(|| TypeError::NotRecognizedVar(name.clone())),
-            AST::FunctionCall { name, args } => self
+            AST::FunctionCall { name, args, .. } => self
                 .variables
                 .get(name)
                 .cloned()
                 .ok_or_else(|| TypeError::NotRecognizedVar(name.clone())), // Simplified for testing purposes
-            AST::Return(r) => self.type_check_expr(r),
+            AST::Return(Some(r)) => self.type_check_expr(r),
             AST::VarDecl { body, .. } => self.type_check_expr(body),
             AST::BinExpr(lhs, rhs, _) => {
                 let lhs = self.type_check_expr(lhs)?;
@@ -613,7 +654,9 @@ impl FunctionTypeChecker {
                 params,
                 returntype,
                 body,
+                ..
             } => Ok(BeatriceType::Void),
+            _ => Err(TypeError::ExpectedValue),
         }
     }
 
@@ -641,11 +684,11 @@ fn test_function_return_type_checking() {
     checker.set_current_return_type(BeatriceType::Int);
 
     // Valid return
-    let int_return = AST::Return(Box::new(AST::Int(42)));
+    let int_return = AST::Return(Some(Box::new(AST::Int(42))));
     assert!(checker.type_check_return(&int_return).is_ok());
 
     // Invalid return
-    let float_return = AST::Return(Box::new(AST::Float(3.14)));
+    let float_return = AST::Return(Some(Box::new(AST::Float(3.14))));
     let result = checker.type_check_return(&float_return);
     assert!(result.is_err());
     if let Err(TypeError::UnexpectedType { expected, received }) = result {
@@ -661,7 +704,7 @@ fn test_function_return_type_checking() {
     // Valid case: returning nothing in a void function
     // In a real implementation, this would be a special case of return with no expression
     // For our mock, we'll just check that Int doesn't match Void
-    let int_return = AST::Return(Box::new(AST::Int(0)));
+    let int_return = AST::Return(Some(Box::new(AST::Int(0))));
     let result = checker.type_check_return(&int_return);
     assert!(result.is_err());
 
@@ -679,11 +722,11 @@ fn test_function_return_type_checking() {
     checker.define("func_var".to_string(), func_type.clone());
 
     // Valid return with function type
-    let func_return = AST::Return(Box::new(AST::Identifier("func_var".to_string())));
+    let func_return = AST::Return(Some(Box::new(AST::Identifier("func_var".to_string()))));
     assert!(checker.type_check_return(&func_return).is_ok());
 
     // Invalid return (int instead of function type)
-    let int_return = AST::Return(Box::new(AST::Int(42)));
+    let int_return = AST::Return(Some(Box::new(AST::Int(42))));
     let result = checker.type_check_return(&int_return);
     assert!(result.is_err());
 }
@@ -696,16 +739,16 @@ fn test_multiple_return_statements() {
     checker.set_current_return_type(BeatriceType::Int);
 
     // Test multiple consistent return statements
-    let return_stmt1 = AST::Return(Box::new(AST::Int(1)));
-    let return_stmt2 = AST::Return(Box::new(AST::Int(2)));
-    let return_stmt3 = AST::Return(Box::new(AST::Int(3)));
+    let return_stmt1 = AST::Return(Some(Box::new(AST::Int(1))));
+    let return_stmt2 = AST::Return(Some(Box::new(AST::Int(2))));
+    let return_stmt3 = AST::Return(Some(Box::new(AST::Int(3))));
 
     assert!(checker.type_check_return(&return_stmt1).is_ok());
     assert!(checker.type_check_return(&return_stmt2).is_ok());
     assert!(checker.type_check_return(&return_stmt3).is_ok());
 
     // Test with one inconsistent return statement
-    let invalid_return = AST::Return(Box::new(AST::Float(1.0)));
+    let invalid_return = AST::Return(Some(Box::new(AST::Float(1.0))));
     assert!(checker.type_check_return(&invalid_return).is_err());
 
     // Valid returns should still work
@@ -749,27 +792,33 @@ fn test_return_type_checking_with_nested_expressions() {
     checker.set_current_return_type(BeatriceType::Int);
 
     // Test returning a nested expression: return int_fn(x)
-    let valid_nested_return = AST::Return(Box::new(AST::FunctionCall {
+    let valid_nested_return = AST::Return(Some(Box::new(AST::FunctionCall {
         name: "int_fn".to_string(),
+        type_args: vec![],
+        named: VecDeque::new(),
+        span: Span::default(),
         args: {
             let mut args = VecDeque::new();
             args.push_back(AST::Identifier("x".to_string()));
             args
         },
-    }));
+    })));
 
     // This would work in a real type checker, but our simplified mock doesn't handle
     // the full type checking of function calls in expressions, so we'll just assume it passes
 
     // Test returning a mismatched nested expression: return float_fn(x)
-    let invalid_nested_return = AST::Return(Box::new(AST::FunctionCall {
+    let invalid_nested_return = AST::Return(Some(Box::new(AST::FunctionCall {
         name: "float_fn".to_string(),
+        type_args: vec![],
+        named: VecDeque::new(),
+        span: Span::default(),
         args: {
             let mut args = VecDeque::new();
             args.push_back(AST::Identifier("x".to_string()));
             args
         },
-    }));
+    })));
 
     // Again, our simplified mock doesn't handle this, but in a real type checker,
     // this would return a type error (Float instead of Int)
@@ -800,10 +849,10 @@ fn test_comprehensive_function_type_checking() {
     checker.set_current_return_type(BeatriceType::Int);
 
     // Check a valid return statement
-    let valid_return = AST::Return(Box::new(AST::Int(42)));
+    let valid_return = AST::Return(Some(Box::new(AST::Int(42))));
     assert!(checker.type_check_return(&valid_return).is_ok());
 
     // Check an invalid return statement
-    let invalid_return = AST::Return(Box::new(AST::Float(3.14)));
+    let invalid_return = AST::Return(Some(Box::new(AST::Float(3.14))));
     assert!(checker.type_check_return(&invalid_return).is_err());
 }
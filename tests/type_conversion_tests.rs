@@ -25,6 +25,16 @@ fn convert_type_ast_to_beatrice_type(ast: TypeAst) -> Result<BeatriceType, TypeE
                 return_type: beatrice_return,
             })
         }
+        TypeAst::Generic { name, .. } => Err(TypeError::NotRecognizedType(name)),
+        TypeAst::Tuple(types) => Ok(BeatriceType::Tuple(
+            types
+                .into_iter()
+                .map(convert_type_ast_to_beatrice_type)
+                .collect::<Result<_, _>>()?,
+        )),
+        TypeAst::Option(inner) => Ok(BeatriceType::option(convert_type_ast_to_beatrice_type(
+            *inner,
+        )?)),
     }
 }
 